@@ -0,0 +1,576 @@
+use std::{cmp::Ordering, fmt};
+
+const LIMB_BITS: u32 = 32;
+// The largest power of 10 that fits in a limb, used for printing in decimal.
+const DECIMAL_CHUNK: u32 = 1_000_000_000;
+const DECIMAL_CHUNK_DIGITS: usize = 9;
+
+// An integer of any size. The magnitude is stored in 32-bit limbs, the least
+// significant one first, without leading zero limbs (so zero has no limbs and
+// every integer has exactly one representation).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigInteger {
+    negative: bool,
+    magnitude: Vec<u32>,
+}
+
+impl BigInteger {
+    pub fn zero() -> BigInteger {
+        BigInteger {
+            negative: false,
+            magnitude: Vec::new(),
+        }
+    }
+
+    fn new(negative: bool, magnitude: Vec<u32>) -> BigInteger {
+        let magnitude = trim(magnitude);
+        BigInteger {
+            negative: negative && !magnitude.is_empty(),
+            magnitude,
+        }
+    }
+
+    // Parses digits (without a sign) in the given radix.
+    pub fn parse(digits: &str, radix: u32, negative: bool) -> Option<BigInteger> {
+        if digits.is_empty() {
+            return None;
+        }
+        let mut magnitude = Vec::new();
+        for char in digits.chars() {
+            let digit = char.to_digit(radix)?;
+            multiply_add_small(&mut magnitude, radix, digit);
+        }
+        Some(BigInteger::new(negative, magnitude))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.magnitude.first().is_none_or(|limb| limb % 2 == 0)
+    }
+
+    pub fn negate(&self) -> BigInteger {
+        BigInteger::new(!self.negative, self.magnitude.clone())
+    }
+
+    pub fn abs(&self) -> BigInteger {
+        BigInteger::new(false, self.magnitude.clone())
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.magnitude.len() > 2 {
+            return None;
+        }
+        let magnitude = self
+            .magnitude
+            .iter()
+            .rev()
+            .fold(0u64, |value, &limb| (value << LIMB_BITS) | limb as u64);
+        if self.negative {
+            0i64.checked_sub_unsigned(magnitude)
+        } else {
+            i64::try_from(magnitude).ok()
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self.magnitude.iter().rev().fold(0.0, |value, &limb| {
+            value * 2f64.powi(LIMB_BITS as i32) + limb as f64
+        });
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    pub fn add(&self, other: &BigInteger) -> BigInteger {
+        if self.negative == other.negative {
+            return BigInteger::new(
+                self.negative,
+                add_magnitudes(&self.magnitude, &other.magnitude),
+            );
+        }
+        // The sign of the result is the sign of the operand with the bigger
+        // magnitude.
+        match compare_magnitudes(&self.magnitude, &other.magnitude) {
+            Ordering::Less => BigInteger::new(
+                other.negative,
+                subtract_magnitudes(&other.magnitude, &self.magnitude),
+            ),
+            _ => BigInteger::new(
+                self.negative,
+                subtract_magnitudes(&self.magnitude, &other.magnitude),
+            ),
+        }
+    }
+
+    pub fn subtract(&self, other: &BigInteger) -> BigInteger {
+        self.add(&other.negate())
+    }
+
+    pub fn multiply(&self, other: &BigInteger) -> BigInteger {
+        BigInteger::new(
+            self.negative != other.negative,
+            multiply_magnitudes(&self.magnitude, &other.magnitude),
+        )
+    }
+
+    // The quotient rounded towards zero and the remainder with the sign of the
+    // dividend. Returns None when dividing by zero.
+    pub fn divide_with_remainder(&self, other: &BigInteger) -> Option<(BigInteger, BigInteger)> {
+        if other.is_zero() {
+            return None;
+        }
+        let (quotient, remainder) = divide_magnitudes(&self.magnitude, &other.magnitude);
+        Some((
+            BigInteger::new(self.negative != other.negative, quotient),
+            BigInteger::new(self.negative, remainder),
+        ))
+    }
+
+    pub fn pow(&self, mut exponent: u32) -> BigInteger {
+        let mut result = BigInteger::from(1);
+        let mut base = self.clone();
+        while exponent > 0 {
+            if exponent % 2 == 1 {
+                result = result.multiply(&base);
+            }
+            exponent /= 2;
+            if exponent > 0 {
+                base = base.multiply(&base);
+            }
+        }
+        result
+    }
+
+    // Digits in the given radix (at most 36), like `-ff` for -255 in radix 16.
+    pub fn to_string_radix(&self, radix: u32) -> String {
+        if radix == 10 {
+            return self.to_string();
+        }
+        let mut digits = Vec::new();
+        let mut magnitude = self.magnitude.clone();
+        while !magnitude.is_empty() {
+            let (quotient, remainder) = divide_magnitude_by_small(&magnitude, radix);
+            digits.push(char::from_digit(remainder, radix).unwrap());
+            magnitude = quotient;
+        }
+        if digits.is_empty() {
+            digits.push('0');
+        }
+        if self.negative {
+            digits.push('-');
+        }
+        digits.iter().rev().collect()
+    }
+
+    // The square root rounded down. The integer must not be negative.
+    pub fn sqrt(&self) -> BigInteger {
+        if self.is_zero() {
+            return BigInteger::zero();
+        }
+        // Newton's method, starting from a power of two that is not smaller
+        // than the root, so that the guesses only decrease.
+        let top_bits = LIMB_BITS - self.magnitude.last().unwrap().leading_zeros();
+        let bits = (self.magnitude.len() as u32 - 1) * LIMB_BITS + top_bits;
+        let mut root = BigInteger::from(2).pow(bits.div_ceil(2));
+        let two = BigInteger::from(2);
+        loop {
+            let (quotient, _) = self.divide_with_remainder(&root).unwrap();
+            let (next, _) = root.add(&quotient).divide_with_remainder(&two).unwrap();
+            if next >= root {
+                return root;
+            }
+            root = next;
+        }
+    }
+
+    // The greatest common divisor, which is never negative.
+    pub fn gcd(&self, other: &BigInteger) -> BigInteger {
+        let mut a = self.abs();
+        let mut b = other.abs();
+        while !b.is_zero() {
+            let (_, remainder) = divide_magnitudes(&a.magnitude, &b.magnitude);
+            a = b;
+            b = BigInteger::new(false, remainder);
+        }
+        a
+    }
+}
+
+impl From<i64> for BigInteger {
+    fn from(value: i64) -> Self {
+        let magnitude = value.unsigned_abs();
+        BigInteger::new(
+            value < 0,
+            vec![magnitude as u32, (magnitude >> LIMB_BITS) as u32],
+        )
+    }
+}
+
+impl PartialOrd for BigInteger {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInteger {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitudes(&self.magnitude, &other.magnitude),
+            (true, true) => compare_magnitudes(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl fmt::Display for BigInteger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        // Chunks of 9 decimal digits, the least significant one first.
+        let mut chunks = Vec::new();
+        let mut magnitude = self.magnitude.clone();
+        while !magnitude.is_empty() {
+            let (quotient, remainder) = divide_magnitude_by_small(&magnitude, DECIMAL_CHUNK);
+            chunks.push(remainder);
+            magnitude = quotient;
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+        let mut chunks = chunks.iter().rev();
+        if let Some(first) = chunks.next() {
+            write!(f, "{}", first)?;
+        }
+        for chunk in chunks {
+            write!(f, "{:0width$}", chunk, width = DECIMAL_CHUNK_DIGITS)?;
+        }
+        Ok(())
+    }
+}
+
+fn trim(mut magnitude: Vec<u32>) -> Vec<u32> {
+    while magnitude.last() == Some(&0) {
+        magnitude.pop();
+    }
+    magnitude
+}
+
+fn compare_magnitudes(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (longer, shorter) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut sum = Vec::with_capacity(longer.len() + 1);
+    let mut carry = 0u64;
+    for (index, &limb) in longer.iter().enumerate() {
+        let total = limb as u64 + *shorter.get(index).unwrap_or(&0) as u64 + carry;
+        sum.push(total as u32);
+        carry = total >> LIMB_BITS;
+    }
+    if carry > 0 {
+        sum.push(carry as u32);
+    }
+    sum
+}
+
+// `a` must not be smaller than `b`.
+fn subtract_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut difference = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (index, &limb) in a.iter().enumerate() {
+        let mut total = limb as i64 - *b.get(index).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if total < 0 {
+            total += 1 << LIMB_BITS;
+            borrow = 1;
+        }
+        difference.push(total as u32);
+    }
+    trim(difference)
+}
+
+fn multiply_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut product = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let total = x as u64 * y as u64 + product[i + j] as u64 + carry;
+            product[i + j] = total as u32;
+            carry = total >> LIMB_BITS;
+        }
+        product[i + b.len()] = carry as u32;
+    }
+    trim(product)
+}
+
+// magnitude = magnitude * factor + addend
+fn multiply_add_small(magnitude: &mut Vec<u32>, factor: u32, addend: u32) {
+    let mut carry = addend as u64;
+    for limb in magnitude.iter_mut() {
+        let total = *limb as u64 * factor as u64 + carry;
+        *limb = total as u32;
+        carry = total >> LIMB_BITS;
+    }
+    if carry > 0 {
+        magnitude.push(carry as u32);
+    }
+}
+
+fn divide_magnitude_by_small(magnitude: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0u32; magnitude.len()];
+    let mut remainder = 0u64;
+    for (index, &limb) in magnitude.iter().enumerate().rev() {
+        let current = (remainder << LIMB_BITS) | limb as u64;
+        quotient[index] = (current / divisor as u64) as u32;
+        remainder = current % divisor as u64;
+    }
+    (trim(quotient), remainder as u32)
+}
+
+// Long division (algorithm D from The Art of Computer Programming, volume 2).
+// `divisor` must not be zero.
+fn divide_magnitudes(dividend: &[u32], divisor: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if compare_magnitudes(dividend, divisor) == Ordering::Less {
+        return (Vec::new(), dividend.to_vec());
+    }
+    if divisor.len() == 1 {
+        let (quotient, remainder) = divide_magnitude_by_small(dividend, divisor[0]);
+        return (quotient, trim(vec![remainder]));
+    }
+
+    // Shifting both numbers so that the top bit of the divisor is set makes
+    // the estimated quotient digits off by at most 2.
+    let shift = divisor[divisor.len() - 1].leading_zeros();
+    let divisor = shift_left(divisor, shift);
+    let mut remainder = shift_left(dividend, shift);
+    remainder.resize(dividend.len() + 1, 0);
+
+    let n = divisor.len();
+    let m = dividend.len() - n;
+    let base = 1u64 << LIMB_BITS;
+    let mut quotient = vec![0u32; m + 1];
+
+    for j in (0..=m).rev() {
+        let top = ((remainder[j + n] as u64) << LIMB_BITS) | remainder[j + n - 1] as u64;
+        let mut estimate = top / divisor[n - 1] as u64;
+        let mut estimate_remainder = top % divisor[n - 1] as u64;
+        while estimate >= base
+            || estimate as u128 * divisor[n - 2] as u128
+                > ((estimate_remainder as u128) << LIMB_BITS) | remainder[j + n - 2] as u128
+        {
+            estimate -= 1;
+            estimate_remainder += divisor[n - 1] as u64;
+            if estimate_remainder >= base {
+                break;
+            }
+        }
+
+        // remainder -= estimate * divisor, shifted by j limbs.
+        let mut borrow = 0i64;
+        let mut carry = 0u64;
+        for i in 0..n {
+            let product = estimate * divisor[i] as u64 + carry;
+            carry = product >> LIMB_BITS;
+            let difference = remainder[i + j] as i64 - borrow - (product & (base - 1)) as i64;
+            remainder[i + j] = difference as u32;
+            borrow = (difference < 0) as i64;
+        }
+        let difference = remainder[j + n] as i64 - borrow - carry as i64;
+        remainder[j + n] = difference as u32;
+
+        // The estimate was one too big - add the divisor back.
+        if difference < 0 {
+            estimate -= 1;
+            let mut carry = 0u64;
+            for i in 0..n {
+                let total = remainder[i + j] as u64 + divisor[i] as u64 + carry;
+                remainder[i + j] = total as u32;
+                carry = total >> LIMB_BITS;
+            }
+            remainder[j + n] = remainder[j + n].wrapping_add(carry as u32);
+        }
+        quotient[j] = estimate as u32;
+    }
+
+    remainder.truncate(n);
+    (trim(quotient), shift_right(&remainder, shift))
+}
+
+fn shift_left(magnitude: &[u32], shift: u32) -> Vec<u32> {
+    if shift == 0 {
+        return magnitude.to_vec();
+    }
+    let mut shifted = Vec::with_capacity(magnitude.len() + 1);
+    let mut carry = 0u32;
+    for &limb in magnitude {
+        shifted.push((limb << shift) | carry);
+        carry = limb >> (LIMB_BITS - shift);
+    }
+    if carry > 0 {
+        shifted.push(carry);
+    }
+    shifted
+}
+
+fn shift_right(magnitude: &[u32], shift: u32) -> Vec<u32> {
+    if shift == 0 {
+        return trim(magnitude.to_vec());
+    }
+    let mut shifted = vec![0u32; magnitude.len()];
+    for index in 0..magnitude.len() {
+        let high = magnitude
+            .get(index + 1)
+            .map_or(0, |limb| limb << (LIMB_BITS - shift));
+        shifted[index] = (magnitude[index] >> shift) | high;
+    }
+    trim(shifted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(digits: &str) -> BigInteger {
+        match digits.strip_prefix('-') {
+            Some(digits) => BigInteger::parse(digits, 10, true).unwrap(),
+            None => BigInteger::parse(digits, 10, false).unwrap(),
+        }
+    }
+
+    #[test]
+    fn should_print_parsed_integers() {
+        for digits in [
+            "0",
+            "7",
+            "-42",
+            "4294967296",
+            "1000000000000000000000000000001",
+            "-1606938044258990275541962092341162602522202993782792835301376",
+        ] {
+            assert_eq!(big(digits).to_string(), digits);
+        }
+        assert_eq!(BigInteger::parse("ff", 16, false), Some(big("255")));
+        assert_eq!(BigInteger::parse("12a", 10, false), None);
+    }
+
+    #[test]
+    fn should_print_in_other_radices() {
+        assert_eq!(big("-255").to_string_radix(16), "-ff");
+        assert_eq!(big("10").to_string_radix(2), "1010");
+        assert_eq!(big("0").to_string_radix(8), "0");
+        assert_eq!(
+            big("18446744073709551616").to_string_radix(16),
+            "10000000000000000"
+        );
+    }
+
+    #[test]
+    fn should_add_and_subtract() {
+        let a = big("18446744073709551615");
+        let b = big("1");
+
+        assert_eq!(a.add(&b), big("18446744073709551616"));
+        assert_eq!(b.subtract(&a), big("-18446744073709551614"));
+        assert_eq!(a.subtract(&a), BigInteger::zero());
+        assert_eq!(a.negate().add(&b), big("-18446744073709551614"));
+    }
+
+    #[test]
+    fn should_multiply() {
+        let a = big("123456789012345678901234567890");
+
+        assert_eq!(
+            a.multiply(&a.negate()),
+            big("-15241578753238836750495351562536198787501905199875019052100")
+        );
+        assert_eq!(a.multiply(&BigInteger::zero()), BigInteger::zero());
+    }
+
+    #[test]
+    fn should_divide_with_remainder() {
+        let dividend = big("-15241578753238836750495351562536198787501905199875019052199");
+        let divisor = big("123456789012345678901234567890");
+
+        let (quotient, remainder) = dividend.divide_with_remainder(&divisor).unwrap();
+
+        assert_eq!(quotient, big("-123456789012345678901234567890"));
+        assert_eq!(remainder, big("-99"));
+        assert_eq!(dividend.divide_with_remainder(&BigInteger::zero()), None);
+    }
+
+    #[test]
+    fn should_divide_when_estimated_quotient_digits_are_too_big() {
+        // Cases that need the correction steps of the long division.
+        let dividend = BigInteger::new(false, vec![0, 0, 0x8000_0000, 0x7fff_ffff]);
+        let divisor = BigInteger::new(false, vec![1, 0, 0x8000_0000]);
+
+        let (quotient, remainder) = dividend.divide_with_remainder(&divisor).unwrap();
+
+        assert_eq!(quotient.multiply(&divisor).add(&remainder), dividend);
+        assert!(remainder < divisor);
+    }
+
+    #[test]
+    fn should_raise_to_a_power() {
+        assert_eq!(
+            big("2").pow(200),
+            big("1606938044258990275541962092341162602522202993782792835301376")
+        );
+        assert_eq!(big("-3").pow(3), big("-27"));
+        assert_eq!(BigInteger::zero().pow(0), big("1"));
+    }
+
+    #[test]
+    fn should_find_square_roots_rounded_down() {
+        assert_eq!(big("0").sqrt(), big("0"));
+        assert_eq!(big("15").sqrt(), big("3"));
+        assert_eq!(big("16").sqrt(), big("4"));
+        assert_eq!(
+            big("1606938044258990275541962092341162602522202993782792835301376").sqrt(),
+            big("1267650600228229401496703205376")
+        );
+        assert_eq!(big("4294967296").sqrt(), big("65536"));
+    }
+
+    #[test]
+    fn should_find_greatest_common_divisor() {
+        let a = big("-1606938044258990275541962092341162602522202993782792835301376");
+        let b = big("6");
+
+        assert_eq!(a.gcd(&b), big("2"));
+        assert_eq!(BigInteger::zero().gcd(&b), big("6"));
+    }
+
+    #[test]
+    fn should_convert_to_i64_if_it_fits() {
+        assert_eq!(big("-9223372036854775808").to_i64(), Some(i64::MIN));
+        assert_eq!(big("9223372036854775807").to_i64(), Some(i64::MAX));
+        assert_eq!(big("9223372036854775808").to_i64(), None);
+        assert_eq!(BigInteger::from(i64::MIN), big("-9223372036854775808"));
+    }
+
+    #[test]
+    fn should_compare() {
+        assert!(big("-5") < big("3"));
+        assert!(big("-50000000000000000000") < big("-5"));
+        assert!(big("50000000000000000000") > big("5"));
+    }
+}
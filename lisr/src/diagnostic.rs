@@ -0,0 +1,155 @@
+use std::fmt;
+
+use crate::lisr_error::LisrError;
+
+// Renders an error the way compilers do - the kind of the error, where it
+// happened, the offending line with the span underlined and a hint on how to
+// fix it:
+//
+// parse error: malformed if expression
+//  --> script.lisr:2:1
+//   |
+// 2 | (if x)
+//   | ^^^^^^
+//   = help: if requires predicate, consequent and alternative; found 1 form
+pub struct Diagnostic<'a> {
+    error: &'a LisrError<'a>,
+    file: &'a str,
+    source: &'a str,
+}
+
+impl<'a> Diagnostic<'a> {
+    pub fn new(error: &'a LisrError<'a>, file: &'a str, source: &'a str) -> Diagnostic<'a> {
+        Diagnostic {
+            error,
+            file,
+            source,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let span = self.error.span();
+        let line_number = span.start.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+
+        writeln!(f, "{} error: {}", self.error.kind(), self.error.message())?;
+        writeln!(f, "{}--> {}:{}", gutter, self.file, span.start)?;
+
+        // Spans that don't point into the source (for example, the ones of
+        // expressions created by the interpreter itself) don't get a snippet.
+        let line = span
+            .start
+            .line
+            .checked_sub(1)
+            .and_then(|index| self.source.lines().nth(index));
+        if let Some(line) = line {
+            let line_length = line.chars().count();
+            let start = span.start.column.saturating_sub(1).min(line_length);
+            let end = if span.end.line == span.start.line {
+                span.end.column.saturating_sub(1).min(line_length)
+            } else {
+                // Only the first line of a multi-line span is underlined.
+                line_length
+            };
+            // Keep tabs, so that the carets line up with the source.
+            let padding: String = line
+                .chars()
+                .take(start)
+                .map(|char| if char == '\t' { '\t' } else { ' ' })
+                .collect();
+            let carets = "^".repeat(end.saturating_sub(start).max(1));
+
+            writeln!(f, "{} |", gutter)?;
+            writeln!(f, "{} | {}", line_number, line)?;
+            writeln!(f, "{} | {}{}", gutter, padding, carets)?;
+        }
+
+        if let Some(help) = self.error.help() {
+            writeln!(f, "{} = help: {}", gutter, help)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpret::interpret;
+
+    fn render(source: &str) -> String {
+        let error = interpret(source).unwrap_err();
+        Diagnostic::new(&error, "test.lisr", source).to_string()
+    }
+
+    #[test]
+    fn should_underline_the_span_of_the_error() {
+        let source = "(define x 1)\n(if x)\n";
+
+        assert_eq!(
+            render(source),
+            "parse error: malformed if expression\n \
+             --> test.lisr:2:1\n  \
+             |\n\
+             2 | (if x)\n  \
+             | ^^^^^^\n  \
+             = help: if requires a predicate, a consequent and an optional alternative; found 1 form\n"
+        );
+    }
+
+    #[test]
+    fn should_point_at_an_undefined_identifier() {
+        let source = "(define (area r)\n    (* pi r r))\n(area 2)";
+
+        assert_eq!(
+            render(source),
+            "evaluation error: undefined identifier\n \
+             --> test.lisr:2:8\n  \
+             |\n\
+             2 |     (* pi r r))\n  \
+             |        ^^\n  \
+             = help: define it before it is used, like (define x 42)\n"
+        );
+    }
+
+    #[test]
+    fn should_underline_the_first_line_of_a_multiline_span() {
+        let source = "(+ 1\n   2";
+
+        assert_eq!(
+            render(source),
+            "parse error: unclosed list\n \
+             --> test.lisr:1:1\n  \
+             |\n\
+             1 | (+ 1\n  \
+             | ^\n  \
+             = help: this `(` does not have a matching `)`\n"
+        );
+    }
+
+    #[test]
+    fn should_render_errors_without_help() {
+        let source = "((lambda (x) x))";
+
+        assert_eq!(
+            render(source),
+            "evaluation error: function called with a wrong number of arguments\n \
+             --> test.lisr:1:1\n  \
+             |\n\
+             1 | ((lambda (x) x))\n  \
+             | ^^^^^^^^^^^^^^^^\n"
+        );
+    }
+
+    #[test]
+    fn should_display_errors_without_the_source() {
+        let error = interpret("\n  (car 1 2 3)").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "evaluation error at 2:3: 'car' function requires one 'cons' argument"
+        );
+    }
+}
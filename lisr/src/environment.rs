@@ -1,62 +1,233 @@
 use crate::{
     evaluate::LisrEvaluationError,
-    expression::{Expression, Identifier},
+    expression::{Expression, Identifier, Parameter},
+    symbol::AddressHasher,
 };
-use std::collections::{hash_map::IntoIter, HashMap};
+use std::{cell::RefCell, collections::HashMap, fmt, hash::BuildHasherDefault, rc::Rc};
 
-type Frame = HashMap<Identifier, Expression>;
-
-// Environment consists of one frame only, because functions are implemented as
-// one-block closures. Every compound procedure has it's own copy of the
-// environment. Not the most elegant solution, but at least it's simple.
-#[derive(Debug, Clone, PartialEq)]
+// Environment is a chain of frames. Looking up a variable starts in the
+// innermost frame and continues through the enclosing ones. Environments are
+// cheap to clone and the clones share their frames, so a procedure that
+// modifies a variable of its enclosing environment modifies it for everyone
+// who can see it.
+#[derive(Clone)]
 pub struct Environment {
-    frame: Frame,
+    scope: Rc<Scope>,
+}
+
+struct Scope {
+    frame: RefCell<Frame>,
+    enclosing: Option<Environment>,
+}
+
+// A procedure defined in a frame (like the loop of a named let or a procedure
+// defined inside of another one) references the frame, which in turn would
+// reference the procedure. Such a cycle is never freed, so the frame keeps the
+// procedure without its environment and puts the frame back when the procedure
+// is looked up.
+enum Value {
+    Expression(Expression),
+    OwnProcedure {
+        parameters: Rc<[Parameter]>,
+        rest_parameter: Option<Parameter>,
+        body: Rc<Expression>,
+    },
+}
+
+// Most frames bind a few variables, which are found faster by comparing them
+// one by one than by hashing them. Frames with more variables (like the global
+// one) switch to a hash map.
+enum Frame {
+    Small(Vec<(Identifier, Value)>),
+    Large(HashMap<Identifier, Value, BuildHasherDefault<AddressHasher>>),
+}
+
+const SMALL_FRAME_SIZE: usize = 8;
+
+impl Frame {
+    fn get_mut(&mut self, variable: &Identifier) -> Option<&mut Value> {
+        match self {
+            Frame::Small(variables) => variables
+                .iter_mut()
+                .find(|(name, _)| name == variable)
+                .map(|(_, value)| value),
+            Frame::Large(variables) => variables.get_mut(variable),
+        }
+    }
+
+    fn get(&self, variable: &Identifier) -> Option<&Value> {
+        match self {
+            Frame::Small(variables) => variables
+                .iter()
+                .find(|(name, _)| name == variable)
+                .map(|(_, value)| value),
+            Frame::Large(variables) => variables.get(variable),
+        }
+    }
+
+    fn insert(&mut self, variable: Identifier, value: Value) {
+        if let Some(definition) = self.get_mut(&variable) {
+            *definition = value;
+            return;
+        }
+        match self {
+            Frame::Small(variables) if variables.len() < SMALL_FRAME_SIZE => {
+                variables.push((variable, value))
+            }
+            Frame::Small(variables) => {
+                let mut large: HashMap<_, _, _> = variables.drain(..).collect();
+                large.insert(variable, value);
+                *self = Frame::Large(large);
+            }
+            Frame::Large(variables) => {
+                variables.insert(variable, value);
+            }
+        }
+    }
 }
 
 impl Environment {
     pub fn new() -> Environment {
         Environment {
-            frame: HashMap::new(),
+            scope: Rc::new(Scope {
+                frame: RefCell::new(Frame::Small(Vec::new())),
+                enclosing: None,
+            }),
+        }
+    }
+
+    // Creates a new, empty frame on top of this environment.
+    pub fn extend(&self) -> Environment {
+        Environment {
+            scope: Rc::new(Scope {
+                frame: RefCell::new(Frame::Small(Vec::new())),
+                enclosing: Some(self.clone()),
+            }),
         }
     }
 
-    pub fn define_variable(&mut self, variable: &Identifier, value: &Expression) {
-        self.frame.insert(variable.clone(), value.clone());
+    // Defines a variable in the innermost frame, shadowing variables with the
+    // same name from the enclosing frames.
+    pub fn define_variable(&self, variable: &Identifier, value: &Expression) {
+        self.scope
+            .frame
+            .borrow_mut()
+            .insert(variable.clone(), self.value(value));
+    }
+
+    // Changes the value of a variable in the frame it was defined in.
+    pub fn set_variable_value(
+        &self,
+        variable: &Identifier,
+        value: &Expression,
+    ) -> Result<(), LisrEvaluationError> {
+        let mut environment = Some(self);
+        while let Some(current) = environment {
+            if let Some(definition) = current.scope.frame.borrow_mut().get_mut(variable) {
+                *definition = current.value(value);
+                return Ok(());
+            }
+            environment = current.scope.enclosing.as_ref();
+        }
+        Err(LisrEvaluationError::UndefinedIdentifier)
     }
 
     pub fn lookup_value(&self, variable: &Identifier) -> Result<Expression, LisrEvaluationError> {
-        if let Some(definition) = self.frame.get(variable) {
-            return Ok(definition.clone());
+        let mut environment = Some(self);
+        while let Some(current) = environment {
+            match current.scope.frame.borrow().get(variable) {
+                Some(Value::Expression(expression)) => return Ok(expression.clone()),
+                Some(Value::OwnProcedure {
+                    parameters,
+                    rest_parameter,
+                    body,
+                }) => {
+                    return Ok(Expression::CompoundProcedure {
+                        parameters: Rc::clone(parameters),
+                        rest_parameter: rest_parameter.clone(),
+                        body: Rc::clone(body),
+                        environment: current.clone(),
+                    })
+                }
+                None => environment = current.scope.enclosing.as_ref(),
+            }
         }
         Err(LisrEvaluationError::UndefinedIdentifier)
     }
 
-    pub fn into_iter(self) -> IntoIter<Identifier, Expression> {
-        self.frame.into_iter()
+    // Turns the value into the way it is kept in the innermost frame.
+    fn value(&self, value: &Expression) -> Value {
+        match value {
+            Expression::CompoundProcedure {
+                parameters,
+                rest_parameter,
+                body,
+                environment,
+            } if environment == self => Value::OwnProcedure {
+                parameters: Rc::clone(parameters),
+                rest_parameter: rest_parameter.clone(),
+                body: Rc::clone(body),
+            },
+            value => Value::Expression(value.clone()),
+        }
+    }
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Environment::new()
+    }
+}
+
+// Two environments are equal only if they share the frames. Comparing the
+// contents could go on forever, because procedures reference their environments.
+impl PartialEq for Environment {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.scope, &other.scope)
+    }
+}
+
+// For the same reason, the contents of the frames are not printed.
+impl fmt::Debug for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Environment").finish_non_exhaustive()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::expression::{Expression, Identifier};
+    use std::rc::Rc;
+
+    use crate::{
+        expression::{Expression, Identifier},
+        interpret::Interpreter,
+        number::Number,
+        symbol::Symbol,
+    };
 
     use super::*;
 
     #[test]
     fn should_store_a_variable() {
-        let mut env = Environment::new();
+        let env = Environment::new();
 
         let variable = Identifier {
-            name: String::from("num"),
+            name: Symbol::new("num"),
+        };
+        let value = Expression::Number {
+            value: Number::Integer(42),
         };
-        let value = Expression::Number { value: 42.0 };
 
         env.define_variable(&variable, &value);
 
         let result = env.lookup_value(&variable);
 
-        assert_eq!(result, Ok(Expression::Number { value: 42.0 }));
+        assert_eq!(
+            result,
+            Ok(Expression::Number {
+                value: Number::Integer(42)
+            })
+        );
     }
 
     #[test]
@@ -64,11 +235,170 @@ mod tests {
         let env = Environment::new();
 
         let variable = Identifier {
-            name: String::from("undefined"),
+            name: Symbol::new("undefined"),
         };
 
         let error = env.lookup_value(&variable).unwrap_err();
 
         assert_eq!(error, LisrEvaluationError::UndefinedIdentifier);
     }
+
+    #[test]
+    fn should_look_up_variables_in_enclosing_frames() {
+        let global = Environment::new();
+        let local = global.extend();
+
+        let variable = Identifier {
+            name: Symbol::new("num"),
+        };
+        global.define_variable(
+            &variable,
+            &Expression::Number {
+                value: Number::Integer(42),
+            },
+        );
+
+        let result = local.lookup_value(&variable);
+
+        assert_eq!(
+            result,
+            Ok(Expression::Number {
+                value: Number::Integer(42)
+            })
+        );
+    }
+
+    #[test]
+    fn should_shadow_variables_of_enclosing_frames() {
+        let global = Environment::new();
+        let local = global.extend();
+
+        let variable = Identifier {
+            name: Symbol::new("num"),
+        };
+        global.define_variable(
+            &variable,
+            &Expression::Number {
+                value: Number::Integer(1),
+            },
+        );
+        local.define_variable(
+            &variable,
+            &Expression::Number {
+                value: Number::Integer(2),
+            },
+        );
+
+        assert_eq!(
+            global.lookup_value(&variable),
+            Ok(Expression::Number {
+                value: Number::Integer(1)
+            })
+        );
+        assert_eq!(
+            local.lookup_value(&variable),
+            Ok(Expression::Number {
+                value: Number::Integer(2)
+            })
+        );
+    }
+
+    #[test]
+    fn should_set_a_variable_in_the_frame_it_was_defined_in() {
+        let global = Environment::new();
+        let local = global.extend();
+
+        let variable = Identifier {
+            name: Symbol::new("num"),
+        };
+        global.define_variable(
+            &variable,
+            &Expression::Number {
+                value: Number::Integer(1),
+            },
+        );
+
+        local
+            .set_variable_value(
+                &variable,
+                &Expression::Number {
+                    value: Number::Integer(2),
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            global.lookup_value(&variable),
+            Ok(Expression::Number {
+                value: Number::Integer(2)
+            })
+        );
+    }
+
+    #[test]
+    fn should_not_set_an_undefined_variable() {
+        let env = Environment::new();
+
+        let variable = Identifier {
+            name: Symbol::new("undefined"),
+        };
+
+        let error = env
+            .set_variable_value(
+                &variable,
+                &Expression::Number {
+                    value: Number::Integer(1),
+                },
+            )
+            .unwrap_err();
+
+        assert_eq!(error, LisrEvaluationError::UndefinedIdentifier);
+    }
+
+    #[test]
+    fn should_free_a_frame_with_a_procedure_defined_in_it() {
+        let global = Environment::new();
+        let local = global.extend();
+
+        let variable = Identifier {
+            name: Symbol::new("loop"),
+        };
+        let procedure = Expression::CompoundProcedure {
+            parameters: Rc::from([]),
+            rest_parameter: None,
+            body: Rc::new(Expression::EmptyList),
+            environment: local.clone(),
+        };
+        local.define_variable(&variable, &procedure);
+
+        // The procedure gets its environment back when it is looked up.
+        assert_eq!(local.lookup_value(&variable), Ok(procedure.clone()));
+
+        let frame = Rc::downgrade(&local.scope);
+        drop(local);
+        assert!(frame.upgrade().is_some());
+        drop(procedure);
+        assert!(frame.upgrade().is_none());
+    }
+
+    #[test]
+    fn should_free_the_frames_of_named_let_loops() {
+        let mut interpreter = Interpreter::new();
+        interpreter
+            .interpret("(define (f) (let loop ((i 0)) (if (= i 3) loop (loop (+ i 1)))))")
+            .unwrap();
+
+        // Every call makes a new frame for the loop, so a frame that is kept
+        // alive by its loop would make memory grow with each call.
+        for _ in 0..100 {
+            let Ok(Expression::CompoundProcedure { environment, .. }) =
+                interpreter.interpret("(f)")
+            else {
+                panic!("expected the loop procedure");
+            };
+            let frame = Rc::downgrade(&environment.scope);
+            drop(environment);
+            assert!(frame.upgrade().is_none());
+        }
+    }
 }
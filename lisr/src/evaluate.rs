@@ -1,6 +1,12 @@
+use std::{cmp::Ordering, fmt, rc::Rc};
+
 use crate::{
     environment::Environment,
-    expression::{Expression, Identifier, Parameter},
+    expression::{Binding, ClauseBody, Expression, Identifier, LetKind, Parameter},
+    number::Number,
+    scan::parse_number,
+    span::{Span, Spanned},
+    symbol::Symbol,
 };
 
 #[derive(Debug, PartialEq)]
@@ -10,17 +16,67 @@ pub enum LisrEvaluationError {
     UndefinedIdentifier,
 }
 
-pub fn evaluate<I>(expressions: I) -> Result<Expression, LisrEvaluationError>
+impl LisrEvaluationError {
+    // A hint on how to fix the error.
+    pub fn help(&self) -> Option<String> {
+        match self {
+            LisrEvaluationError::RuntimeError { .. } => None,
+            LisrEvaluationError::TypeError => Some(String::from(
+                "the procedure cannot be applied to arguments of these types",
+            )),
+            LisrEvaluationError::UndefinedIdentifier => Some(String::from(
+                "define it before it is used, like (define x 42)",
+            )),
+        }
+    }
+}
+
+impl fmt::Display for LisrEvaluationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // Reasons are written as sentences, but messages of the other
+            // errors start in lowercase.
+            LisrEvaluationError::RuntimeError { reason } => {
+                let mut chars = reason.chars();
+                match chars.next() {
+                    Some(first) => write!(f, "{}{}", first.to_lowercase(), chars.as_str()),
+                    None => Ok(()),
+                }
+            }
+            LisrEvaluationError::TypeError => write!(f, "type error"),
+            LisrEvaluationError::UndefinedIdentifier => write!(f, "undefined identifier"),
+        }
+    }
+}
+
+pub fn evaluate<I>(expressions: I) -> Result<Expression, Spanned<LisrEvaluationError>>
 where
     I: IntoIterator<Item = Expression>,
 {
-    let mut environment = Environment::new();
-    setup_primitive_procedures(&mut environment);
+    let environment = create_global_environment();
+    evaluate_in_environment(expressions, &environment)
+}
+
+// Creates an environment with all the primitive procedures defined.
+pub fn create_global_environment() -> Environment {
+    let environment = Environment::new();
+    setup_primitive_procedures(&environment);
+    environment
+}
 
+// Evaluates expressions in the given environment, so that definitions outlive
+// a single call (useful for a REPL).
+pub fn evaluate_in_environment<I>(
+    expressions: I,
+    environment: &Environment,
+) -> Result<Expression, Spanned<LisrEvaluationError>>
+where
+    I: IntoIterator<Item = Expression>,
+{
     // Keeps evaluating even if an error happens.
     let outcome = expressions
         .into_iter()
-        .map(|expression| evaluate_expression(expression, &mut environment))
+        .map(|expression| evaluate_expression(&expression, environment))
         .last();
 
     match outcome {
@@ -31,172 +87,515 @@ where
     }
 }
 
+// Defines the `command-line` procedure, which returns the given arguments as a
+// list of strings.
+pub fn define_command_line(environment: &Environment, arguments: Vec<String>) {
+    let arguments = make_list(
+        arguments
+            .into_iter()
+            .map(|argument| Expression::String { value: argument }),
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("command-line"),
+        },
+        &Expression::CompoundProcedure {
+            parameters: Rc::from([]),
+            rest_parameter: None,
+            body: Rc::new(arguments),
+            environment: Environment::new(),
+        },
+    );
+}
+
+// What's left after evaluating an expression up to a procedure call in a tail
+// position.
+enum Outcome {
+    Value(Expression),
+    // The body of the called procedure, which has to be evaluated next (in the
+    // environment with the arguments bound).
+    TailCall(Rc<Expression>),
+}
+
+// Calls in a tail position (in the branches of an if, the last expression of a
+// begin, and or or and the body of a procedure) do not evaluate the body of the
+// called procedure recursively. Instead, the loop goes on with the body, so that
+// tail calls run in constant space.
 fn evaluate_expression(
-    expression: Expression,
-    environment: &mut Environment,
-) -> Result<Expression, LisrEvaluationError> {
+    expression: &Expression,
+    environment: &Environment,
+) -> Result<Expression, Spanned<LisrEvaluationError>> {
+    // Variables and numbers are evaluated the most often, so they skip the loop.
     match expression {
-        Expression::EmptyList
-        | Expression::String { .. }
-        | Expression::Number { .. }
-        | Expression::Quotation { .. }
-        | Expression::True
-        | Expression::False
-        | Expression::LisrInternalObject { .. }
-        | Expression::PrimitiveProcedure { .. }
-        | Expression::CompoundProcedure { .. } => Ok(expression),
-        Expression::Identifier(identifier) => {
-            let value = environment.lookup_value(&identifier)?;
-            Ok(value)
+        Expression::Identifier { identifier, span } => {
+            return environment
+                .lookup_value(identifier)
+                .map_err(|error| Spanned::new(error, *span))
         }
-        Expression::Assignment { variable, value } => {
-            // Check if the value has already been defined.
-            environment.lookup_value(&variable)?;
-
-            let evaluated_value = evaluate_expression(*value, environment)?;
-            environment.define_variable(&variable, &evaluated_value);
-            Ok(evaluated_value)
-        }
-        Expression::Definition { variable, value } => {
-            let evaluated_value = evaluate_expression(*value, environment)?;
-            environment.define_variable(&variable, &evaluated_value);
-            Ok(Expression::Identifier(variable))
-        }
-        Expression::Lambda { parameters, body } => Ok(Expression::CompoundProcedure {
-            parameters,
-            body,
-            environment: Box::new(environment.clone()),
-        }),
-        Expression::If {
-            predicate,
-            consequent,
-            alternative,
+        Expression::Number { .. } => return Ok(expression.clone()),
+        // Calls that are not in a tail position only need the loop for the
+        // body of a compound procedure.
+        Expression::Application {
+            procedure,
+            arguments,
+            span,
         } => {
-            let evaluated_predicate = evaluate_expression(*predicate, environment)?;
-            match evaluated_predicate {
-                Expression::True => evaluate_expression(*consequent, environment),
-                Expression::False => evaluate_expression(*alternative, environment),
-                _ => Err(LisrEvaluationError::RuntimeError {
-                    reason: "Predicate of an if expression did not evaluate to a boolean value",
-                }),
+            let procedure = evaluate_expression(procedure, environment)?;
+            let arguments = evaluate_arguments(arguments, environment)?;
+            if let Expression::PrimitiveProcedure { .. } = procedure {
+                return apply(procedure, arguments, *span);
             }
+            let mut environment = environment.clone();
+            let outcome = call(procedure, arguments, &mut environment, *span)?;
+            return finish_tail_calls(outcome, &mut environment);
         }
-        Expression::And { operands } => {
-            for operand in operands.into_iter() {
-                let evaluated_operand = evaluate_expression(operand, environment)?;
-                match evaluated_operand {
-                    Expression::True => {
-                        continue;
-                    }
-                    Expression::False => return Ok(Expression::False),
-                    _ => {
-                        return Err(LisrEvaluationError::RuntimeError {
-                            reason:
-                                "Operand of an and expression did not evaluate to a boolean value",
-                        })
+        _ => {}
+    }
+    let mut environment = environment.clone();
+    let outcome = evaluate_until_tail_call(expression, &mut environment)?;
+    finish_tail_calls(outcome, &mut environment)
+}
+
+// Calls a procedure with already evaluated arguments, like macros are called
+// with the code they are used with.
+pub fn call_procedure(
+    procedure: Expression,
+    arguments: Vec<Expression>,
+    span: Span,
+) -> Result<Expression, Spanned<LisrEvaluationError>> {
+    // Compound procedures replace the environment with their own one.
+    let mut environment = Environment::new();
+    let outcome = call(procedure, arguments, &mut environment, span)?;
+    finish_tail_calls(outcome, &mut environment)
+}
+
+fn finish_tail_calls(
+    outcome: Outcome,
+    environment: &mut Environment,
+) -> Result<Expression, Spanned<LisrEvaluationError>> {
+    let mut body = match outcome {
+        Outcome::Value(value) => return Ok(value),
+        Outcome::TailCall(body) => body,
+    };
+    loop {
+        body = match evaluate_until_tail_call(&body, environment)? {
+            Outcome::Value(value) => return Ok(value),
+            Outcome::TailCall(body) => body,
+        };
+    }
+}
+
+// Evaluates the expression, following its tail positions. When a compound
+// procedure is called in a tail position, its arguments are bound in a new
+// `environment` and its body is returned instead of being evaluated.
+fn evaluate_until_tail_call(
+    expression: &Expression,
+    environment: &mut Environment,
+) -> Result<Outcome, Spanned<LisrEvaluationError>> {
+    let mut expression = expression;
+
+    loop {
+        match expression {
+            Expression::EmptyList
+            | Expression::String { .. }
+            | Expression::Number { .. }
+            | Expression::Character { .. }
+            | Expression::Symbol { .. }
+            | Expression::True
+            | Expression::False
+            | Expression::Unspecified
+            | Expression::LisrInternalObject { .. }
+            | Expression::PrimitiveProcedure { .. }
+            | Expression::CompoundProcedure { .. } => {
+                return Ok(Outcome::Value(expression.clone()))
+            }
+            Expression::Quote { datum } => return Ok(Outcome::Value((**datum).clone())),
+            Expression::Quasiquote { template } => {
+                return fill_template(template, environment).map(Outcome::Value)
+            }
+            Expression::Unquote { .. } | Expression::UnquoteSplicing { .. } => {
+                panic!("Internal error: unquote outside of a quasiquote template")
+            }
+            Expression::Identifier { identifier, span } => {
+                return environment
+                    .lookup_value(identifier)
+                    .map(Outcome::Value)
+                    .map_err(|error| Spanned::new(error, *span))
+            }
+            Expression::Assignment {
+                variable,
+                value,
+                span,
+            } => {
+                let evaluated_value = evaluate_expression(value, environment)?;
+                // The variable is changed in the frame it has been defined in.
+                environment
+                    .set_variable_value(variable, &evaluated_value)
+                    .map_err(|error| Spanned::new(error, *span))?;
+                return Ok(Outcome::Value(evaluated_value));
+            }
+            Expression::Definition {
+                variable,
+                value,
+                span,
+            } => {
+                let evaluated_value = evaluate_expression(value, environment)?;
+                environment.define_variable(variable, &evaluated_value);
+                return Ok(Outcome::Value(Expression::Identifier {
+                    identifier: variable.clone(),
+                    span: *span,
+                }));
+            }
+            Expression::Lambda {
+                parameters,
+                rest_parameter,
+                body,
+            } => {
+                return Ok(Outcome::Value(Expression::CompoundProcedure {
+                    parameters: Rc::clone(parameters),
+                    rest_parameter: rest_parameter.clone(),
+                    body: Rc::clone(body),
+                    environment: environment.clone(),
+                }))
+            }
+            Expression::If {
+                predicate,
+                consequent,
+                alternative,
+                ..
+            } => {
+                let evaluated_predicate = evaluate_expression(predicate, environment)?;
+                expression = if is_true(&evaluated_predicate) {
+                    consequent
+                } else {
+                    alternative
+                };
+            }
+            // The last operand of an and (and or) is in a tail position, so its
+            // value is returned as it is. Other operands stop the evaluation
+            // when they are false (or true for or) and their value is returned.
+            Expression::And { operands, .. } => {
+                let Some((last, operands)) = operands.split_last() else {
+                    return Ok(Outcome::Value(Expression::True));
+                };
+                for operand in operands {
+                    let evaluated_operand = evaluate_expression(operand, environment)?;
+                    if !is_true(&evaluated_operand) {
+                        return Ok(Outcome::Value(evaluated_operand));
                     }
                 }
+                expression = last;
             }
-            Ok(Expression::True)
-        }
-        Expression::Or { operands } => {
-            for operand in operands.into_iter() {
-                let evaluated_operand = evaluate_expression(operand, environment)?;
-                match evaluated_operand {
-                    Expression::False => {
-                        continue;
+            Expression::Or { operands, .. } => {
+                let Some((last, operands)) = operands.split_last() else {
+                    return Ok(Outcome::Value(Expression::False));
+                };
+                for operand in operands {
+                    let evaluated_operand = evaluate_expression(operand, environment)?;
+                    if is_true(&evaluated_operand) {
+                        return Ok(Outcome::Value(evaluated_operand));
                     }
-                    Expression::True => return Ok(Expression::True),
-                    _ => {
-                        return Err(LisrEvaluationError::RuntimeError {
+                }
+                expression = last;
+            }
+            Expression::Begin { sequence, span } => {
+                let Some((last, sequence)) = sequence.split_last() else {
+                    return Err(Spanned::new(
+                        LisrEvaluationError::RuntimeError {
                             reason:
-                                "Operand of an or expression did not evaluate to a boolean value",
-                        })
+                                "A sequence of expressions in a begin statement cannot be empty",
+                        },
+                        *span,
+                    ));
+                };
+                // Stops at the first error, just like evaluation of a whole program.
+                for expression in sequence {
+                    evaluate_expression(expression, environment)?;
+                }
+                expression = last;
+            }
+            Expression::Cond { clauses, span } => {
+                let mut selected_clause = None;
+                for clause in clauses {
+                    let value = evaluate_expression(&clause.test, environment)?;
+                    if is_true(&value) {
+                        selected_clause = Some((value, &clause.body));
+                        break;
+                    }
+                }
+                let Some((value, body)) = selected_clause else {
+                    return Ok(Outcome::Value(Expression::Unspecified));
+                };
+                match body {
+                    ClauseBody::TestValue => return Ok(Outcome::Value(value)),
+                    ClauseBody::Sequence(sequence) => expression = sequence,
+                    ClauseBody::Receiver(receiver) => {
+                        let receiver = evaluate_expression(receiver, environment)?;
+                        return call(receiver, vec![value], environment, *span);
                     }
                 }
             }
-            Ok(Expression::False)
-        }
-        Expression::Begin { sequence } => {
-            let result = sequence
-                .into_iter()
-                .map(|expression| evaluate_expression(expression, environment))
-                .last();
-            let Some(result ) = result else {
-                return Err(LisrEvaluationError::RuntimeError { reason: "A sequence of expressions in a begin statement cannot be empty" })
-            };
-            result
-        }
-        Expression::Cons { first, rest } => Ok(Expression::Cons {
-            first: Box::new(evaluate_expression(*first, environment)?),
-            rest: Box::new(evaluate_expression(*rest, environment)?),
-        }),
-        Expression::Application {
-            procedure,
-            arguments,
-        } => {
-            let procedure = evaluate_expression(*procedure, environment)?;
-            let arguments = arguments
-                .into_iter()
-                .map(|argument| evaluate_expression(argument, environment))
-                .collect::<Result<Vec<Expression>, LisrEvaluationError>>()?;
-            apply(procedure, arguments, environment)
+            // Keys match data that are equal to them, so strings can be used as
+            // data too.
+            Expression::Case {
+                key,
+                clauses,
+                else_clause,
+                span,
+            } => {
+                let key = evaluate_expression(key, environment)?;
+                let body = clauses
+                    .iter()
+                    .find(|clause| clause.data.contains(&key))
+                    .map(|clause| &clause.body)
+                    .or(else_clause.as_ref());
+                match body {
+                    None => return Ok(Outcome::Value(Expression::Unspecified)),
+                    Some(ClauseBody::TestValue) => return Ok(Outcome::Value(key)),
+                    Some(ClauseBody::Sequence(sequence)) => expression = sequence,
+                    Some(ClauseBody::Receiver(receiver)) => {
+                        let receiver = evaluate_expression(receiver, environment)?;
+                        return call(receiver, vec![key], environment, *span);
+                    }
+                }
+            }
+            Expression::Let {
+                kind,
+                bindings,
+                body,
+                ..
+            } => {
+                *environment = bind_let_variables(*kind, bindings, environment)?;
+                expression = body;
+            }
+            Expression::Cons { first, rest } => {
+                return Ok(Outcome::Value(Expression::Cons {
+                    first: Box::new(evaluate_expression(first, environment)?),
+                    rest: Box::new(evaluate_expression(rest, environment)?),
+                }))
+            }
+            Expression::Application {
+                procedure,
+                arguments,
+                span,
+            } => {
+                let procedure = evaluate_expression(procedure, environment)?;
+                let arguments = evaluate_arguments(arguments, environment)?;
+                return call(procedure, arguments, environment, *span);
+            }
         }
     }
 }
 
-fn apply(
+fn evaluate_arguments(
+    arguments: &[Expression],
+    environment: &Environment,
+) -> Result<Vec<Expression>, Spanned<LisrEvaluationError>> {
+    let mut evaluated_arguments = Vec::with_capacity(arguments.len());
+    for argument in arguments {
+        evaluated_arguments.push(evaluate_expression(argument, environment)?);
+    }
+    Ok(evaluated_arguments)
+}
+
+// Calls a procedure in a tail position.
+fn call(
     procedure: Expression,
     arguments: Vec<Expression>,
-    environment: &Environment,
-) -> Result<Expression, LisrEvaluationError> {
+    environment: &mut Environment,
+    span: Span,
+) -> Result<Outcome, Spanned<LisrEvaluationError>> {
     match procedure {
-        Expression::PrimitiveProcedure { procedure } => procedure(arguments),
         Expression::CompoundProcedure {
             parameters,
+            rest_parameter,
             body,
-            environment: closed_environment,
+            environment: procedure_environment,
         } => {
-            let mut combined_environment = environment.clone();
-            for (identifier, expression) in closed_environment.into_iter() {
-                combined_environment.define_variable(&identifier, &expression);
+            // Every call gets a new frame on top of the environment the
+            // procedure has been defined in.
+            *environment = bind_arguments(
+                arguments,
+                &parameters,
+                rest_parameter,
+                procedure_environment.extend(),
+                span,
+            )?;
+            Ok(Outcome::TailCall(body))
+        }
+        _ => apply(procedure, arguments, span).map(Outcome::Value),
+    }
+}
+
+// Evaluates the unquoted expressions of a quasiquote template. The other parts
+// of the template are copied.
+fn fill_template(
+    template: &Expression,
+    environment: &Environment,
+) -> Result<Expression, Spanned<LisrEvaluationError>> {
+    match template {
+        Expression::Unquote { expression } => evaluate_expression(expression, environment),
+        Expression::Cons { first, rest } => {
+            let rest = fill_template(rest, environment)?;
+            match first.as_ref() {
+                Expression::UnquoteSplicing { expression, span } => {
+                    let spliced = evaluate_expression(expression, environment)?;
+                    splice(spliced, rest).map_err(|error| Spanned::new(error, *span))
+                }
+                first => Ok(Expression::Cons {
+                    first: Box::new(fill_template(first, environment)?),
+                    rest: Box::new(rest),
+                }),
+            }
+        }
+        datum => Ok(datum.clone()),
+    }
+}
+
+// Puts the elements of the `list` in front of the `rest`.
+fn splice(list: Expression, rest: Expression) -> Result<Expression, LisrEvaluationError> {
+    let mut elements = Vec::new();
+    let mut list = list;
+    loop {
+        match list {
+            Expression::Cons { first, rest } => {
+                elements.push(*first);
+                list = *rest;
+            }
+            Expression::EmptyList => break,
+            _ => {
+                return Err(LisrEvaluationError::RuntimeError {
+                    reason: "'unquote-splicing' requires a list",
+                })
             }
-            apply_compound_procedure(arguments, parameters, *body, combined_environment)
         }
-        _ => Err(LisrEvaluationError::RuntimeError {
-            reason: "Object cannot be invoked",
-        }),
     }
+
+    Ok(elements
+        .into_iter()
+        .rev()
+        .fold(rest, |rest, element| Expression::Cons {
+            first: Box::new(element),
+            rest: Box::new(rest),
+        }))
 }
 
-fn apply_compound_procedure(
+// Only false is false in conditionals, every other value (even 0 and the empty
+// list) is true.
+fn is_true(value: &Expression) -> bool {
+    !matches!(value, Expression::False)
+}
+
+// Errors that are not related to a particular subexpression of the procedure
+// (like a wrong number of arguments) are reported at the `span` of the application.
+// Compound procedures are called by `call`.
+fn apply(
+    procedure: Expression,
     arguments: Vec<Expression>,
-    parameters: Vec<Parameter>,
-    body: Expression,
-    mut environment: Environment,
-) -> Result<Expression, LisrEvaluationError> {
-    if arguments.len() != parameters.len() {
-        return Err(LisrEvaluationError::RuntimeError {
-            reason: "Function called with a wrong number of arguments",
-        });
+    span: Span,
+) -> Result<Expression, Spanned<LisrEvaluationError>> {
+    match procedure {
+        Expression::PrimitiveProcedure { procedure } => {
+            procedure(arguments).map_err(|error| Spanned::new(error, span))
+        }
+        _ => Err(Spanned::new(
+            LisrEvaluationError::RuntimeError {
+                reason: "Object cannot be invoked",
+            },
+            span,
+        )),
+    }
+}
+
+// Defines the parameters of a procedure in the given environment. The arguments
+// left after binding the parameters are bound as a list to the rest parameter.
+fn bind_arguments(
+    arguments: Vec<Expression>,
+    parameters: &[Parameter],
+    rest_parameter: Option<Parameter>,
+    environment: Environment,
+    span: Span,
+) -> Result<Environment, Spanned<LisrEvaluationError>> {
+    let has_wrong_number_of_arguments = match rest_parameter {
+        Some(_) => arguments.len() < parameters.len(),
+        None => arguments.len() != parameters.len(),
+    };
+    if has_wrong_number_of_arguments {
+        return Err(Spanned::new(
+            LisrEvaluationError::RuntimeError {
+                reason: "Function called with a wrong number of arguments",
+            },
+            span,
+        ));
     }
 
-    for (argument, parameter) in arguments.into_iter().zip(parameters.into_iter()) {
+    let mut arguments = arguments.into_iter();
+    for (parameter, argument) in parameters.iter().zip(arguments.by_ref()) {
         environment.define_variable(
             &Identifier {
-                name: parameter.name,
+                name: parameter.name.clone(),
             },
             &argument,
         );
     }
 
-    evaluate_expression(body, &mut environment)
+    if let Some(rest_parameter) = rest_parameter {
+        let rest = make_list(arguments);
+        environment.define_variable(
+            &Identifier {
+                name: rest_parameter.name,
+            },
+            &rest,
+        );
+    }
+
+    Ok(environment)
+}
+
+// Returns the environment the body of a let is evaluated in.
+fn bind_let_variables(
+    kind: LetKind,
+    bindings: &[Binding],
+    environment: &Environment,
+) -> Result<Environment, Spanned<LisrEvaluationError>> {
+    match kind {
+        LetKind::Let => {
+            let values = bindings
+                .iter()
+                .map(|binding| evaluate_expression(&binding.value, environment))
+                .collect::<Result<Vec<Expression>, Spanned<LisrEvaluationError>>>()?;
+            let let_environment = environment.extend();
+            for (binding, value) in bindings.iter().zip(values) {
+                let_environment.define_variable(&binding.variable, &value);
+            }
+            Ok(let_environment)
+        }
+        // Every variable gets its own frame, so that a value cannot see the
+        // variables bound after it.
+        LetKind::LetStar => {
+            let mut let_environment = environment.clone();
+            for binding in bindings {
+                let value = evaluate_expression(&binding.value, &let_environment)?;
+                let_environment = let_environment.extend();
+                let_environment.define_variable(&binding.variable, &value);
+            }
+            Ok(let_environment)
+        }
+        LetKind::Letrec | LetKind::LetrecStar => {
+            let let_environment = environment.extend();
+            for binding in bindings {
+                let value = evaluate_expression(&binding.value, &let_environment)?;
+                let_environment.define_variable(&binding.variable, &value);
+            }
+            Ok(let_environment)
+        }
+    }
 }
 
-fn setup_primitive_procedures(environment: &mut Environment) {
+fn setup_primitive_procedures(environment: &Environment) {
     environment.define_variable(
         &Identifier {
-            name: "+".to_string(),
+            name: Symbol::new("+"),
         },
         &Expression::PrimitiveProcedure {
             procedure: primitive_addition,
@@ -204,7 +603,7 @@ fn setup_primitive_procedures(environment: &mut Environment) {
     );
     environment.define_variable(
         &Identifier {
-            name: "-".to_string(),
+            name: Symbol::new("-"),
         },
         &Expression::PrimitiveProcedure {
             procedure: primitive_subtraction,
@@ -212,7 +611,7 @@ fn setup_primitive_procedures(environment: &mut Environment) {
     );
     environment.define_variable(
         &Identifier {
-            name: "*".to_string(),
+            name: Symbol::new("*"),
         },
         &Expression::PrimitiveProcedure {
             procedure: primitive_multiplication,
@@ -220,7 +619,7 @@ fn setup_primitive_procedures(environment: &mut Environment) {
     );
     environment.define_variable(
         &Identifier {
-            name: "/".to_string(),
+            name: Symbol::new("/"),
         },
         &Expression::PrimitiveProcedure {
             procedure: primitive_division,
@@ -228,7 +627,7 @@ fn setup_primitive_procedures(environment: &mut Environment) {
     );
     environment.define_variable(
         &Identifier {
-            name: "remainder".to_string(),
+            name: Symbol::new("remainder"),
         },
         &Expression::PrimitiveProcedure {
             procedure: primitive_remainder,
@@ -236,7 +635,79 @@ fn setup_primitive_procedures(environment: &mut Environment) {
     );
     environment.define_variable(
         &Identifier {
-            name: "=".to_string(),
+            name: Symbol::new("quotient"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_quotient,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("modulo"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_modulo,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("gcd"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_gcd,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("floor"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_floor,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("numerator"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_numerator,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("denominator"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_denominator,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("exact?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_is_exact,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("inexact?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_is_inexact,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("exact->inexact"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_exact_to_inexact,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("="),
         },
         &Expression::PrimitiveProcedure {
             procedure: primitive_equals,
@@ -244,7 +715,7 @@ fn setup_primitive_procedures(environment: &mut Environment) {
     );
     environment.define_variable(
         &Identifier {
-            name: "<".to_string(),
+            name: Symbol::new("<"),
         },
         &Expression::PrimitiveProcedure {
             procedure: primitive_less_than,
@@ -252,163 +723,1759 @@ fn setup_primitive_procedures(environment: &mut Environment) {
     );
     environment.define_variable(
         &Identifier {
-            name: "car".to_string(),
+            name: Symbol::new(">"),
         },
         &Expression::PrimitiveProcedure {
-            procedure: primitive_car,
+            procedure: primitive_greater_than,
         },
     );
     environment.define_variable(
         &Identifier {
-            name: "cdr".to_string(),
+            name: Symbol::new("<="),
         },
         &Expression::PrimitiveProcedure {
-            procedure: primitive_cdr,
+            procedure: primitive_less_than_or_equal,
         },
     );
     environment.define_variable(
         &Identifier {
-            name: "empty-list?".to_string(),
+            name: Symbol::new(">="),
         },
         &Expression::PrimitiveProcedure {
-            procedure: primitive_is_empty_list,
+            procedure: primitive_greater_than_or_equal,
         },
     );
-}
-
-fn primitive_remainder(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
-    let divisor = arguments.pop();
-    let dividend = arguments.pop();
-
-    if !arguments.is_empty() {
-        return Err(LisrEvaluationError::RuntimeError {
-            reason: "Remainder requires two arguments - a dividend and a divisor",
-        });
-    }
-
-    match (dividend, divisor) {
-        (
-            Some(Expression::Number { value: dividend }),
-            Some(Expression::Number { value: divisor }),
-        ) => Ok(Expression::Number {
-            value: dividend % divisor,
-        }),
-        _ => Err(LisrEvaluationError::TypeError),
-    }
-}
-
-fn primitive_equals(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
-    let mut arguments = arguments.into_iter();
-    let Some(mut current) = arguments.next() else {
-        return Err(LisrEvaluationError::RuntimeError { reason: "Equals cannot be invoked without any arguments" });
-    };
-
-    for next in arguments {
-        match (current, &next) {
-            (Expression::Number { value: a }, Expression::Number { value: b }) => {
-                if a != *b {
-                    return Ok(Expression::False);
-                }
-            }
-            (Expression::String { value: a }, Expression::String { value: b }) => {
-                if a != *b {
-                    return Ok(Expression::False);
-                }
-            }
-            _ => {
-                return Err(LisrEvaluationError::RuntimeError {
-                    reason: "Equals is only implemented for pairs of strings or numbers",
-                })
-            }
-        }
-        current = next;
-    }
-
-    Ok(Expression::True)
-}
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("abs"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_abs,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("min"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_min,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("max"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_max,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("ceiling"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_ceiling,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("round"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_round,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("truncate"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_truncate,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("sqrt"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_sqrt,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("expt"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_expt,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("exp"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_exp,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("log"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_log,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("sin"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_sin,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("cos"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_cos,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("tan"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_tan,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("atan"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_atan,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("number?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_is_number,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("integer?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_is_integer,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("zero?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_is_zero,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("positive?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_is_positive,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("negative?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_is_negative,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("even?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_is_even,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("odd?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_is_odd,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("lcm"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_lcm,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("car"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_car,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("cdr"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_cdr,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("empty-list?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_is_empty_list,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("symbol?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_is_symbol,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("eq?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_eq,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("symbol->string"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_symbol_to_string,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("string->symbol"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_string_to_symbol,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("string?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_is_string,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("string-length"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_string_length,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("substring"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_substring,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("string-ref"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_string_ref,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("string-append"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_string_append,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("string-upcase"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_string_upcase,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("string-downcase"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_string_downcase,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("string-index"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_string_index,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("string-split"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_string_split,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("string-join"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_string_join,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("string->number"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_string_to_number,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("number->string"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_number_to_string,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("string->list"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_string_to_list,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("string=?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_string_equals,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("string<?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_string_less_than,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("string>?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_string_greater_than,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("char?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_is_char,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("char->integer"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_char_to_integer,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("integer->char"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_integer_to_char,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("char-upcase"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_char_upcase,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("char-downcase"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_char_downcase,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("char-alphabetic?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_is_char_alphabetic,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("char-numeric?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_is_char_numeric,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("char-whitespace?"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_is_char_whitespace,
+        },
+    );
+    environment.define_variable(
+        &Identifier {
+            name: Symbol::new("list->string"),
+        },
+        &Expression::PrimitiveProcedure {
+            procedure: primitive_list_to_string,
+        },
+    );
+}
+
+fn primitive_remainder(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let divisor = arguments.pop();
+    let dividend = arguments.pop();
+
+    if !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "Remainder requires two arguments - a dividend and a divisor",
+        });
+    }
+
+    match (dividend, divisor) {
+        (
+            Some(Expression::Number { value: dividend }),
+            Some(Expression::Number { value: divisor }),
+        ) => Ok(Expression::Number {
+            value: dividend.remainder(&divisor)?,
+        }),
+        _ => Err(LisrEvaluationError::TypeError),
+    }
+}
+
+fn primitive_quotient(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let divisor = arguments.pop();
+    let dividend = arguments.pop();
+
+    if !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "Quotient requires two arguments - a dividend and a divisor",
+        });
+    }
+
+    match (dividend, divisor) {
+        (
+            Some(Expression::Number { value: dividend }),
+            Some(Expression::Number { value: divisor }),
+        ) => Ok(Expression::Number {
+            value: dividend.quotient(&divisor)?,
+        }),
+        _ => Err(LisrEvaluationError::TypeError),
+    }
+}
+
+fn primitive_modulo(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let divisor = arguments.pop();
+    let dividend = arguments.pop();
+
+    if !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "Modulo requires two arguments - a dividend and a divisor",
+        });
+    }
+
+    match (dividend, divisor) {
+        (
+            Some(Expression::Number { value: dividend }),
+            Some(Expression::Number { value: divisor }),
+        ) => Ok(Expression::Number {
+            value: dividend.modulo(&divisor)?,
+        }),
+        _ => Err(LisrEvaluationError::TypeError),
+    }
+}
+
+// Unlike the other arithmetic functions, `(gcd)` is allowed and gives 0.
+fn primitive_gcd(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let mut result = Number::Integer(0);
+    for argument in arguments {
+        let Expression::Number { value } = argument else {
+            return Err(LisrEvaluationError::TypeError);
+        };
+        result = result.gcd(&value)?;
+    }
+    Ok(Expression::Number { value: result })
+}
+
+fn primitive_floor(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = take_number(arguments, "'floor' function requires one number argument")?;
+    Ok(Expression::Number {
+        value: number.floor(),
+    })
+}
+
+fn primitive_numerator(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = arguments.pop();
+
+    if !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'numerator' function requires one number argument",
+        });
+    }
+
+    match number {
+        Some(Expression::Number { value }) => match value.numerator() {
+            Some(numerator) => Ok(Expression::Number { value: numerator }),
+            None => Err(LisrEvaluationError::RuntimeError {
+                reason: "Only finite numbers have a numerator",
+            }),
+        },
+        _ => Err(LisrEvaluationError::TypeError),
+    }
+}
+
+fn primitive_denominator(
+    mut arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    let number = arguments.pop();
+
+    if !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'denominator' function requires one number argument",
+        });
+    }
+
+    match number {
+        Some(Expression::Number { value }) => match value.denominator() {
+            Some(denominator) => Ok(Expression::Number { value: denominator }),
+            None => Err(LisrEvaluationError::RuntimeError {
+                reason: "Only finite numbers have a denominator",
+            }),
+        },
+        _ => Err(LisrEvaluationError::TypeError),
+    }
+}
+
+fn primitive_is_exact(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = arguments.pop();
+
+    if !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'exact?' function requires one number argument",
+        });
+    }
+
+    match number {
+        Some(Expression::Number { value }) if value.is_exact() => Ok(Expression::True),
+        Some(Expression::Number { .. }) => Ok(Expression::False),
+        _ => Err(LisrEvaluationError::TypeError),
+    }
+}
+
+fn primitive_is_inexact(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = arguments.pop();
+
+    if !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'inexact?' function requires one number argument",
+        });
+    }
+
+    match number {
+        Some(Expression::Number { value }) if value.is_exact() => Ok(Expression::False),
+        Some(Expression::Number { .. }) => Ok(Expression::True),
+        _ => Err(LisrEvaluationError::TypeError),
+    }
+}
+
+fn primitive_exact_to_inexact(
+    mut arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    let number = arguments.pop();
+
+    if !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'exact->inexact' function requires one number argument",
+        });
+    }
+
+    match number {
+        Some(Expression::Number { value }) => Ok(Expression::Number {
+            value: value.to_inexact(),
+        }),
+        _ => Err(LisrEvaluationError::TypeError),
+    }
+}
+
+fn primitive_equals(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let mut arguments = arguments.into_iter();
+    let Some(mut current) = arguments.next() else {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "Equals cannot be invoked without any arguments",
+        });
+    };
+
+    for next in arguments {
+        match (current, &next) {
+            (Expression::Number { value: a }, Expression::Number { value: b }) => {
+                if a.compare(b) != Some(Ordering::Equal) {
+                    return Ok(Expression::False);
+                }
+            }
+            (Expression::String { value: a }, Expression::String { value: b }) => {
+                if a != *b {
+                    return Ok(Expression::False);
+                }
+            }
+            _ => {
+                return Err(LisrEvaluationError::RuntimeError {
+                    reason: "Equals is only implemented for pairs of strings or numbers",
+                })
+            }
+        }
+        current = next;
+    }
+
+    Ok(Expression::True)
+}
+
+// Two strings can be compared too, like `(< "a" "b")`.
+fn primitive_less_than(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    if let [Expression::String { value: left }, Expression::String { value: right }] =
+        arguments.as_slice()
+    {
+        return Ok(if left < right {
+            Expression::True
+        } else {
+            Expression::False
+        });
+    }
+    compare_numbers(
+        arguments,
+        Ordering::is_lt,
+        "'Less than' function requires at least two arguments",
+    )
+}
+
+// The functions below compare numbers only. Each adjacent pair of arguments
+// must be in order, like in `(<= 1 2 2 3)`.
+fn compare_numbers(
+    arguments: Vec<Expression>,
+    is_in_order: fn(Ordering) -> bool,
+    reason: &'static str,
+) -> Result<Expression, LisrEvaluationError> {
+    if arguments.len() < 2 {
+        return Err(LisrEvaluationError::RuntimeError { reason });
+    }
+    let mut numbers = Vec::new();
+    for argument in arguments {
+        let Expression::Number { value } = argument else {
+            return Err(LisrEvaluationError::TypeError);
+        };
+        numbers.push(value);
+    }
+    let is_sorted = numbers
+        .windows(2)
+        .all(|pair| pair[0].compare(&pair[1]).is_some_and(is_in_order));
+    if is_sorted {
+        Ok(Expression::True)
+    } else {
+        Ok(Expression::False)
+    }
+}
+
+fn primitive_greater_than(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    compare_numbers(
+        arguments,
+        Ordering::is_gt,
+        "'Greater than' function requires at least two arguments",
+    )
+}
+
+fn primitive_less_than_or_equal(
+    arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    compare_numbers(
+        arguments,
+        Ordering::is_le,
+        "'Less than or equal' function requires at least two arguments",
+    )
+}
+
+fn primitive_greater_than_or_equal(
+    arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    compare_numbers(
+        arguments,
+        Ordering::is_ge,
+        "'Greater than or equal' function requires at least two arguments",
+    )
+}
+
+// Takes the only argument of a function of one number.
+fn take_number(
+    mut arguments: Vec<Expression>,
+    reason: &'static str,
+) -> Result<Number, LisrEvaluationError> {
+    let number = arguments.pop();
+
+    if number.is_none() || !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError { reason });
+    }
+
+    match number {
+        Some(Expression::Number { value }) => Ok(value),
+        _ => Err(LisrEvaluationError::TypeError),
+    }
+}
+
+// Takes the arguments of a function of one number and an optional second
+// number, like `(log 8 2)`.
+fn take_one_or_two_numbers(
+    arguments: Vec<Expression>,
+    reason: &'static str,
+) -> Result<(Number, Option<Number>), LisrEvaluationError> {
+    let mut numbers = Vec::new();
+    for argument in arguments {
+        let Expression::Number { value } = argument else {
+            return Err(LisrEvaluationError::TypeError);
+        };
+        numbers.push(value);
+    }
+    let second = if numbers.len() == 2 {
+        numbers.pop()
+    } else {
+        None
+    };
+    match numbers.pop() {
+        Some(first) if numbers.is_empty() => Ok((first, second)),
+        _ => Err(LisrEvaluationError::RuntimeError { reason }),
+    }
+}
+
+fn primitive_abs(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = take_number(arguments, "'abs' function requires one number argument")?;
+    Ok(Expression::Number {
+        value: number.abs(),
+    })
+}
+
+// If any argument is inexact, so is the result, like in `(max 1 2.0)`.
+fn find_extremum(
+    arguments: Vec<Expression>,
+    should_replace: fn(Ordering) -> bool,
+    reason: &'static str,
+) -> Result<Expression, LisrEvaluationError> {
+    let mut extremum: Option<Number> = None;
+    let mut is_exact = true;
+    for argument in arguments {
+        let Expression::Number { value } = argument else {
+            return Err(LisrEvaluationError::TypeError);
+        };
+        is_exact &= value.is_exact();
+        extremum = match extremum {
+            Some(current) if !value.compare(&current).is_some_and(should_replace) => Some(current),
+            _ => Some(value),
+        };
+    }
+    match extremum {
+        Some(value) if is_exact => Ok(Expression::Number { value }),
+        Some(value) => Ok(Expression::Number {
+            value: value.to_inexact(),
+        }),
+        None => Err(LisrEvaluationError::RuntimeError { reason }),
+    }
+}
+
+fn primitive_min(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    find_extremum(
+        arguments,
+        Ordering::is_lt,
+        "'min' function requires at least one argument",
+    )
+}
+
+fn primitive_max(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    find_extremum(
+        arguments,
+        Ordering::is_gt,
+        "'max' function requires at least one argument",
+    )
+}
+
+fn primitive_ceiling(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = take_number(arguments, "'ceiling' function requires one number argument")?;
+    Ok(Expression::Number {
+        value: number.ceiling(),
+    })
+}
+
+fn primitive_round(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = take_number(arguments, "'round' function requires one number argument")?;
+    Ok(Expression::Number {
+        value: number.round(),
+    })
+}
+
+fn primitive_truncate(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = take_number(
+        arguments,
+        "'truncate' function requires one number argument",
+    )?;
+    Ok(Expression::Number {
+        value: number.truncate(),
+    })
+}
+
+fn primitive_sqrt(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = take_number(arguments, "'sqrt' function requires one number argument")?;
+    Ok(Expression::Number {
+        value: number.sqrt(),
+    })
+}
+
+fn primitive_expt(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let exponent = arguments.pop();
+    let base = arguments.pop();
+
+    if base.is_none() || !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'expt' function requires two arguments - a base and an exponent",
+        });
+    }
+
+    match (base, exponent) {
+        (
+            Some(Expression::Number { value: base }),
+            Some(Expression::Number { value: exponent }),
+        ) => Ok(Expression::Number {
+            value: base.expt(&exponent)?,
+        }),
+        _ => Err(LisrEvaluationError::TypeError),
+    }
+}
+
+fn primitive_exp(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = take_number(arguments, "'exp' function requires one number argument")?;
+    Ok(Expression::Number {
+        value: Number::Real(number.to_f64().exp()),
+    })
+}
+
+// `(log z)` is the natural logarithm, `(log z b)` is the logarithm in base b.
+fn primitive_log(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let (number, base) = take_one_or_two_numbers(
+        arguments,
+        "'log' function requires a number and an optional base",
+    )?;
+    let logarithm = match base {
+        Some(base) => number.to_f64().ln() / base.to_f64().ln(),
+        None => number.to_f64().ln(),
+    };
+    Ok(Expression::Number {
+        value: Number::Real(logarithm),
+    })
+}
+
+fn primitive_sin(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = take_number(arguments, "'sin' function requires one number argument")?;
+    Ok(Expression::Number {
+        value: Number::Real(number.to_f64().sin()),
+    })
+}
+
+fn primitive_cos(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = take_number(arguments, "'cos' function requires one number argument")?;
+    Ok(Expression::Number {
+        value: Number::Real(number.to_f64().cos()),
+    })
+}
+
+fn primitive_tan(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = take_number(arguments, "'tan' function requires one number argument")?;
+    Ok(Expression::Number {
+        value: Number::Real(number.to_f64().tan()),
+    })
+}
+
+// `(atan y x)` is the angle of the point (x, y), so it knows the quadrant.
+fn primitive_atan(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let (y, x) = take_one_or_two_numbers(
+        arguments,
+        "'atan' function requires one number argument or two coordinates",
+    )?;
+    let angle = match x {
+        Some(x) => y.to_f64().atan2(x.to_f64()),
+        None => y.to_f64().atan(),
+    };
+    Ok(Expression::Number {
+        value: Number::Real(angle),
+    })
+}
+
+fn primitive_is_number(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let value = arguments.pop();
+
+    if value.is_none() || !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'number?' function requires one argument",
+        });
+    }
+
+    match value {
+        Some(Expression::Number { .. }) => Ok(Expression::True),
+        _ => Ok(Expression::False),
+    }
+}
+
+// Unlike the other number predicates, `integer?` accepts any value.
+fn primitive_is_integer(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let value = arguments.pop();
+
+    if value.is_none() || !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'integer?' function requires one argument",
+        });
+    }
+
+    match value {
+        Some(Expression::Number { value }) if value.is_integer() => Ok(Expression::True),
+        _ => Ok(Expression::False),
+    }
+}
+
+fn primitive_is_zero(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = take_number(arguments, "'zero?' function requires one number argument")?;
+    if number.is_zero() {
+        Ok(Expression::True)
+    } else {
+        Ok(Expression::False)
+    }
+}
+
+fn primitive_is_positive(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = take_number(
+        arguments,
+        "'positive?' function requires one number argument",
+    )?;
+    if number.compare(&Number::Integer(0)) == Some(Ordering::Greater) {
+        Ok(Expression::True)
+    } else {
+        Ok(Expression::False)
+    }
+}
+
+fn primitive_is_negative(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = take_number(
+        arguments,
+        "'negative?' function requires one number argument",
+    )?;
+    if number.compare(&Number::Integer(0)) == Some(Ordering::Less) {
+        Ok(Expression::True)
+    } else {
+        Ok(Expression::False)
+    }
+}
+
+// Only integers are even or odd, so `(even? 1.5)` is a type error.
+fn is_even(number: Number) -> Result<bool, LisrEvaluationError> {
+    if !number.is_integer() {
+        return Err(LisrEvaluationError::TypeError);
+    }
+    Ok(number.remainder(&Number::Integer(2))?.is_zero())
+}
+
+fn primitive_is_even(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = take_number(arguments, "'even?' function requires one integer argument")?;
+    if is_even(number)? {
+        Ok(Expression::True)
+    } else {
+        Ok(Expression::False)
+    }
+}
+
+fn primitive_is_odd(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let number = take_number(arguments, "'odd?' function requires one integer argument")?;
+    if is_even(number)? {
+        Ok(Expression::False)
+    } else {
+        Ok(Expression::True)
+    }
+}
+
+// `(lcm)` is allowed and gives 1, like `(gcd)` gives 0.
+fn primitive_lcm(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let mut result = Number::Integer(1);
+    for argument in arguments {
+        let Expression::Number { value } = argument else {
+            return Err(LisrEvaluationError::TypeError);
+        };
+        result = result.lcm(&value)?;
+    }
+    Ok(Expression::Number { value: result })
+}
+
+fn primitive_car(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let pair = arguments.pop();
+
+    if !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'car' function requires one 'cons' argument",
+        });
+    }
+
+    match pair {
+        Some(Expression::Cons { first, rest: _ }) => Ok(*first),
+        _ => Err(LisrEvaluationError::RuntimeError {
+            reason: "'car' requires one 'cons' argument",
+        }),
+    }
+}
+
+fn primitive_cdr(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let pair = arguments.pop();
+
+    if !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'cdr' function requires one 'cons' argument",
+        });
+    }
+
+    match pair {
+        Some(Expression::Cons { first: _, rest }) => Ok(*rest),
+        _ => Err(LisrEvaluationError::RuntimeError {
+            reason: "'cdr' requires one 'cons' argument",
+        }),
+    }
+}
+
+fn primitive_is_empty_list(
+    mut arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    let object = arguments.pop();
+
+    if !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'empty-list?' function requires exactly one argument",
+        });
+    }
+
+    match object {
+        Some(Expression::EmptyList) => Ok(Expression::True),
+        _ => Ok(Expression::False),
+    }
+}
+
+fn primitive_is_symbol(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let object = arguments.pop();
+
+    if !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'symbol?' function requires exactly one argument",
+        });
+    }
+
+    match object {
+        Some(Expression::Symbol { .. }) => Ok(Expression::True),
+        _ => Ok(Expression::False),
+    }
+}
+
+fn primitive_eq(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let right = arguments.pop();
+    let left = arguments.pop();
+
+    if !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'eq?' function requires two arguments",
+        });
+    }
+
+    match (left, right) {
+        (Some(left), Some(right)) if is_same_object(&left, &right) => Ok(Expression::True),
+        (Some(_), Some(_)) => Ok(Expression::False),
+        _ => Err(LisrEvaluationError::RuntimeError {
+            reason: "'eq?' function requires two arguments",
+        }),
+    }
+}
+
+// Symbols, booleans, numbers, the empty list and procedures can be the same
+// object. Strings and pairs are copied when they are passed around, so they are
+// never the same object.
+fn is_same_object(left: &Expression, right: &Expression) -> bool {
+    match (left, right) {
+        (Expression::Symbol { value: left }, Expression::Symbol { value: right }) => left == right,
+        (Expression::Number { value: left }, Expression::Number { value: right }) => left == right,
+        (Expression::Character { value: left }, Expression::Character { value: right }) => {
+            left == right
+        }
+        (Expression::True, Expression::True)
+        | (Expression::False, Expression::False)
+        | (Expression::EmptyList, Expression::EmptyList) => true,
+        (Expression::PrimitiveProcedure { .. }, Expression::PrimitiveProcedure { .. })
+        | (Expression::CompoundProcedure { .. }, Expression::CompoundProcedure { .. }) => {
+            left == right
+        }
+        _ => false,
+    }
+}
+
+fn primitive_symbol_to_string(
+    mut arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    let symbol = arguments.pop();
+
+    match symbol {
+        Some(Expression::Symbol { value }) if arguments.is_empty() => Ok(Expression::String {
+            value: value.name().to_string(),
+        }),
+        _ => Err(LisrEvaluationError::RuntimeError {
+            reason: "'symbol->string' function requires one symbol argument",
+        }),
+    }
+}
+
+fn primitive_string_to_symbol(
+    mut arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    let string = arguments.pop();
+
+    match string {
+        Some(Expression::String { value }) if arguments.is_empty() => Ok(Expression::Symbol {
+            value: Symbol::new(&value),
+        }),
+        _ => Err(LisrEvaluationError::RuntimeError {
+            reason: "'string->symbol' function requires one string argument",
+        }),
+    }
+}
+
+// Builds a proper list of the elements.
+fn make_list<I>(elements: I) -> Expression
+where
+    I: IntoIterator<Item = Expression>,
+    I::IntoIter: DoubleEndedIterator,
+{
+    elements
+        .into_iter()
+        .rev()
+        .fold(Expression::EmptyList, |rest, element| Expression::Cons {
+            first: Box::new(element),
+            rest: Box::new(rest),
+        })
+}
 
-fn primitive_less_than(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
-    let right = arguments.pop();
-    let left = arguments.pop();
+// The elements of a proper list, or None if it is not one.
+fn list_elements(list: Expression) -> Option<Vec<Expression>> {
+    let mut elements = Vec::new();
+    let mut list = list;
+    loop {
+        match list {
+            Expression::Cons { first, rest } => {
+                elements.push(*first);
+                list = *rest;
+            }
+            Expression::EmptyList => return Some(elements),
+            _ => return None,
+        }
+    }
+}
 
-    if !arguments.is_empty() {
+fn expect_string(argument: Option<Expression>) -> Result<String, LisrEvaluationError> {
+    match argument {
+        Some(Expression::String { value }) => Ok(value),
+        _ => Err(LisrEvaluationError::TypeError),
+    }
+}
+
+// String indices count characters rather than bytes, so that they work the
+// same for any Unicode text.
+fn expect_index(argument: Option<Expression>) -> Result<usize, LisrEvaluationError> {
+    match argument {
+        Some(Expression::Number {
+            value: Number::Integer(index),
+        }) => usize::try_from(index).map_err(|_| string_index_out_of_range()),
+        // Big integers don't fit in an integer, so they are out of range of
+        // any string.
+        Some(Expression::Number {
+            value: Number::Big(_),
+        }) => Err(string_index_out_of_range()),
+        _ => Err(LisrEvaluationError::TypeError),
+    }
+}
+
+fn string_index_out_of_range() -> LisrEvaluationError {
+    LisrEvaluationError::RuntimeError {
+        reason: "String index out of range",
+    }
+}
+
+// Numbers can be written in binary, octal, decimal or hexadecimal, which is
+// decimal if the radix is not given.
+fn expect_radix(argument: Option<Expression>) -> Result<u32, LisrEvaluationError> {
+    match argument {
+        None => Ok(10),
+        Some(Expression::Number {
+            value: Number::Integer(radix @ (2 | 8 | 10 | 16)),
+        }) => Ok(radix as u32),
+        Some(Expression::Number { .. }) => Err(LisrEvaluationError::RuntimeError {
+            reason: "A radix must be 2, 8, 10 or 16",
+        }),
+        _ => Err(LisrEvaluationError::TypeError),
+    }
+}
+
+fn primitive_is_string(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let value = arguments.pop();
+
+    if value.is_none() || !arguments.is_empty() {
         return Err(LisrEvaluationError::RuntimeError {
-            reason: "'Less than' function requires two arguments",
+            reason: "'string?' function requires one argument",
         });
     }
 
-    match (left, right) {
-        (Some(Expression::Number { value: left }), Some(Expression::Number { value: right })) => {
-            if left < right {
-                Ok(Expression::True)
-            } else {
-                Ok(Expression::False)
+    match value {
+        Some(Expression::String { .. }) => Ok(Expression::True),
+        _ => Ok(Expression::False),
+    }
+}
+
+fn primitive_string_length(
+    mut arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    let string = arguments.pop();
+
+    if string.is_none() || !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'string-length' function requires one string argument",
+        });
+    }
+
+    let length = expect_string(string)?.chars().count();
+    Ok(Expression::Number {
+        value: Number::Integer(length as i64),
+    })
+}
+
+// `(substring string start end)` takes the characters from start up to, but
+// not including, end. Without the end, it takes the rest of the string.
+fn primitive_substring(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    if !(2..=3).contains(&arguments.len()) {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'substring' function requires a string, a start and an optional end",
+        });
+    }
+
+    let mut arguments = arguments.into_iter();
+    let string = expect_string(arguments.next())?;
+    let length = string.chars().count();
+    let start = expect_index(arguments.next())?;
+    let end = match arguments.next() {
+        Some(end) => expect_index(Some(end))?,
+        None => length,
+    };
+    if start > end || end > length {
+        return Err(string_index_out_of_range());
+    }
+
+    Ok(Expression::String {
+        value: string.chars().skip(start).take(end - start).collect(),
+    })
+}
+
+fn primitive_string_ref(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let index = arguments.pop();
+    let string = arguments.pop();
+
+    if string.is_none() || !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'string-ref' function requires a string and an index",
+        });
+    }
+
+    let string = expect_string(string)?;
+    let index = expect_index(index)?;
+    match string.chars().nth(index) {
+        Some(value) => Ok(Expression::Character { value }),
+        None => Err(string_index_out_of_range()),
+    }
+}
+
+fn primitive_string_append(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let mut result = String::new();
+    for argument in arguments {
+        result.push_str(&expect_string(Some(argument))?);
+    }
+    Ok(Expression::String { value: result })
+}
+
+// Uses the full Unicode case mapping, so `(string-upcase "straße")` is
+// `"STRASSE"`.
+fn primitive_string_upcase(
+    mut arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    let string = arguments.pop();
+
+    if string.is_none() || !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'string-upcase' function requires one string argument",
+        });
+    }
+
+    Ok(Expression::String {
+        value: expect_string(string)?.to_uppercase(),
+    })
+}
+
+fn primitive_string_downcase(
+    mut arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    let string = arguments.pop();
+
+    if string.is_none() || !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'string-downcase' function requires one string argument",
+        });
+    }
+
+    Ok(Expression::String {
+        value: expect_string(string)?.to_lowercase(),
+    })
+}
+
+// `(string-index string pattern)` gives the index of the first occurrence of
+// the pattern (a string or a character), or false if there is none.
+fn primitive_string_index(
+    mut arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    let pattern = arguments.pop();
+    let string = arguments.pop();
+
+    if string.is_none() || !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'string-index' function requires a string and a pattern",
+        });
+    }
+
+    let string = expect_string(string)?;
+    let pattern = match pattern {
+        Some(Expression::Character { value }) => value.to_string(),
+        pattern => expect_string(pattern)?,
+    };
+    match string.find(&pattern) {
+        Some(byte_index) => Ok(Expression::Number {
+            value: Number::Integer(string[..byte_index].chars().count() as i64),
+        }),
+        None => Ok(Expression::False),
+    }
+}
+
+// `(string-split string separator)` gives a list of the parts between the
+// separators. Without a separator, the string is split at whitespace.
+fn primitive_string_split(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    if !(1..=2).contains(&arguments.len()) {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'string-split' function requires a string and an optional separator",
+        });
+    }
+
+    let mut arguments = arguments.into_iter();
+    let string = expect_string(arguments.next())?;
+    let parts: Vec<&str> = match arguments.next() {
+        Some(separator) => {
+            let separator = expect_string(Some(separator))?;
+            if separator.is_empty() {
+                return Err(LisrEvaluationError::RuntimeError {
+                    reason: "'string-split' separator cannot be empty",
+                });
             }
+            string.split(separator.as_str()).collect()
         }
-        (Some(Expression::String { value: left }), Some(Expression::String { value: right })) => {
-            if left < right {
-                Ok(Expression::True)
-            } else {
-                Ok(Expression::False)
-            }
+        None => string.split_whitespace().collect(),
+    };
+
+    Ok(make_list(parts.into_iter().map(|part| {
+        Expression::String {
+            value: part.to_string(),
         }
-        _ => Err(LisrEvaluationError::RuntimeError {
-            reason: "Equals is only implemented for pairs of strings or numbers",
-        }),
+    })))
+}
+
+// `(string-join strings separator)` puts the separator between the strings of
+// the list. The separator is a space if it is not given.
+fn primitive_string_join(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    if !(1..=2).contains(&arguments.len()) {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'string-join' function requires a list of strings and an optional separator",
+        });
     }
+
+    let mut arguments = arguments.into_iter();
+    let Some(elements) = arguments.next().and_then(list_elements) else {
+        return Err(LisrEvaluationError::TypeError);
+    };
+    let separator = match arguments.next() {
+        Some(separator) => expect_string(Some(separator))?,
+        None => " ".to_string(),
+    };
+    let strings = elements
+        .into_iter()
+        .map(|element| expect_string(Some(element)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Expression::String {
+        value: strings.join(&separator),
+    })
 }
 
-fn primitive_car(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
-    let pair = arguments.pop();
+// Reads the number like the scanner does, so `(string->number "#x1F")` works.
+// Gives false if the string is not a number.
+fn primitive_string_to_number(
+    arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    if !(1..=2).contains(&arguments.len()) {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'string->number' function requires a string and an optional radix",
+        });
+    }
 
-    if !arguments.is_empty() {
+    let mut arguments = arguments.into_iter();
+    let string = expect_string(arguments.next())?;
+    let prefix = match expect_radix(arguments.next())? {
+        2 => "#b",
+        8 => "#o",
+        16 => "#x",
+        _ => "",
+    };
+    match parse_number(&format!("{prefix}{string}")) {
+        Ok(value) => Ok(Expression::Number { value }),
+        Err(_) => Ok(Expression::False),
+    }
+}
+
+fn primitive_number_to_string(
+    arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    if !(1..=2).contains(&arguments.len()) {
         return Err(LisrEvaluationError::RuntimeError {
-            reason: "'car' function requires one 'cons' argument",
+            reason: "'number->string' function requires a number and an optional radix",
         });
     }
 
-    match pair {
-        Some(Expression::Cons { first, rest: _ }) => Ok(*first),
-        _ => Err(LisrEvaluationError::RuntimeError {
-            reason: "'car' requires one 'cons' argument",
+    let mut arguments = arguments.into_iter();
+    let Some(Expression::Number { value }) = arguments.next() else {
+        return Err(LisrEvaluationError::TypeError);
+    };
+    match value.to_string_radix(expect_radix(arguments.next())?) {
+        Some(string) => Ok(Expression::String { value: string }),
+        None => Err(LisrEvaluationError::RuntimeError {
+            reason: "Only exact numbers can be written in a radix other than 10",
         }),
     }
 }
 
-fn primitive_cdr(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
-    let pair = arguments.pop();
+fn primitive_string_to_list(
+    mut arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    let string = arguments.pop();
 
-    if !arguments.is_empty() {
+    if string.is_none() || !arguments.is_empty() {
         return Err(LisrEvaluationError::RuntimeError {
-            reason: "'cdr' function requires one 'cons' argument",
+            reason: "'string->list' function requires one string argument",
         });
     }
 
-    match pair {
-        Some(Expression::Cons { first: _, rest }) => Ok(*rest),
-        _ => Err(LisrEvaluationError::RuntimeError {
-            reason: "'cdr' requires one 'cons' argument",
+    Ok(make_list(
+        expect_string(string)?
+            .chars()
+            .map(|value| Expression::Character { value }),
+    ))
+}
+
+// The functions below compare strings only, by their characters. Each adjacent
+// pair of arguments must be in order, like in `(string<? "a" "b" "c")`.
+fn compare_strings(
+    arguments: Vec<Expression>,
+    is_in_order: fn(Ordering) -> bool,
+    reason: &'static str,
+) -> Result<Expression, LisrEvaluationError> {
+    if arguments.len() < 2 {
+        return Err(LisrEvaluationError::RuntimeError { reason });
+    }
+    let strings = arguments
+        .into_iter()
+        .map(|argument| expect_string(Some(argument)))
+        .collect::<Result<Vec<_>, _>>()?;
+    if strings
+        .windows(2)
+        .all(|pair| is_in_order(pair[0].cmp(&pair[1])))
+    {
+        Ok(Expression::True)
+    } else {
+        Ok(Expression::False)
+    }
+}
+
+fn primitive_string_equals(arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    compare_strings(
+        arguments,
+        Ordering::is_eq,
+        "'string=?' function requires at least two arguments",
+    )
+}
+
+fn primitive_string_less_than(
+    arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    compare_strings(
+        arguments,
+        Ordering::is_lt,
+        "'string<?' function requires at least two arguments",
+    )
+}
+
+fn primitive_string_greater_than(
+    arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    compare_strings(
+        arguments,
+        Ordering::is_gt,
+        "'string>?' function requires at least two arguments",
+    )
+}
+
+fn expect_char(argument: Option<Expression>) -> Result<char, LisrEvaluationError> {
+    match argument {
+        Some(Expression::Character { value }) => Ok(value),
+        _ => Err(LisrEvaluationError::TypeError),
+    }
+}
+
+fn primitive_is_char(mut arguments: Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
+    let value = arguments.pop();
+
+    if value.is_none() || !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'char?' function requires one argument",
+        });
+    }
+
+    match value {
+        Some(Expression::Character { .. }) => Ok(Expression::True),
+        _ => Ok(Expression::False),
+    }
+}
+
+// Gives the Unicode code point of the character.
+fn primitive_char_to_integer(
+    mut arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    let char = arguments.pop();
+
+    if char.is_none() || !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'char->integer' function requires one character argument",
+        });
+    }
+
+    Ok(Expression::Number {
+        value: Number::Integer(expect_char(char)? as i64),
+    })
+}
+
+fn primitive_integer_to_char(
+    mut arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    let code_point = arguments.pop();
+
+    if code_point.is_none() || !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'integer->char' function requires one integer argument",
+        });
+    }
+
+    let Some(Expression::Number { value }) = code_point else {
+        return Err(LisrEvaluationError::TypeError);
+    };
+    let Number::Integer(code_point) = value else {
+        return Err(LisrEvaluationError::TypeError);
+    };
+    match u32::try_from(code_point).ok().and_then(char::from_u32) {
+        Some(value) => Ok(Expression::Character { value }),
+        None => Err(LisrEvaluationError::RuntimeError {
+            reason: "'integer->char' requires a Unicode code point",
         }),
     }
 }
 
-fn primitive_is_empty_list(
+// Characters whose case mapping has more than one character, like `ß`, are
+// left as they are.
+fn change_case<I: Iterator<Item = char>>(char: char, mut mapped: I) -> char {
+    match (mapped.next(), mapped.next()) {
+        (Some(mapped), None) => mapped,
+        _ => char,
+    }
+}
+
+fn primitive_char_upcase(
     mut arguments: Vec<Expression>,
 ) -> Result<Expression, LisrEvaluationError> {
-    let object = arguments.pop();
+    let char = arguments.pop();
 
-    if !arguments.is_empty() {
+    if char.is_none() || !arguments.is_empty() {
         return Err(LisrEvaluationError::RuntimeError {
-            reason: "'empty-list?' function requires exactly one argument",
+            reason: "'char-upcase' function requires one character argument",
         });
     }
 
-    match object {
-        Some(Expression::EmptyList) => Ok(Expression::True),
-        _ => Ok(Expression::False),
+    let char = expect_char(char)?;
+    Ok(Expression::Character {
+        value: change_case(char, char.to_uppercase()),
+    })
+}
+
+fn primitive_char_downcase(
+    mut arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    let char = arguments.pop();
+
+    if char.is_none() || !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'char-downcase' function requires one character argument",
+        });
+    }
+
+    let char = expect_char(char)?;
+    Ok(Expression::Character {
+        value: change_case(char, char.to_lowercase()),
+    })
+}
+
+fn primitive_is_char_alphabetic(
+    mut arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    let char = arguments.pop();
+
+    if char.is_none() || !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'char-alphabetic?' function requires one character argument",
+        });
+    }
+
+    if expect_char(char)?.is_alphabetic() {
+        Ok(Expression::True)
+    } else {
+        Ok(Expression::False)
     }
 }
 
+fn primitive_is_char_numeric(
+    mut arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    let char = arguments.pop();
+
+    if char.is_none() || !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'char-numeric?' function requires one character argument",
+        });
+    }
+
+    if expect_char(char)?.is_numeric() {
+        Ok(Expression::True)
+    } else {
+        Ok(Expression::False)
+    }
+}
+
+fn primitive_is_char_whitespace(
+    mut arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    let char = arguments.pop();
+
+    if char.is_none() || !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'char-whitespace?' function requires one character argument",
+        });
+    }
+
+    if expect_char(char)?.is_whitespace() {
+        Ok(Expression::True)
+    } else {
+        Ok(Expression::False)
+    }
+}
+
+// The opposite of `string->list`.
+fn primitive_list_to_string(
+    mut arguments: Vec<Expression>,
+) -> Result<Expression, LisrEvaluationError> {
+    let list = arguments.pop();
+
+    if list.is_none() || !arguments.is_empty() {
+        return Err(LisrEvaluationError::RuntimeError {
+            reason: "'list->string' function requires one list of characters",
+        });
+    }
+
+    let Some(elements) = list.and_then(list_elements) else {
+        return Err(LisrEvaluationError::TypeError);
+    };
+    Ok(Expression::String {
+        value: elements
+            .into_iter()
+            .map(|element| expect_char(Some(element)))
+            .collect::<Result<String, _>>()?,
+    })
+}
+
 // Creates a function that can apply a primitive reducer to a sequence of
 // Expressions accumulating the result.
 fn create_primitive_procedure(
@@ -416,7 +2483,9 @@ fn create_primitive_procedure(
 ) -> impl Fn(Vec<Expression>) -> Result<Expression, LisrEvaluationError> {
     move |arguments: Vec<Expression>| -> Result<Expression, LisrEvaluationError> {
         let Some(result) = arguments.into_iter().try_reduce(reducer)? else {
-            return Err(LisrEvaluationError::RuntimeError { reason: "An arithmetic function cannot be invoked without any arguments" })
+            return Err(LisrEvaluationError::RuntimeError {
+                reason: "An arithmetic function cannot be invoked without any arguments",
+            });
         };
         Ok(result)
     }
@@ -426,7 +2495,7 @@ fn primitive_add(a: Expression, b: Expression) -> Result<Expression, LisrEvaluat
     match (a, b) {
         (Expression::Number { value: augend }, Expression::Number { value: addend }) => {
             Ok(Expression::Number {
-                value: augend + addend,
+                value: augend.add(&addend),
             })
         }
         (Expression::String { value: a }, Expression::String { value: b }) => {
@@ -440,7 +2509,7 @@ fn primitive_subtract(a: Expression, b: Expression) -> Result<Expression, LisrEv
     match (a, b) {
         (Expression::Number { value: minuend }, Expression::Number { value: subtrahend }) => {
             Ok(Expression::Number {
-                value: minuend - subtrahend,
+                value: minuend.subtract(&subtrahend),
             })
         }
         _ => Err(LisrEvaluationError::TypeError),
@@ -455,7 +2524,7 @@ fn primitive_multiply(a: Expression, b: Expression) -> Result<Expression, LisrEv
                 value: multiplicand,
             },
         ) => Ok(Expression::Number {
-            value: multiplier * multiplicand,
+            value: multiplier.multiply(&multiplicand),
         }),
         _ => Err(LisrEvaluationError::TypeError),
     }
@@ -464,7 +2533,9 @@ fn primitive_multiply(a: Expression, b: Expression) -> Result<Expression, LisrEv
 fn primitive_divide(a: Expression, b: Expression) -> Result<Expression, LisrEvaluationError> {
     match (a, b) {
         (Expression::Number { value: a }, Expression::Number { value: b }) => {
-            Ok(Expression::Number { value: a / b })
+            Ok(Expression::Number {
+                value: a.divide(&b)?,
+            })
         }
         _ => Err(LisrEvaluationError::TypeError),
     }
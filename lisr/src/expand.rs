@@ -0,0 +1,1635 @@
+// Expands macros defined with `define-syntax`, `let-syntax`, `letrec-syntax`
+// and `define-macro` before the nodes are translated to expressions.
+//
+// Macros defined with `define-macro` are procedures that get the code they are
+// used with as data and return the code to replace the use with. Unlike macros
+// of `syntax-rules`, they are not hygienic.
+//
+// Macros of `syntax-rules` are hygienic: identifiers introduced by a template
+// are renamed to `name mark`, where the mark is unique for every use of a macro.
+// Once a form is expanded, renamed identifiers that are bound inside of it (like
+// a temporary variable of the template) keep their new names, so they never
+// capture the variables of the user. The rest get their names back, so they
+// refer to the definitions seen where the macro is defined (like `+` or
+// `display`). Variables of the user that are bound between the definition of
+// the macro and its use would capture them, so these variables are renamed
+// instead.
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    rc::Rc,
+};
+
+use crate::{
+    environment::Environment,
+    evaluate::{call_procedure, create_global_environment, evaluate_in_environment},
+    expression::{Expression, LetKind},
+    node::Node,
+    parse::LisrParseError,
+    span::{Span, Spanned},
+    token::Token,
+    translate::{translate, translate_datum, untranslate_datum},
+};
+
+const ELLIPSIS: &str = "...";
+const WILDCARD: &str = "_";
+// Identifiers cannot contain spaces, so renamed identifiers never clash with
+// the ones written by hand. Symbols with spaces made by `define-macro` are not
+// turned into code.
+const MARK_SEPARATOR: char = ' ';
+
+pub fn expand(nodes: Vec<Node>) -> Result<Vec<Node>, Spanned<LisrParseError>> {
+    Expander::new().expand(nodes)
+}
+
+#[derive(Clone)]
+enum Macro {
+    SyntaxRules(Rc<SyntaxRules>),
+    Procedure(Expression),
+}
+
+#[derive(Default)]
+struct Scope {
+    // A name mapped to None is a variable that shadows a macro with the same
+    // name.
+    bindings: HashMap<String, Option<Macro>>,
+    // Variables that would capture identifiers introduced by macros defined
+    // outside of the scope. They are renamed once the scope is expanded.
+    captured: HashSet<String>,
+}
+
+// New names for the captured variables of a scope.
+struct Renaming {
+    names: HashMap<String, String>,
+    // Marks of the macros defined inside of the scope. Their identifiers refer
+    // to the variables of the scope too.
+    inner_marks: HashSet<String>,
+}
+
+// Keeps the global macros between calls to `expand`.
+pub struct Expander {
+    // The innermost scope is the last one.
+    scopes: Vec<Scope>,
+    next_mark: usize,
+    // The index of the scope the macro of every mark is defined in.
+    macro_scopes: HashMap<usize, usize>,
+    // Procedures of `define-macro` are evaluated and called in this environment.
+    environment: Environment,
+}
+
+struct SyntaxRules {
+    literals: Vec<Token>,
+    // Patterns (without the keyword of the macro) and their templates.
+    rules: Vec<(VecDeque<Node>, Node)>,
+}
+
+// Forms matched by a pattern variable. Variables followed by an ellipsis match
+// many forms.
+#[derive(Clone)]
+enum Matched {
+    One(Node),
+    Many(Vec<Matched>),
+}
+
+type Matches = HashMap<String, Matched>;
+
+impl Expander {
+    pub fn new() -> Expander {
+        Expander::with_environment(create_global_environment())
+    }
+
+    pub fn with_environment(environment: Environment) -> Expander {
+        Expander {
+            scopes: vec![Scope::default()],
+            next_mark: 0,
+            macro_scopes: HashMap::new(),
+            environment,
+        }
+    }
+
+    pub fn expand(&mut self, nodes: Vec<Node>) -> Result<Vec<Node>, Spanned<LisrParseError>> {
+        nodes
+            .into_iter()
+            .map(|node| self.expand_form(node))
+            .collect()
+    }
+
+    // Expands a single top-level form.
+    pub fn expand_form(&mut self, node: Node) -> Result<Node, Spanned<LisrParseError>> {
+        self.expand_node(node).map(resolve_marks)
+    }
+
+    fn expand_node(&mut self, node: Node) -> Result<Node, Spanned<LisrParseError>> {
+        let Node::List { elements, span } = node else {
+            return Ok(node);
+        };
+        let Some(Node::Leaf { token, .. }) = elements.front() else {
+            return self.expand_list(elements, 0, span);
+        };
+
+        match token {
+            Token::Identifier { name } => match self.lookup_macro(name) {
+                Some((Macro::SyntaxRules(syntax_rules), scope_index)) => {
+                    let expansion =
+                        self.expand_macro(&syntax_rules, scope_index, elements, span)?;
+                    self.expand_node(expansion)
+                }
+                Some((Macro::Procedure(procedure), _)) => {
+                    let expansion = self.call_macro(procedure, elements, span)?;
+                    self.expand_node(expansion)
+                }
+                None => self.expand_list(elements, 0, span),
+            },
+            Token::Quote => Ok(Node::List { elements, span }),
+            Token::Quasiquote => self.expand_template(elements, 0, span),
+            Token::DefineSyntax => self.define_syntax(elements, span),
+            Token::DefineMacro => self.define_procedure_macro(elements, span),
+            Token::LetSyntax | Token::LetrecSyntax => self.let_syntax(elements, span),
+            Token::SyntaxRules => Err(Spanned::new(LisrParseError::MalformedSyntaxRules, span)),
+            Token::Lambda => {
+                let variables = elements.get(1).map(bound_identifiers).unwrap_or_default();
+                let (elements, captured) =
+                    self.in_scope(variables, |expander| expander.expand_elements(elements, 2))?;
+                let elements = self.renaming(captured).rename_elements(elements, 1);
+                Ok(Node::List { elements, span })
+            }
+            Token::Define => self.expand_definition(elements, span),
+            Token::Let => self.expand_let(elements, LetKind::Let, span),
+            Token::LetStar => self.expand_let(elements, LetKind::LetStar, span),
+            Token::Letrec => self.expand_let(elements, LetKind::Letrec, span),
+            Token::LetrecStar => self.expand_let(elements, LetKind::LetrecStar, span),
+            Token::Cond => {
+                let mut elements = elements;
+                let clauses = elements.split_off(elements.len().min(1));
+                elements.extend(self.expand_clauses(clauses, 0)?);
+                Ok(Node::List { elements, span })
+            }
+            // The data of case clauses are not expressions.
+            Token::Case => {
+                let mut elements = elements;
+                let clauses = elements.split_off(elements.len().min(2));
+                let mut elements = self.expand_elements(elements, 1)?;
+                elements.extend(self.expand_clauses(clauses, 1)?);
+                Ok(Node::List { elements, span })
+            }
+            _ => self.expand_list(elements, 0, span),
+        }
+    }
+
+    // Expands the elements from `start` on.
+    fn expand_elements(
+        &mut self,
+        mut elements: VecDeque<Node>,
+        start: usize,
+    ) -> Result<VecDeque<Node>, Spanned<LisrParseError>> {
+        let rest = elements.split_off(elements.len().min(start));
+        for node in rest {
+            elements.push_back(self.expand_node(node)?);
+        }
+        Ok(elements)
+    }
+
+    fn expand_list(
+        &mut self,
+        elements: VecDeque<Node>,
+        start: usize,
+        span: Span,
+    ) -> Result<Node, Spanned<LisrParseError>> {
+        Ok(Node::List {
+            elements: self.expand_elements(elements, start)?,
+            span,
+        })
+    }
+
+    // Expands only the unquoted parts of a quasiquote template. `depth` counts
+    // the quasiquotes the list is nested in.
+    fn expand_template(
+        &mut self,
+        elements: VecDeque<Node>,
+        depth: usize,
+        span: Span,
+    ) -> Result<Node, Spanned<LisrParseError>> {
+        let depth = match elements.front() {
+            Some(Node::Leaf {
+                token: Token::Quasiquote,
+                ..
+            }) => depth + 1,
+            Some(Node::Leaf {
+                token: Token::Unquote | Token::UnquoteSplicing,
+                ..
+            }) if depth == 1 => return self.expand_list(elements, 1, span),
+            Some(Node::Leaf {
+                token: Token::Unquote | Token::UnquoteSplicing,
+                ..
+            }) => depth - 1,
+            _ => depth,
+        };
+
+        let elements = elements
+            .into_iter()
+            .map(|element| match element {
+                Node::List { elements, span } => self.expand_template(elements, depth, span),
+                leaf => Ok(leaf),
+            })
+            .collect::<Result<VecDeque<Node>, Spanned<LisrParseError>>>()?;
+        Ok(Node::List { elements, span })
+    }
+
+    // Expands the elements of every clause from `start` on.
+    fn expand_clauses(
+        &mut self,
+        clauses: VecDeque<Node>,
+        start: usize,
+    ) -> Result<VecDeque<Node>, Spanned<LisrParseError>> {
+        clauses
+            .into_iter()
+            .map(|clause| match clause {
+                Node::List { elements, span } => self.expand_list(elements, start, span),
+                clause => Ok(clause),
+            })
+            .collect()
+    }
+
+    // Runs `expand` in a new scope with the variables. Also returns the
+    // variables that have to be renamed, because they would capture identifiers
+    // introduced by macros.
+    fn in_scope<T>(
+        &mut self,
+        variables: Vec<String>,
+        expand: impl FnOnce(&mut Self) -> Result<T, Spanned<LisrParseError>>,
+    ) -> Result<(T, HashSet<String>), Spanned<LisrParseError>> {
+        self.with_scope(variables_scope(variables), expand)
+    }
+
+    fn with_scope<T>(
+        &mut self,
+        scope: Scope,
+        expand: impl FnOnce(&mut Self) -> Result<T, Spanned<LisrParseError>>,
+    ) -> Result<(T, HashSet<String>), Spanned<LisrParseError>> {
+        self.scopes.push(scope);
+        let result = expand(self);
+        let scope = self.scopes.pop().expect("the scope was just pushed");
+        Ok((result?, scope.captured))
+    }
+
+    // Gives new names to the captured variables of the scope that was just
+    // left. The names are marked like the identifiers introduced by a macro, so
+    // they never clash with the ones written by hand.
+    fn renaming(&mut self, captured: HashSet<String>) -> Renaming {
+        let scope_index = self.scopes.len();
+        let mut names = HashMap::new();
+        for variable in captured {
+            let name = format!("{}{}{}", variable, MARK_SEPARATOR, self.next_mark);
+            self.next_mark += 1;
+            names.insert(variable, name);
+        }
+        let inner_marks = if names.is_empty() {
+            HashSet::new()
+        } else {
+            self.macro_scopes
+                .iter()
+                .filter(|(_, &index)| index >= scope_index)
+                .map(|(mark, _)| mark.to_string())
+                .collect()
+        };
+        Renaming { names, inner_marks }
+    }
+
+    fn expand_definition(
+        &mut self,
+        elements: VecDeque<Node>,
+        span: Span,
+    ) -> Result<Node, Spanned<LisrParseError>> {
+        let mut variables = elements.get(1).map(bound_identifiers).unwrap_or_default();
+        if variables.is_empty() {
+            return self.expand_list(elements, 1, span);
+        }
+        let variable = variables.remove(0);
+        self.shadow(variable);
+        match elements.get(1) {
+            // The parameters of a procedure are visible in its body only.
+            Some(Node::List { .. }) => {
+                let (mut elements, captured) =
+                    self.in_scope(variables, |expander| expander.expand_elements(elements, 2))?;
+                let renaming = self.renaming(captured);
+                if let Some(Node::List {
+                    elements: signature,
+                    ..
+                }) = elements.get_mut(1)
+                {
+                    *signature = renaming.rename_elements(std::mem::take(signature), 1);
+                }
+                Ok(Node::List {
+                    elements: renaming.rename_elements(elements, 2),
+                    span,
+                })
+            }
+            _ => self.expand_list(elements, 2, span),
+        }
+    }
+
+    fn expand_let(
+        &mut self,
+        mut elements: VecDeque<Node>,
+        kind: LetKind,
+        span: Span,
+    ) -> Result<Node, Spanned<LisrParseError>> {
+        let mut variables = Vec::new();
+        let mut bindings_index = 1;
+        // Named let.
+        if let Some(Node::Leaf {
+            token: Token::Identifier { name },
+            ..
+        }) = elements.get(1)
+        {
+            variables.push(name.clone());
+            bindings_index = 2;
+        }
+
+        let mut captured = HashSet::new();
+        // The variables that every value sees.
+        let mut value_scopes = Vec::new();
+        if let Some(Node::List {
+            elements: bindings, ..
+        }) = elements.get_mut(bindings_index)
+        {
+            let names: Vec<Option<String>> = bindings
+                .iter()
+                .map(|binding| match binding {
+                    Node::List { elements, .. } => match elements.front() {
+                        Some(Node::Leaf {
+                            token: Token::Identifier { name },
+                            ..
+                        }) => Some(name.clone()),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect();
+
+            for (index, binding) in std::mem::take(bindings).into_iter().enumerate() {
+                let scope: Vec<String> = match kind {
+                    LetKind::Let => Vec::new(),
+                    // Values of let* see the variables bound before them.
+                    LetKind::LetStar => names[..index].iter().flatten().cloned().collect(),
+                    // Values of letrec see all the variables it binds.
+                    LetKind::Letrec | LetKind::LetrecStar => {
+                        names.iter().flatten().cloned().collect()
+                    }
+                };
+                let (binding, binding_captured) =
+                    self.in_scope(scope.clone(), |expander| match binding {
+                        Node::List { elements, span } => expander.expand_list(elements, 1, span),
+                        binding => Ok(binding),
+                    })?;
+                bindings.push_back(binding);
+                captured.extend(binding_captured);
+                value_scopes.push(scope);
+            }
+            variables.extend(names.into_iter().flatten());
+        }
+
+        let (elements, body_captured) = self.in_scope(variables, |expander| {
+            expander.expand_elements(elements, bindings_index + 1)
+        })?;
+        captured.extend(body_captured);
+
+        // Values only get the new names of the variables they see.
+        let renaming = self.renaming(captured);
+        let elements = elements
+            .into_iter()
+            .enumerate()
+            .map(|(index, element)| match element {
+                Node::List {
+                    elements: bindings,
+                    span,
+                } if index == bindings_index => Node::List {
+                    elements: bindings
+                        .into_iter()
+                        .zip(&value_scopes)
+                        .map(|(binding, scope)| match binding {
+                            Node::List { elements, span } => {
+                                let value_renaming = renaming.only(scope);
+                                let elements = elements
+                                    .into_iter()
+                                    .enumerate()
+                                    .map(|(index, node)| match index {
+                                        0 => renaming.rename(node),
+                                        _ => value_renaming.rename(node),
+                                    })
+                                    .collect();
+                                Node::List { elements, span }
+                            }
+                            binding => binding,
+                        })
+                        .collect(),
+                    span,
+                },
+                element if index > 0 => renaming.rename(element),
+                element => element,
+            })
+            .collect();
+        Ok(Node::List { elements, span })
+    }
+
+    // `(define-syntax name (syntax-rules ...))` defines the macro in the current
+    // scope and becomes `'name`, just like a definition evaluates to its name.
+    fn define_syntax(
+        &mut self,
+        elements: VecDeque<Node>,
+        span: Span,
+    ) -> Result<Node, Spanned<LisrParseError>> {
+        let (
+            Some(Node::Leaf {
+                token: Token::Identifier { name },
+                span: name_span,
+            }),
+            Some(syntax_rules),
+            true,
+        ) = (
+            elements.get(1).cloned(),
+            elements.get(2),
+            elements.len() == 3,
+        )
+        else {
+            return Err(Spanned::new(
+                LisrParseError::DefineSyntaxRequiresNameAndRules,
+                span,
+            ));
+        };
+        let syntax_rules = parse_syntax_rules(syntax_rules)?;
+        self.define_macro(name.clone(), Macro::SyntaxRules(Rc::new(syntax_rules)));
+
+        Ok(quote_name(name, name_span, span))
+    }
+
+    // `(define-macro (name parameter ...) body ...)` and `(define-macro name
+    // procedure)` define the macro in the current scope and become `'name`.
+    fn define_procedure_macro(
+        &mut self,
+        mut elements: VecDeque<Node>,
+        span: Span,
+    ) -> Result<Node, Spanned<LisrParseError>> {
+        let malformed_define_macro =
+            || Spanned::new(LisrParseError::DefineMacroRequiresNameAndProcedure, span);
+        elements.pop_front();
+        let (name, procedure) = match elements.pop_front() {
+            // Like `(define (name parameter ...) body ...)`, it is a shorthand for
+            // a lambda.
+            Some(Node::List {
+                elements: mut signature,
+                span: signature_span,
+            }) if !elements.is_empty() => {
+                let name = signature.pop_front().ok_or_else(malformed_define_macro)?;
+                elements.push_front(Node::List {
+                    elements: signature,
+                    span: signature_span,
+                });
+                elements.push_front(Node::Leaf {
+                    token: Token::Lambda,
+                    span,
+                });
+                (name, Node::List { elements, span })
+            }
+            Some(name) if elements.len() == 1 => (name, elements.pop_front().unwrap()),
+            _ => return Err(malformed_define_macro()),
+        };
+        let Node::Leaf {
+            token: Token::Identifier { name },
+            span: name_span,
+        } = name
+        else {
+            return Err(malformed_define_macro());
+        };
+
+        // The procedure can use macros defined before it.
+        let procedure = self.expand_form(procedure)?;
+        let procedure = evaluate_in_environment(translate([procedure])?, &self.environment)
+            .map_err(|error| {
+                Spanned::new(
+                    LisrParseError::MacroFailed {
+                        name: name.clone(),
+                        error: error.value,
+                    },
+                    error.span,
+                )
+            })?;
+        self.define_macro(name.clone(), Macro::Procedure(procedure));
+
+        Ok(quote_name(name, name_span, span))
+    }
+
+    // `(let-syntax ((name (syntax-rules ...)) ...) body ...)` becomes
+    // `(let () body ...)` with the macros expanded in the body.
+    fn let_syntax(
+        &mut self,
+        mut elements: VecDeque<Node>,
+        span: Span,
+    ) -> Result<Node, Spanned<LisrParseError>> {
+        let malformed_let_syntax =
+            || Spanned::new(LisrParseError::LetSyntaxRequiresBindingsAndBody, span);
+        let (
+            Some(keyword),
+            Some(Node::List {
+                elements: bindings, ..
+            }),
+        ) = (elements.pop_front(), elements.pop_front())
+        else {
+            return Err(malformed_let_syntax());
+        };
+        if elements.is_empty() {
+            return Err(malformed_let_syntax());
+        }
+
+        let mut scope = Scope::default();
+        for binding in bindings {
+            let binding_span = binding.span();
+            let Node::List {
+                elements: binding, ..
+            } = binding
+            else {
+                return Err(Spanned::new(
+                    LisrParseError::BindingRequiresVariableAndValue,
+                    binding_span,
+                ));
+            };
+            match (binding.front(), binding.get(1), binding.len()) {
+                (
+                    Some(Node::Leaf {
+                        token: Token::Identifier { name },
+                        ..
+                    }),
+                    Some(syntax_rules),
+                    2,
+                ) => {
+                    scope.bindings.insert(
+                        name.clone(),
+                        Some(Macro::SyntaxRules(Rc::new(parse_syntax_rules(
+                            syntax_rules,
+                        )?))),
+                    );
+                }
+                _ => {
+                    return Err(Spanned::new(
+                        LisrParseError::BindingRequiresVariableAndValue,
+                        binding_span,
+                    ))
+                }
+            }
+        }
+
+        elements.push_front(Node::List {
+            elements: VecDeque::new(),
+            span,
+        });
+        elements.push_front(Node::Leaf {
+            token: Token::Let,
+            span: keyword.span(),
+        });
+        let (elements, captured) =
+            self.with_scope(scope, |expander| expander.expand_elements(elements, 2))?;
+        let elements = self.renaming(captured).rename_elements(elements, 2);
+        Ok(Node::List { elements, span })
+    }
+
+    fn define_macro(&mut self, name: String, definition: Macro) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.bindings.insert(name, Some(definition));
+        }
+    }
+
+    // A variable defined in the current scope hides a macro with the same name.
+    fn shadow(&mut self, variable: String) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.bindings.insert(variable, None);
+        }
+    }
+
+    // Returns the macro and the index of the scope it is defined in.
+    // Identifiers introduced by a macro refer to macros by their original name,
+    // as they are seen where the macro is defined.
+    fn lookup_macro(&self, name: &str) -> Option<(Macro, usize)> {
+        let lookup = |name: &str, scopes: &[Scope]| {
+            scopes.iter().enumerate().rev().find_map(|(index, scope)| {
+                scope
+                    .bindings
+                    .get(name)
+                    .map(|definition| definition.clone().map(|definition| (definition, index)))
+            })
+        };
+        match lookup(name, &self.scopes) {
+            Some(definition) => definition,
+            None => {
+                let scopes = match self.macro_scope(name) {
+                    Some(index) => &self.scopes[..=index],
+                    None => &self.scopes[..],
+                };
+                lookup(original_name(name), scopes).flatten()
+            }
+        }
+    }
+
+    // The index of the scope of the macro that introduced the identifier.
+    fn macro_scope(&self, name: &str) -> Option<usize> {
+        let (_, mark) = name.rsplit_once(MARK_SEPARATOR)?;
+        let index = self.macro_scopes.get(&mark.parse().ok()?)?;
+        Some((*index).min(self.scopes.len() - 1))
+    }
+
+    // Variables bound between the definition of a macro and its use would
+    // capture the identifiers the macro introduces, so they get new names.
+    fn capture_variables(&mut self, expansion: &Node, mark: usize, scope_index: usize) {
+        let mark = mark.to_string();
+        for name in template_identifiers(expansion) {
+            if name.rsplit_once(MARK_SEPARATOR).map(|(_, mark)| mark) != Some(mark.as_str()) {
+                continue;
+            }
+            let name = original_name(name);
+            for scope in self.scopes.iter_mut().skip(scope_index + 1) {
+                if let Some(None) = scope.bindings.get(name) {
+                    scope.captured.insert(String::from(name));
+                }
+            }
+        }
+    }
+
+    fn expand_macro(
+        &mut self,
+        syntax_rules: &SyntaxRules,
+        scope_index: usize,
+        mut elements: VecDeque<Node>,
+        span: Span,
+    ) -> Result<Node, Spanned<LisrParseError>> {
+        let Some(Node::Leaf {
+            token: Token::Identifier { name },
+            ..
+        }) = elements.pop_front()
+        else {
+            unreachable!("a macro is used by its name");
+        };
+        let forms: Vec<&Node> = elements.iter().collect();
+
+        for (pattern, template) in &syntax_rules.rules {
+            let patterns: Vec<&Node> = pattern.iter().collect();
+            let mut matches = Matches::new();
+            if syntax_rules.match_list(&patterns, &forms, span, &mut matches) {
+                let mark = self.next_mark;
+                self.next_mark += 1;
+                self.macro_scopes.insert(mark, scope_index);
+                let expansion = instantiate(template, &matches, mark, span)?;
+                self.capture_variables(&expansion, mark, scope_index);
+                return Ok(expansion);
+            }
+        }
+
+        Err(Spanned::new(
+            LisrParseError::NoMatchingSyntaxRule {
+                name: String::from(original_name(&name)),
+            },
+            span,
+        ))
+    }
+
+    // Calls the procedure of the macro with the forms it is used with as data and
+    // turns the returned data back into code.
+    fn call_macro(
+        &mut self,
+        procedure: Expression,
+        mut elements: VecDeque<Node>,
+        span: Span,
+    ) -> Result<Node, Spanned<LisrParseError>> {
+        let Some(Node::Leaf {
+            token: Token::Identifier { name },
+            ..
+        }) = elements.pop_front()
+        else {
+            unreachable!("a macro is used by its name");
+        };
+        let arguments = elements
+            .into_iter()
+            .map(translate_datum)
+            .collect::<Result<Vec<Expression>, Spanned<LisrParseError>>>()?;
+
+        let expansion = call_procedure(procedure, arguments, span).map_err(|error| {
+            Spanned::new(
+                LisrParseError::MacroFailed {
+                    name: String::from(original_name(&name)),
+                    error: error.value,
+                },
+                error.span,
+            )
+        })?;
+        untranslate_datum(expansion, span)
+    }
+}
+
+impl Default for Expander {
+    fn default() -> Self {
+        Expander::new()
+    }
+}
+
+impl Renaming {
+    // The new names of the variables only.
+    fn only(&self, variables: &[String]) -> Renaming {
+        Renaming {
+            names: self
+                .names
+                .iter()
+                .filter(|(variable, _)| variables.contains(variable))
+                .map(|(variable, name)| (variable.clone(), name.clone()))
+                .collect(),
+            inner_marks: self.inner_marks.clone(),
+        }
+    }
+
+    // Renames the elements from `start` on.
+    fn rename_elements(&self, elements: VecDeque<Node>, start: usize) -> VecDeque<Node> {
+        elements
+            .into_iter()
+            .enumerate()
+            .map(|(index, element)| {
+                if index < start {
+                    element
+                } else {
+                    self.rename(element)
+                }
+            })
+            .collect()
+    }
+
+    // Renames the variables everywhere but in quoted data.
+    fn rename(&self, node: Node) -> Node {
+        if self.names.is_empty() {
+            return node;
+        }
+        self.rename_node(node, false)
+    }
+
+    fn rename_node(&self, node: Node, is_quoted: bool) -> Node {
+        match node {
+            Node::Leaf {
+                token: Token::Identifier { name },
+                span,
+            } if !is_quoted => Node::Leaf {
+                token: Token::Identifier {
+                    name: self.new_name(&name).unwrap_or(name),
+                },
+                span,
+            },
+            Node::Leaf { .. } => node,
+            Node::List { elements, span } => {
+                let is_quoted = is_quoted_list(&elements, is_quoted);
+                Node::List {
+                    elements: elements
+                        .into_iter()
+                        .map(|element| self.rename_node(element, is_quoted))
+                        .collect(),
+                    span,
+                }
+            }
+        }
+    }
+
+    fn new_name(&self, name: &str) -> Option<String> {
+        match name.rsplit_once(MARK_SEPARATOR) {
+            None => self.names.get(name).cloned(),
+            Some((_, mark)) if self.inner_marks.contains(mark) => {
+                self.names.get(original_name(name)).cloned()
+            }
+            Some(_) => None,
+        }
+    }
+}
+
+// `(syntax-rules (literal ...) ((_ pattern ...) template) ...)`
+fn parse_syntax_rules(node: &Node) -> Result<SyntaxRules, Spanned<LisrParseError>> {
+    let malformed_syntax_rules = |span| Spanned::new(LisrParseError::MalformedSyntaxRules, span);
+    let Node::List { elements, span } = node else {
+        return Err(malformed_syntax_rules(node.span()));
+    };
+    let mut elements = elements.iter();
+    let (
+        Some(Node::Leaf {
+            token: Token::SyntaxRules,
+            ..
+        }),
+        Some(Node::List {
+            elements: literals, ..
+        }),
+    ) = (elements.next(), elements.next())
+    else {
+        return Err(malformed_syntax_rules(*span));
+    };
+
+    let literals = literals
+        .iter()
+        .map(|literal| match literal {
+            Node::Leaf { token, .. } => Ok(token.clone()),
+            Node::List { span, .. } => Err(malformed_syntax_rules(*span)),
+        })
+        .collect::<Result<Vec<Token>, Spanned<LisrParseError>>>()?;
+
+    let rules = elements
+        .map(|rule| match rule {
+            Node::List { elements, span } => match (elements.front(), elements.get(1)) {
+                (
+                    Some(Node::List {
+                        elements: pattern, ..
+                    }),
+                    Some(template),
+                ) if elements.len() == 2 => {
+                    // The keyword of the macro is skipped.
+                    Ok((pattern.iter().skip(1).cloned().collect(), template.clone()))
+                }
+                _ => Err(malformed_syntax_rules(*span)),
+            },
+            Node::Leaf { span, .. } => Err(malformed_syntax_rules(*span)),
+        })
+        .collect::<Result<Vec<(VecDeque<Node>, Node)>, Spanned<LisrParseError>>>()?;
+
+    Ok(SyntaxRules { literals, rules })
+}
+
+impl SyntaxRules {
+    fn is_literal(&self, name: &str) -> bool {
+        self.literals.iter().any(|literal| match literal {
+            Token::Identifier { name: literal } => literal == name,
+            _ => false,
+        })
+    }
+
+    fn match_pattern(&self, pattern: &Node, form: &Node, matches: &mut Matches) -> bool {
+        match (pattern, form) {
+            (
+                Node::Leaf {
+                    token: Token::Identifier { name },
+                    ..
+                },
+                _,
+            ) if name == WILDCARD => true,
+            (
+                Node::Leaf {
+                    token: Token::Identifier { name },
+                    ..
+                },
+                _,
+            ) if self.is_literal(name) => matches!(
+                form,
+                Node::Leaf { token: Token::Identifier { name: form_name }, .. }
+                    if original_name(form_name) == name
+            ),
+            (
+                Node::Leaf {
+                    token: Token::Identifier { name },
+                    ..
+                },
+                _,
+            ) => {
+                matches.insert(name.clone(), Matched::One(form.clone()));
+                true
+            }
+            // Keywords, numbers and strings match themselves.
+            (Node::Leaf { token, .. }, Node::Leaf { token: form, .. }) => token == form,
+            (
+                Node::List {
+                    elements: patterns, ..
+                },
+                Node::List {
+                    elements: forms,
+                    span,
+                },
+            ) => {
+                let patterns: Vec<&Node> = patterns.iter().collect();
+                let forms: Vec<&Node> = forms.iter().collect();
+                self.match_list(&patterns, &forms, *span, matches)
+            }
+            _ => false,
+        }
+    }
+
+    // Matches lists like `(a b)`, `(a b ... c)` and `(a . rest)`.
+    fn match_list(
+        &self,
+        patterns: &[&Node],
+        forms: &[&Node],
+        span: Span,
+        matches: &mut Matches,
+    ) -> bool {
+        let (patterns, tail) = match patterns {
+            [patterns @ .., dot, tail] if is_dot(dot) => (patterns, Some(*tail)),
+            _ => (patterns, None),
+        };
+
+        let (before, repeated, after) = match patterns.iter().position(|node| is_ellipsis(node)) {
+            Some(position) if position > 0 => (
+                &patterns[..position - 1],
+                Some(patterns[position - 1]),
+                &patterns[position + 1..],
+            ),
+            _ => (patterns, None, &patterns[patterns.len()..]),
+        };
+
+        let fixed = before.len() + after.len();
+        let has_right_length = match (repeated, tail) {
+            (None, None) => forms.len() == fixed,
+            _ => forms.len() >= fixed,
+        };
+        if !has_right_length {
+            return false;
+        }
+
+        let matches_all = |patterns: &[&Node], forms: &[&Node], matches: &mut Matches| {
+            patterns
+                .iter()
+                .zip(forms)
+                .all(|(pattern, form)| self.match_pattern(pattern, form, matches))
+        };
+        if !matches_all(before, &forms[..before.len()], matches) {
+            return false;
+        }
+        let forms = &forms[before.len()..];
+
+        let forms = match repeated {
+            Some(repeated) => {
+                // The ellipsis takes all the forms but the ones matched after it.
+                let (repeated_forms, rest) = forms.split_at(forms.len() - after.len());
+                if !self.match_repeated(repeated, repeated_forms, matches)
+                    || !matches_all(after, rest, matches)
+                {
+                    return false;
+                }
+                &rest[rest.len()..]
+            }
+            None => forms,
+        };
+
+        match tail {
+            Some(tail) => {
+                let rest = Node::List {
+                    elements: forms.iter().map(|&form| form.clone()).collect(),
+                    span,
+                };
+                self.match_pattern(tail, &rest, matches)
+            }
+            None => true,
+        }
+    }
+
+    fn match_repeated(&self, pattern: &Node, forms: &[&Node], matches: &mut Matches) -> bool {
+        let mut all_matches = Vec::new();
+        for form in forms {
+            let mut form_matches = Matches::new();
+            if !self.match_pattern(pattern, form, &mut form_matches) {
+                return false;
+            }
+            all_matches.push(form_matches);
+        }
+
+        for variable in self.pattern_variables(pattern) {
+            let matched = all_matches
+                .iter_mut()
+                .filter_map(|form_matches| form_matches.remove(&variable))
+                .collect();
+            matches.insert(variable, Matched::Many(matched));
+        }
+        true
+    }
+
+    fn pattern_variables(&self, pattern: &Node) -> Vec<String> {
+        match pattern {
+            Node::Leaf {
+                token: Token::Identifier { name },
+                ..
+            } if name != WILDCARD && name != ELLIPSIS && !self.is_literal(name) => {
+                vec![name.clone()]
+            }
+            Node::Leaf { .. } => Vec::new(),
+            Node::List { elements, .. } => elements
+                .iter()
+                .flat_map(|element| self.pattern_variables(element))
+                .collect(),
+        }
+    }
+}
+
+// Fills the template with the matched forms. Nodes introduced by the template
+// are placed at the `span` of the macro use.
+fn instantiate(
+    template: &Node,
+    matches: &Matches,
+    mark: usize,
+    span: Span,
+) -> Result<Node, Spanned<LisrParseError>> {
+    match template {
+        Node::Leaf {
+            token: Token::Identifier { name },
+            ..
+        } => match matches.get(name) {
+            Some(Matched::One(form)) => Ok(form.clone()),
+            // An ellipsis is missing after the variable.
+            Some(Matched::Many(_)) => Err(Spanned::new(LisrParseError::MalformedSyntaxRules, span)),
+            None => Ok(Node::Leaf {
+                token: Token::Identifier {
+                    name: format!("{}{}{}", name, MARK_SEPARATOR, mark),
+                },
+                span,
+            }),
+        },
+        Node::Leaf { token, .. } => Ok(Node::Leaf {
+            token: token.clone(),
+            span,
+        }),
+        // `(... ...)` is an ellipsis that is not followed by anything.
+        Node::List { elements, .. } if elements.len() == 2 && elements.iter().all(is_ellipsis) => {
+            Ok(Node::Leaf {
+                token: Token::Identifier {
+                    name: String::from(ELLIPSIS),
+                },
+                span,
+            })
+        }
+        Node::List { elements, .. } => {
+            let templates: Vec<&Node> = elements.iter().collect();
+            let mut instantiated = VecDeque::new();
+            let mut index = 0;
+            while index < templates.len() {
+                let depth = templates[index + 1..]
+                    .iter()
+                    .take_while(|template| is_ellipsis(template))
+                    .count();
+                instantiated.extend(instantiate_repeated(
+                    templates[index],
+                    matches,
+                    depth,
+                    mark,
+                    span,
+                )?);
+                index += depth + 1;
+            }
+            Ok(Node::List {
+                elements: instantiated,
+                span,
+            })
+        }
+    }
+}
+
+// Instantiates a template followed by `depth` ellipses once for every form
+// matched by its variables.
+fn instantiate_repeated(
+    template: &Node,
+    matches: &Matches,
+    depth: usize,
+    mark: usize,
+    span: Span,
+) -> Result<Vec<Node>, Spanned<LisrParseError>> {
+    if depth == 0 {
+        return Ok(vec![instantiate(template, matches, mark, span)?]);
+    }
+
+    let malformed_syntax_rules = || Spanned::new(LisrParseError::MalformedSyntaxRules, span);
+    let repeated_variables: Vec<(&String, &Vec<Matched>)> = template_identifiers(template)
+        .into_iter()
+        .filter_map(|name| match matches.get_key_value(name) {
+            Some((name, Matched::Many(forms))) => Some((name, forms)),
+            _ => None,
+        })
+        .collect();
+    let Some((_, first_forms)) = repeated_variables.first() else {
+        return Err(malformed_syntax_rules());
+    };
+    let count = first_forms.len();
+    if repeated_variables
+        .iter()
+        .any(|(_, forms)| forms.len() != count)
+    {
+        return Err(malformed_syntax_rules());
+    }
+
+    let mut instantiated = Vec::new();
+    for index in 0..count {
+        let mut form_matches = matches.clone();
+        for (name, forms) in &repeated_variables {
+            form_matches.insert((*name).clone(), forms[index].clone());
+        }
+        instantiated.extend(instantiate_repeated(
+            template,
+            &form_matches,
+            depth - 1,
+            mark,
+            span,
+        )?);
+    }
+    Ok(instantiated)
+}
+
+fn template_identifiers(template: &Node) -> Vec<&String> {
+    match template {
+        Node::Leaf {
+            token: Token::Identifier { name },
+            ..
+        } => vec![name],
+        Node::Leaf { .. } => Vec::new(),
+        Node::List { elements, .. } => elements.iter().flat_map(template_identifiers).collect(),
+    }
+}
+
+// Gives the original names back to renamed identifiers that are not bound in
+// the expanded form. Quoted identifiers always get their original names back.
+fn resolve_marks(node: Node) -> Node {
+    let mut bound = HashSet::new();
+    collect_bound_renamed_identifiers(&node, &mut bound);
+    rename_identifiers(node, &bound, false)
+}
+
+fn collect_bound_renamed_identifiers(node: &Node, bound: &mut HashSet<String>) {
+    let Node::List { elements, .. } = node else {
+        return;
+    };
+    let mut variables = Vec::new();
+    match elements.front() {
+        Some(Node::Leaf {
+            token: Token::Quote,
+            ..
+        }) => return,
+        Some(Node::Leaf {
+            token: Token::Lambda | Token::Define,
+            ..
+        }) => variables.extend(elements.get(1).map(bound_identifiers).unwrap_or_default()),
+        Some(Node::Leaf {
+            token: Token::Let | Token::LetStar | Token::Letrec | Token::LetrecStar,
+            ..
+        }) => {
+            let mut bindings = elements.get(1);
+            if let Some(Node::Leaf {
+                token: Token::Identifier { name },
+                ..
+            }) = bindings
+            {
+                variables.push(name.clone());
+                bindings = elements.get(2);
+            }
+            if let Some(Node::List { elements, .. }) = bindings {
+                for binding in elements {
+                    if let Node::List { elements, .. } = binding {
+                        variables
+                            .extend(elements.front().map(bound_identifiers).unwrap_or_default());
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+
+    bound.extend(
+        variables
+            .into_iter()
+            .filter(|variable| variable.contains(MARK_SEPARATOR)),
+    );
+    for element in elements {
+        collect_bound_renamed_identifiers(element, bound);
+    }
+}
+
+fn rename_identifiers(node: Node, bound: &HashSet<String>, is_quoted: bool) -> Node {
+    match node {
+        Node::Leaf {
+            token: Token::Identifier { name },
+            span,
+        } if name.contains(MARK_SEPARATOR) && (is_quoted || !bound.contains(&name)) => Node::Leaf {
+            token: Token::Identifier {
+                name: String::from(original_name(&name)),
+            },
+            span,
+        },
+        Node::Leaf { .. } => node,
+        Node::List { elements, span } => {
+            let is_quoted = is_quoted_list(&elements, is_quoted);
+            Node::List {
+                elements: elements
+                    .into_iter()
+                    .map(|element| rename_identifiers(element, bound, is_quoted))
+                    .collect(),
+                span,
+            }
+        }
+    }
+}
+
+// Whether the elements of a list are quoted. Unquoted parts of a quasiquote
+// template are code again.
+fn is_quoted_list(elements: &VecDeque<Node>, is_quoted: bool) -> bool {
+    match elements.front() {
+        Some(Node::Leaf {
+            token: Token::Quote | Token::Quasiquote,
+            ..
+        }) => true,
+        Some(Node::Leaf {
+            token: Token::Unquote | Token::UnquoteSplicing,
+            ..
+        }) => false,
+        _ => is_quoted,
+    }
+}
+
+// Identifiers bound by a parameter list (or a single parameter).
+fn bound_identifiers(node: &Node) -> Vec<String> {
+    match node {
+        Node::Leaf {
+            token: Token::Identifier { name },
+            ..
+        } => vec![name.clone()],
+        Node::Leaf { .. } => Vec::new(),
+        Node::List { elements, .. } => elements
+            .iter()
+            .filter_map(|element| match element {
+                Node::Leaf {
+                    token: Token::Identifier { name },
+                    ..
+                } => Some(name.clone()),
+                _ => None,
+            })
+            .collect(),
+    }
+}
+
+fn quote_name(name: String, name_span: Span, span: Span) -> Node {
+    Node::List {
+        elements: VecDeque::from([
+            Node::Leaf {
+                token: Token::Quote,
+                span,
+            },
+            Node::Leaf {
+                token: Token::Identifier { name },
+                span: name_span,
+            },
+        ]),
+        span,
+    }
+}
+
+fn variables_scope(variables: Vec<String>) -> Scope {
+    Scope {
+        bindings: variables
+            .into_iter()
+            .map(|variable| (variable, None))
+            .collect(),
+        captured: HashSet::new(),
+    }
+}
+
+fn original_name(name: &str) -> &str {
+    name.split(MARK_SEPARATOR).next().unwrap_or(name)
+}
+
+fn is_ellipsis(node: &Node) -> bool {
+    matches!(node, Node::Leaf { token: Token::Identifier { name }, .. } if original_name(name) == ELLIPSIS)
+}
+
+fn is_dot(node: &Node) -> bool {
+    matches!(
+        node,
+        Node::Leaf {
+            token: Token::Dot,
+            ..
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{evaluate::LisrEvaluationError, parse::parse, scan::scan};
+
+    fn expand_source(input: &str) -> Result<Vec<String>, Spanned<LisrParseError>> {
+        let nodes = parse(scan(input).unwrap()).unwrap();
+        Ok(expand(nodes)?.iter().map(to_source).collect())
+    }
+
+    fn to_source(node: &Node) -> String {
+        match node {
+            Node::Leaf { token, .. } => match token {
+                Token::Identifier { name } => name.clone(),
+                Token::Number { value } => value.to_string(),
+                Token::String { value } => format!("{:?}", value),
+                Token::Dot => String::from("."),
+                token => String::from(token.keyword().unwrap()),
+            },
+            Node::List { elements, .. } => format!(
+                "({})",
+                elements.iter().map(to_source).collect::<Vec<_>>().join(" ")
+            ),
+        }
+    }
+
+    #[test]
+    fn should_expand_macro() {
+        let result = expand_source(
+            "
+            (define-syntax my-unless
+              (syntax-rules ()
+                ((_ test body) (if test false body))))
+            (my-unless (= x 0) (/ 1 x))
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            result,
+            vec!["(quote my-unless)", "(if (= x 0) false (/ 1 x))"]
+        );
+    }
+
+    #[test]
+    fn should_expand_ellipsis() {
+        let result = expand_source(
+            "
+            (define-syntax my-list
+              (syntax-rules ()
+                ((_ first rest ...) (cons first (list rest ...)))))
+            (my-list 1 2 3)
+            (my-list 1)
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(result[1], "(cons 1 (list 2 3))");
+        assert_eq!(result[2], "(cons 1 (list))");
+    }
+
+    #[test]
+    fn should_expand_nested_ellipsis() {
+        let result = expand_source(
+            "
+            (define-syntax my-let
+              (syntax-rules ()
+                ((_ ((name value) ...) body ...) ((lambda (name ...) body ...) value ...))))
+            (my-let ((a 1) (b 2)) (display a) b)
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(result[1], "((lambda (a b) (display a) b) 1 2)");
+    }
+
+    #[test]
+    fn should_match_literals() {
+        let result = expand_source(
+            "
+            (define-syntax for
+              (syntax-rules (in from)
+                ((_ x in items body) (map (lambda (x) body) items))
+                ((_ x from start body) (body start))))
+            (for y in numbers (* y y))
+            (for y from 0 f)
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(result[1], "(map (lambda (y) (* y y)) numbers)");
+        assert_eq!(result[2], "(f 0)");
+    }
+
+    #[test]
+    fn should_rename_variables_introduced_by_macro() {
+        let result = expand_source(
+            "
+            (define-syntax swap!
+              (syntax-rules ()
+                ((_ a b) (let ((tmp a)) (set! a b) (set! b tmp)))))
+            (swap! tmp other)
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            result[1],
+            "(let ((tmp 0 tmp)) (set! tmp other) (set! other tmp 0))"
+        );
+    }
+
+    #[test]
+    fn should_rename_variables_that_would_capture_macro_identifiers() {
+        let result = expand_source(
+            "
+            (define-syntax get-x (syntax-rules () ((_) x)))
+            (define (g x) (+ x (get-x)))
+            (let ((y 1) (x y)) (get-x))
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(result[1], "(define (g x 1) (+ x 1 x))");
+        assert_eq!(result[2], "(let ((y 1) (x 3 y)) x)");
+    }
+
+    #[test]
+    fn should_expand_recursive_macro() {
+        let result = expand_source(
+            "
+            (define-syntax my-and
+              (syntax-rules ()
+                ((_) true)
+                ((_ e) e)
+                ((_ e rest ...) (if e (my-and rest ...) false))))
+            (my-and a b c)
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(result[1], "(if a (if b c false) false)");
+    }
+
+    #[test]
+    fn should_escape_ellipsis() {
+        let result = expand_source(
+            "
+            (define-syntax quote-ellipsis
+              (syntax-rules ()
+                ((_) '(... ...))))
+            (quote-ellipsis)
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(result[1], "(quote ...)");
+    }
+
+    #[test]
+    fn should_fail_if_no_rule_matches() {
+        let error = expand_source(
+            "
+            (define-syntax two
+              (syntax-rules ()
+                ((_ a b) (list a b))))
+            (two 1)
+            ",
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error.value,
+            LisrParseError::NoMatchingSyntaxRule {
+                name: String::from("two")
+            }
+        );
+    }
+
+    #[test]
+    fn should_fail_on_malformed_syntax_rules() {
+        let error = expand_source("(define-syntax broken (lambda (x) x))").unwrap_err();
+
+        assert_eq!(error.value, LisrParseError::MalformedSyntaxRules);
+    }
+
+    #[test]
+    fn should_scope_let_syntax_macros_to_body() {
+        let result = expand_source(
+            "
+            (let-syntax ((double (syntax-rules () ((_ x) (* 2 x)))))
+              (double 21))
+            (double 21)
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(result, vec!["(let () (* 2 21))", "(double 21)"]);
+    }
+
+    #[test]
+    fn should_not_expand_quoted_forms() {
+        let result = expand_source(
+            "
+            (define-syntax double (syntax-rules () ((_ x) (* 2 x))))
+            '(double 21)
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(result[1], "(quote (double 21))");
+    }
+
+    #[test]
+    fn should_not_expand_shadowed_macros() {
+        let result = expand_source(
+            "
+            (define-syntax double (syntax-rules () ((_ x) (* 2 x))))
+            (lambda (double) (double 21))
+            (let ((double car)) (double 21))
+            (double 21)
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(result[1], "(lambda (double) (double 21))");
+        assert_eq!(result[2], "(let ((double car)) (double 21))");
+        assert_eq!(result[3], "(* 2 21)");
+    }
+
+    #[test]
+    fn should_expand_procedure_macro() {
+        let result = expand_source(
+            "
+            (define-macro (my-unless test body)
+              (cons 'if (cons test (cons false (cons body '())))))
+            (my-unless (= x 0) (/ 1 x))
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            result,
+            vec!["(quote my-unless)", "(if (= x 0) false (/ 1 x))"]
+        );
+    }
+
+    #[test]
+    fn should_expand_procedure_macro_with_rest_parameter() {
+        let result = expand_source(
+            "
+            (define-macro (my-begin . body) (cons 'let (cons '() body)))
+            (define-macro reverse-call
+              (lambda (f a b) (cons f (cons b (cons a '())))))
+            (my-begin (reverse-call - 1 10) 2)
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(result[2], "(let () (- 10 1) 2)");
+    }
+
+    #[test]
+    fn should_report_failing_procedure_macro() {
+        let error = expand_source(
+            "
+            (define-macro (broken x) (car x))
+            (broken 1)
+            ",
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error.value,
+            LisrParseError::MacroFailed {
+                name: String::from("broken"),
+                error: LisrEvaluationError::RuntimeError {
+                    reason: "'car' requires one 'cons' argument"
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn should_fail_if_procedure_macro_returns_symbol_with_spaces() {
+        let error = expand_source(
+            "
+            (define-macro (broken) (string->symbol \"a 0\"))
+            (broken)
+            ",
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error.value,
+            LisrParseError::ValueIsNotCode {
+                value: String::from("a 0")
+            }
+        );
+    }
+
+    #[test]
+    fn should_fail_if_procedure_macro_returns_value_that_is_not_code() {
+        let error = expand_source(
+            "
+            (define-macro (broken) car)
+            (broken)
+            ",
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error.value,
+            LisrParseError::ValueIsNotCode {
+                value: String::from("#<primitive-procedure>")
+            }
+        );
+    }
+
+    #[test]
+    fn should_expand_only_unquoted_parts_of_quasiquotations() {
+        let result = expand_source(
+            "
+            (define-syntax double (syntax-rules () ((_ x) (* 2 x))))
+            `((double 1) ,(double 2) ,@(double 3))
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(
+            result[1],
+            "(quasiquote ((double 1) (unquote (* 2 2)) (unquote-splicing (* 2 3))))"
+        );
+    }
+}
@@ -1,37 +1,116 @@
-use crate::{environment::Environment, evaluate::LisrEvaluationError};
+use std::{fmt, rc::Rc};
+
+use crate::{
+    environment::Environment, evaluate::LisrEvaluationError, number::Number, scan::CHARACTER_NAMES,
+    span::Span, symbol::Symbol,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Identifier {
-    pub name: String,
+    pub name: Symbol,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Parameter {
-    pub name: String,
+    pub name: Symbol,
+}
+
+// A variable bound by a let expression, like `(x 42)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binding {
+    pub variable: Identifier,
+    pub value: Expression,
+}
+
+// A clause of a cond, like `((< x 0) 'negative)`. The test of an else clause
+// is always true.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CondClause {
+    pub test: Expression,
+    pub body: ClauseBody,
+}
+
+// A clause of a case, like `((1 2 3) 'small)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaseClause {
+    pub data: Vec<Expression>,
+    pub body: ClauseBody,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClauseBody {
+    // A clause without a body, like `(x)`, evaluates to the value of its test.
+    TestValue,
+    Sequence(Box<Expression>),
+    // The value of the test is passed to the receiver, like in `(x => f)`.
+    Receiver(Box<Expression>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LetKind {
+    // Values are evaluated outside of the let.
+    Let,
+    // Each value sees the variables bound before it.
+    LetStar,
+    // Values see all the variables of the let, so they can define mutually
+    // recursive procedures.
+    Letrec,
+    LetrecStar,
 }
 
+// Primitive procedures are compared by their address, which is good enough to
+// tell whether a variable still refers to the same builtin.
+#[allow(unpredictable_function_pointer_comparisons)]
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expression {
     String {
         value: String,
     },
     Number {
-        value: f64,
+        value: Number,
+    },
+    Character {
+        value: char,
     },
 
-    Quotation {
-        text_of_quotation: String,
+    // A symbol, like the `abc` in `'abc`.
+    Symbol {
+        value: Symbol,
+    },
+    // Quoted data, like `'(1 2)`. Evaluates to the datum itself.
+    Quote {
+        datum: Box<Expression>,
+    },
+    // Quasiquoted data, like `` `(1 ,x ,@rest) ``. Evaluates to the template
+    // with its unquoted expressions replaced by their values.
+    Quasiquote {
+        template: Box<Expression>,
+    },
+    // The `,x` of a quasiquote template.
+    Unquote {
+        expression: Box<Expression>,
+    },
+    // The `,@rest` of a quasiquote template, an element whose value is a list
+    // spliced into the template.
+    UnquoteSplicing {
+        expression: Box<Expression>,
+        span: Span,
     },
 
-    Identifier(Identifier),
+    Identifier {
+        identifier: Identifier,
+        span: Span,
+    },
 
     Assignment {
         variable: Identifier,
         value: Box<Expression>,
+        span: Span,
     },
     Definition {
         variable: Identifier,
         value: Box<Expression>,
+        span: Span,
     },
 
     True,
@@ -41,26 +120,54 @@ pub enum Expression {
         predicate: Box<Expression>,
         consequent: Box<Expression>,
         alternative: Box<Expression>,
+        span: Span,
     },
     And {
         operands: Vec<Expression>,
+        span: Span,
     },
     Or {
         operands: Vec<Expression>,
+        span: Span,
     },
 
+    // Parameters and bodies of procedures are shared, so that creating and
+    // calling a procedure does not copy them. Arguments that do not have a
+    // matching parameter are bound as a list to the rest parameter, like `args`
+    // in `(lambda (a . args) a)`.
     Lambda {
-        parameters: Vec<Parameter>,
-        body: Box<Expression>,
+        parameters: Rc<[Parameter]>,
+        rest_parameter: Option<Parameter>,
+        body: Rc<Expression>,
     },
 
     Begin {
         sequence: Vec<Expression>,
+        span: Span,
+    },
+
+    Cond {
+        clauses: Vec<CondClause>,
+        span: Span,
+    },
+    Case {
+        key: Box<Expression>,
+        clauses: Vec<CaseClause>,
+        else_clause: Option<ClauseBody>,
+        span: Span,
+    },
+
+    Let {
+        kind: LetKind,
+        bindings: Vec<Binding>,
+        body: Box<Expression>,
+        span: Span,
     },
 
     Application {
         procedure: Box<Expression>,
         arguments: Vec<Expression>,
+        span: Span,
     },
 
     Cons {
@@ -69,20 +176,74 @@ pub enum Expression {
     },
     EmptyList,
 
+    // The value of expressions whose value is not specified, like a cond without
+    // a matching clause.
+    Unspecified,
+
     LisrInternalObject {
         name: String,
     },
 
-    // Implemented as a flat-closure (a.k.a. one block closure) for simplicity - each
-    // function has a copy of it's enclosing environment. This means a procedure
-    // cannot modify it's enclosing environment, but can only read it.
+    // The environment is shared with the place the procedure has been defined in,
+    // so the procedure can modify the variables it closes over.
     CompoundProcedure {
-        parameters: Vec<Parameter>,
-        body: Box<Expression>,
-        environment: Box<Environment>,
+        parameters: Rc<[Parameter]>,
+        rest_parameter: Option<Parameter>,
+        body: Rc<Expression>,
+        environment: Environment,
     },
 
     PrimitiveProcedure {
         procedure: fn(Vec<Expression>) -> Result<Expression, LisrEvaluationError>,
     },
 }
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::String { value } => write!(f, "{:?}", value),
+            Expression::Number { value } => write!(f, "{}", value),
+            // Characters are printed the way they are written, like `#\a`.
+            Expression::Character { value } => {
+                match CHARACTER_NAMES.iter().find(|(_, char)| char == value) {
+                    Some((name, _)) => write!(f, "#\\{}", name),
+                    None if value.is_control() => write!(f, "#\\x{:x}", *value as u32),
+                    None => write!(f, "#\\{}", value),
+                }
+            }
+            Expression::Symbol { value } => write!(f, "{}", value),
+            Expression::Identifier {
+                identifier: Identifier { name },
+                ..
+            } => write!(f, "{}", name),
+            Expression::True => write!(f, "true"),
+            Expression::False => write!(f, "false"),
+            Expression::EmptyList => write!(f, "()"),
+            Expression::Unspecified => write!(f, "#<unspecified>"),
+            Expression::Cons { first, rest } => {
+                write!(f, "({}", first)?;
+                let mut rest = rest.as_ref();
+                // Print proper lists as (1 2 3) and improper ones as (1 2 . 3).
+                loop {
+                    match rest {
+                        Expression::Cons { first, rest: next } => {
+                            write!(f, " {}", first)?;
+                            rest = next.as_ref();
+                        }
+                        Expression::EmptyList => break,
+                        _ => {
+                            write!(f, " . {}", rest)?;
+                            break;
+                        }
+                    }
+                }
+                write!(f, ")")
+            }
+            Expression::LisrInternalObject { name } => write!(f, "#<{}>", name),
+            Expression::CompoundProcedure { .. } => write!(f, "#<procedure>"),
+            Expression::PrimitiveProcedure { .. } => write!(f, "#<primitive-procedure>"),
+            // The remaining expressions are never the result of an evaluation.
+            _ => write!(f, "#<expression>"),
+        }
+    }
+}
@@ -1,18 +1,94 @@
 use crate::{
-    evaluate::evaluate, expression::Expression, lisr_error::LisrError, parse::parse, scan::scan,
+    environment::Environment,
+    evaluate::{create_global_environment, define_command_line, evaluate_in_environment},
+    expand::Expander,
+    expression::Expression,
+    lisr_error::LisrError,
+    node::Node,
+    parse::parse,
+    scan::scan,
     translate::translate,
 };
 
-pub fn interpret(input: &str) -> Result<Expression, LisrError> {
-    let tokens = scan(input)?;
-    let nodes = parse(tokens)?;
-    let expressions = translate(nodes)?;
-    let result = evaluate(expressions)?;
-    Ok(result)
+// Keeps the global environment and macros between calls to `interpret`, so
+// that definitions made by one input are visible to the following ones.
+pub struct Interpreter {
+    environment: Environment,
+    expander: Expander,
+}
+
+impl Interpreter {
+    pub fn new() -> Interpreter {
+        let environment = create_global_environment();
+        Interpreter {
+            expander: Expander::with_environment(environment.clone()),
+            environment,
+        }
+    }
+
+    // Makes the arguments available to programs through `(command-line)`.
+    pub fn define_command_line(&mut self, arguments: Vec<String>) {
+        define_command_line(&self.environment, arguments);
+    }
+
+    // Keeps evaluating the following forms even if one of them fails, and
+    // returns the outcome of the last one.
+    pub fn interpret<'a>(&mut self, input: &'a str) -> Result<Expression, LisrError<'a>> {
+        self.interpret_forms(input, false)
+    }
+
+    // Stops at the first error, so that a failing script doesn't keep running
+    // with a broken state.
+    pub fn interpret_script<'a>(&mut self, input: &'a str) -> Result<Expression, LisrError<'a>> {
+        self.interpret_forms(input, true)
+    }
+
+    fn interpret_forms<'a>(
+        &mut self,
+        input: &'a str,
+        stop_at_first_error: bool,
+    ) -> Result<Expression, LisrError<'a>> {
+        let tokens = scan(input)?;
+        let nodes = parse(tokens)?;
+        // Each form is evaluated before the next one is expanded, so that macros
+        // can use the procedures defined before them.
+        let mut result = Ok(Expression::EmptyList);
+        for node in nodes {
+            result = self.interpret_form(node);
+            if stop_at_first_error && result.is_err() {
+                break;
+            }
+        }
+        result
+    }
+
+    fn interpret_form<'a>(&mut self, node: Node) -> Result<Expression, LisrError<'a>> {
+        let node = self.expander.expand_form(node)?;
+        let expressions = translate([node])?;
+        Ok(evaluate_in_environment(expressions, &self.environment)?)
+    }
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Interpreter::new()
+    }
+}
+
+pub fn interpret(input: &str) -> Result<Expression, LisrError<'_>> {
+    Interpreter::new().interpret(input)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::{
+        evaluate::LisrEvaluationError,
+        number::Number,
+        parse::LisrParseError,
+        span::{Position, Span, Spanned},
+        symbol::Symbol,
+    };
+
     use super::*;
 
     #[test]
@@ -27,7 +103,12 @@ mod tests {
 
         let result = interpret(input).unwrap();
 
-        assert_eq!(result, Expression::Number { value: 20.0 });
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Real(20.0)
+            }
+        );
     }
 
     #[test]
@@ -64,7 +145,12 @@ mod tests {
 
         let result = interpret(input).unwrap();
 
-        assert_eq!(result, Expression::Number { value: 5.0 });
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(5)
+            }
+        );
     }
 
     #[test]
@@ -76,7 +162,12 @@ mod tests {
 
         let result = interpret(input).unwrap();
 
-        assert_eq!(result, Expression::Number { value: 16.0 });
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(16)
+            }
+        );
     }
 
     #[test]
@@ -89,7 +180,12 @@ mod tests {
 
         let result = interpret(input).unwrap();
 
-        assert_eq!(result, Expression::Number { value: 6.0 });
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(6)
+            }
+        );
     }
 
     #[test]
@@ -102,7 +198,12 @@ mod tests {
 
         let result = interpret(input).unwrap();
 
-        assert_eq!(result, Expression::Number { value: 5.0 });
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(5)
+            }
+        );
     }
 
     #[test]
@@ -111,7 +212,12 @@ mod tests {
 
         let result = interpret(input).unwrap();
 
-        assert_eq!(result, Expression::Number { value: 2.0 });
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(2)
+            }
+        );
     }
 
     #[test]
@@ -130,7 +236,12 @@ mod tests {
 
         let result = interpret(input).unwrap();
 
-        assert_eq!(result, Expression::Number { value: 2.0 });
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(2)
+            }
+        );
     }
 
     #[test]
@@ -142,7 +253,12 @@ mod tests {
 
         let result = interpret(input).unwrap();
 
-        assert_eq!(result, Expression::Number { value: 3.0 });
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(3)
+            }
+        );
     }
 
     #[test]
@@ -177,7 +293,285 @@ mod tests {
 
         let result = interpret(input).unwrap();
 
-        assert_eq!(result, Expression::Number { value: 3.0 });
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(3)
+            }
+        );
+    }
+
+    #[test]
+    fn test_interpreter_keeps_definitions() {
+        let mut interpreter = Interpreter::new();
+
+        interpreter
+            .interpret("(define (square x) (* x x))")
+            .unwrap();
+        interpreter.interpret("(define x 3)").unwrap();
+        let result = interpreter.interpret("(square x)").unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(9)
+            }
+        );
+    }
+
+    #[test]
+    fn test_command_line() {
+        let mut interpreter = Interpreter::new();
+        interpreter.define_command_line(vec![String::from("script.lisr"), String::from("42")]);
+
+        let result = interpreter.interpret("(car (cdr (command-line)))").unwrap();
+
+        assert_eq!(
+            result,
+            Expression::String {
+                value: String::from("42")
+            }
+        );
+    }
+
+    #[test]
+    fn test_keeps_evaluating_after_an_error() {
+        let input = "
+            (define x 1)
+            (set! y 2)
+            (set! x 2)
+            x
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(2)
+            }
+        );
+    }
+
+    #[test]
+    fn test_scripts_stop_at_the_first_error() {
+        let input = "
+            (define x 1)
+            (set! y 2)
+            (set! x 2)
+            x
+        ";
+
+        let error = Interpreter::new().interpret_script(input).unwrap_err();
+
+        assert!(matches!(
+            error,
+            LisrError::Evaluation(Spanned {
+                value: LisrEvaluationError::UndefinedIdentifier,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_errors_report_their_position() {
+        let input = "
+            (define (area r)
+                (* pi r r))
+            (area 2)
+        ";
+
+        let error = interpret(input).unwrap_err();
+
+        assert_eq!(
+            error.span(),
+            Span::new(Position::new(3, 20), Position::new(3, 22))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_report_their_position() {
+        let input = "
+            (define x 1)
+            (if x)
+        ";
+
+        let error = interpret(input).unwrap_err();
+
+        assert!(matches!(
+            error,
+            LisrError::Parse(Spanned {
+                value: LisrParseError::IfRequiresPredicateAndConsequent { found: 1 },
+                ..
+            })
+        ));
+        assert_eq!(
+            error.span(),
+            Span::new(Position::new(3, 13), Position::new(3, 19))
+        );
+    }
+
+    #[test]
+    fn test_closures_share_their_environment() {
+        let input = "
+            (define (make-counter)
+                (begin
+                    (define count 0)
+                    (lambda () (begin (set! count (+ count 1)) count))
+                )
+            )
+            (define counter (make-counter))
+            (define other-counter (make-counter))
+            (counter)
+            (counter)
+            (other-counter)
+            (counter)
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(3)
+            }
+        );
+    }
+
+    #[test]
+    fn test_assignment_changes_a_captured_variable() {
+        let input = "
+            (define balance 100)
+            (define (withdraw amount) (set! balance (- balance amount)))
+            (withdraw 30)
+            (withdraw 20)
+            balance
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(50)
+            }
+        );
+    }
+
+    #[test]
+    fn test_definitions_inside_a_body_are_local() {
+        let input = "
+            (define x 1)
+            (define (f) (begin (define x 2) x))
+            (+ (f) x)
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(3)
+            }
+        );
+    }
+
+    #[test]
+    fn test_procedure_bodies_evaluate_every_expression() {
+        let input = "
+            (define counter 0)
+            (define (count-twice)
+                (set! counter (+ counter 1))
+                (set! counter (+ counter 1))
+                counter)
+            ((lambda (n) (count-twice) (+ n (count-twice))) 10)
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(14)
+            }
+        );
+    }
+
+    #[test]
+    fn test_procedures_do_not_see_the_callers_variables() {
+        let input = "
+            (define (get-y) y)
+            (define (f y) (get-y))
+            (f 1)
+        ";
+
+        let error = interpret(input).unwrap_err();
+
+        assert!(matches!(
+            error,
+            LisrError::Evaluation(Spanned {
+                value: LisrEvaluationError::UndefinedIdentifier,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_tail_calls_in_if_run_in_constant_space() {
+        let input = "
+            (define (count-up n acc)
+                (if (= n 0)
+                    acc
+                    (count-up (- n 1) (+ acc 1))
+                )
+            )
+            (count-up 1000000 0)
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(1000000)
+            }
+        );
+    }
+
+    #[test]
+    fn test_mutually_recursive_tail_calls_run_in_constant_space() {
+        let input = "
+            (define (my-even? n) (if (= n 0) true (my-odd? (- n 1))))
+            (define (my-odd? n) (if (= n 0) false (my-even? (- n 1))))
+            (my-even? 1000000)
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result, Expression::True);
+    }
+
+    #[test]
+    fn test_tail_calls_in_begin_run_in_constant_space() {
+        let input = "
+            (define counter 0)
+            (define (loop-begin n)
+                (begin
+                    (set! counter (+ counter 1))
+                    (if (= n 0) counter (loop-begin (- n 1)))
+                )
+            )
+            (loop-begin 1000000)
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(1000001)
+            }
+        );
     }
 
     // And a couple of fun programs:
@@ -197,7 +591,12 @@ mod tests {
 
         let result = interpret(input).unwrap();
 
-        assert_eq!(result, Expression::Number { value: 4.0 });
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(4)
+            }
+        );
     }
 
     #[test]
@@ -224,7 +623,12 @@ mod tests {
 
         let result = interpret(input).unwrap();
 
-        assert_eq!(result, Expression::Number { value: 2048.0 });
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(2048)
+            }
+        );
     }
 
     #[test]
@@ -271,7 +675,7 @@ mod tests {
 
         let result = interpret(input).unwrap();
         if let Expression::Number { value } = result {
-            assert!(f64::abs(value - 3.0) < 0.0001)
+            assert!(f64::abs(value.to_f64() - 3.0) < 0.0001)
         } else {
             panic!("The result of a square root function should be a number.");
         }
@@ -298,13 +702,21 @@ mod tests {
         assert_eq!(
             result,
             Expression::Cons {
-                first: Box::new(Expression::Number { value: 1.0 }),
+                first: Box::new(Expression::Number {
+                    value: Number::Integer(1)
+                }),
                 rest: Box::new(Expression::Cons {
-                    first: Box::new(Expression::Number { value: 2.0 }),
+                    first: Box::new(Expression::Number {
+                        value: Number::Integer(2)
+                    }),
                     rest: Box::new(Expression::Cons {
-                        first: Box::new(Expression::Number { value: 3.0 }),
+                        first: Box::new(Expression::Number {
+                            value: Number::Integer(3)
+                        }),
                         rest: Box::new(Expression::Cons {
-                            first: Box::new(Expression::Number { value: 4.0 }),
+                            first: Box::new(Expression::Number {
+                                value: Number::Integer(4)
+                            }),
                             rest: Box::new(Expression::EmptyList)
                         })
                     })
@@ -332,6 +744,1234 @@ mod tests {
 
         let result = interpret(input).unwrap();
 
-        assert_eq!(result, Expression::Number { value: 6.0 });
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(6)
+            }
+        );
+    }
+
+    #[test]
+    fn test_quoted_lists_are_data() {
+        let input = "
+            (define data '(1 2 (a b) \"three\" true))
+
+            (car (cdr (car (cdr (cdr data)))))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Symbol {
+                value: Symbol::new("b")
+            }
+        );
+    }
+
+    #[test]
+    fn test_quoted_data_is_printed_as_lists() {
+        let input = "(cons (quote (if x)) '(1 \"two\" () 'c))";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), "((if x) 1 \"two\" () (quote c))");
+    }
+
+    #[test]
+    fn test_quoted_lists_can_be_traversed() {
+        let input = "
+            (define (length items)
+                (if (empty-list? items)
+                    0
+                    (+ 1 (length (cdr items)))))
+
+            (length '(a (b c) \"d\" 4))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(4)
+            }
+        );
+    }
+
+    #[test]
+    fn test_symbols_tag_records() {
+        let input = "
+            (define (make-circle radius) (cons 'circle radius))
+            (define (make-square side) (cons 'square side))
+
+            (define (area shape)
+                (if (eq? (car shape) 'circle)
+                    (* 3 (* (cdr shape) (cdr shape)))
+                    (* (cdr shape) (cdr shape))))
+
+            (+ (area (make-circle 2)) (area (make-square 3)))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(21)
+            }
+        );
+    }
+
+    #[test]
+    fn test_symbols_are_interned() {
+        let input = "
+            (and (eq? 'abc (string->symbol \"abc\"))
+                 (eq? (quote define) (car '(define x 1)))
+                 (symbol? 'abc)
+                 (symbol? (string->symbol \"with spaces\")))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result, Expression::True);
+    }
+
+    #[test]
+    fn test_eq_tells_apart_different_objects() {
+        let input = "
+            (or (eq? 'abc 'abd)
+                (eq? 'abc \"abc\")
+                (symbol? \"abc\")
+                (eq? '(1) '(1)))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result, Expression::False);
+    }
+
+    #[test]
+    fn test_symbol_to_string() {
+        let input = "(symbol->string 'hello)";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::String {
+                value: String::from("hello")
+            }
+        );
+        assert_eq!(
+            interpret("(string->symbol \"hello\")").unwrap(),
+            Expression::Symbol {
+                value: Symbol::new("hello")
+            }
+        );
+    }
+
+    #[test]
+    fn test_symbol_conversions_report_wrong_arguments() {
+        let inputs = [
+            "(symbol->string \"hello\")",
+            "(symbol->string 'hello 'world)",
+            "(string->symbol 'hello)",
+            "(string->symbol)",
+        ];
+
+        let messages: Vec<String> = inputs
+            .iter()
+            .map(|input| interpret(input).unwrap_err().message())
+            .collect();
+
+        assert_eq!(
+            messages,
+            [
+                "'symbol->string' function requires one symbol argument",
+                "'symbol->string' function requires one symbol argument",
+                "'string->symbol' function requires one string argument",
+                "'string->symbol' function requires one string argument",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_variadic_procedures() {
+        let input = "
+            (define (list . items) items)
+
+            (define (max-of current items)
+                (if (empty-list? items)
+                    current
+                    (max-of (if (< current (car items)) (car items) current)
+                            (cdr items))))
+            (define (max first . rest) (max-of first rest))
+
+            (define count-arguments
+                (lambda args
+                    (if (empty-list? args) 0 (+ 1 (apply-count (cdr args))))))
+            (define (apply-count items)
+                (if (empty-list? items) 0 (+ 1 (apply-count (cdr items)))))
+
+            (list (max 3 9 2) (max 4) (count-arguments) (count-arguments 'a 'b 'c) (list))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), "(9 4 0 3 ())");
+    }
+
+    #[test]
+    fn test_rest_parameters_require_the_other_arguments() {
+        let input = "
+            (define (log level . messages) messages)
+            (log)
+        ";
+
+        let error = interpret(input).unwrap_err();
+
+        assert!(matches!(
+            error,
+            LisrError::Evaluation(Spanned {
+                value: LisrEvaluationError::RuntimeError {
+                    reason: "Function called with a wrong number of arguments"
+                },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_let_evaluates_values_outside_of_the_let() {
+        let input = "
+            (define x 1)
+            (let ((x 2)
+                  (y x))
+                (define z 3)
+                (+ x (+ y z)))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(6)
+            }
+        );
+    }
+
+    #[test]
+    fn test_let_star_binds_variables_in_sequence() {
+        let input = "
+            (define x 1)
+            (define f (let* ((x 2) (get-x (lambda () x)) (x 3)) get-x))
+            (let* ((x 10) (y (+ x 1))) (+ y (f)))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(13)
+            }
+        );
+    }
+
+    #[test]
+    fn test_letrec_binds_mutually_recursive_procedures() {
+        let input = "
+            (letrec ((even? (lambda (n) (if (= n 0) true (odd? (- n 1)))))
+                     (odd? (lambda (n) (if (= n 0) false (even? (- n 1))))))
+                (even? 100000))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result, Expression::True);
+    }
+
+    #[test]
+    fn test_letrec_star_values_see_earlier_variables() {
+        let input = "
+            (letrec* ((a 5)
+                      (b (* a 2)))
+                (+ a b))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(15)
+            }
+        );
+    }
+
+    #[test]
+    fn test_let_variables_are_not_visible_outside() {
+        let input = "
+            (let ((local 1)) local)
+            local
+        ";
+
+        let error = interpret(input).unwrap_err();
+
+        assert!(matches!(
+            error,
+            LisrError::Evaluation(Spanned {
+                value: LisrEvaluationError::UndefinedIdentifier,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_named_let_loops_in_constant_space() {
+        let input = "
+            (let loop ((i 0) (sum 0))
+                (if (= i 1000000)
+                    sum
+                    (loop (+ i 1) (+ sum 2))))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(2000000)
+            }
+        );
+    }
+
+    #[test]
+    fn test_named_let_inside_a_procedure() {
+        let input = "
+            (define (reverse items)
+                (let iter ((items items) (acc ()))
+                    (if (empty-list? items)
+                        acc
+                        (iter (cdr items) (cons (car items) acc)))))
+
+            (reverse '(1 2 3))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), "(3 2 1)");
+    }
+
+    #[test]
+    fn test_named_let_name_is_local() {
+        let input = "
+            (define (loop) 'outer)
+            (let loop ((i 0)) i)
+            (loop)
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Symbol {
+                value: Symbol::new("outer")
+            }
+        );
+    }
+
+    #[test]
+    fn test_cond() {
+        let input = "
+            (define (list . items) items)
+
+            (define (sign x)
+                (cond ((< x 0) 'negative)
+                      ((= x 0) 'zero)
+                      (else 'positive)))
+
+            (list (sign -5)
+                  (sign 0)
+                  (sign 7)
+                  (cond ((eq? 'b 'b) => (lambda (found) (if found 'found 'missing))))
+                  (cond (false 1) ((empty-list? ()))))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), "(negative zero positive found true)");
+    }
+
+    #[test]
+    fn test_cond_without_a_matching_clause_is_unspecified() {
+        let result = interpret("(cond (false 1))").unwrap();
+
+        assert_eq!(result, Expression::Unspecified);
+    }
+
+    #[test]
+    fn test_cond_clauses_are_in_tail_position() {
+        let input = "
+            (define (count-down n)
+                (cond ((= n 0) 'done)
+                      (else (count-down (- n 1)))))
+
+            (count-down 1000000)
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Symbol {
+                value: Symbol::new("done")
+            }
+        );
+    }
+
+    #[test]
+    fn test_case() {
+        let input = "
+            (define (list . items) items)
+
+            (define (describe x)
+                (case x
+                    ((1 2 3) 'small)
+                    ((\"one\" \"two\") 'word)
+                    ((red green) 'color)
+                    (else 'unknown)))
+
+            (list (describe 2)
+                  (describe \"two\")
+                  (describe 'green)
+                  (describe 42)
+                  (case 5 ((5) => (lambda (x) (* x x))))
+                  (case 'a ((b) 1)))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result.to_string(),
+            "(small word color unknown 25 #<unspecified>)"
+        );
+    }
+
+    #[test]
+    fn test_when_and_unless() {
+        let input = "
+            (define (list . items) items)
+            (define x 0)
+
+            (when (< x 1)
+                (set! x (+ x 1))
+                (set! x (+ x 10)))
+            (unless (< x 1)
+                (set! x (+ x 100)))
+
+            (list x (when false 1) (unless true 1))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), "(111 #<unspecified> #<unspecified>)");
+    }
+
+    #[test]
+    fn test_only_false_is_false() {
+        let input = "
+            (define (list . items) items)
+
+            (list (if 0 'true 'false)
+                  (if () 'true 'false)
+                  (if \"\" 'true 'false)
+                  (if false 'true 'false)
+                  (if false 'true))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), "(true true true false #<unspecified>)");
+    }
+
+    #[test]
+    fn test_and_and_or_return_the_last_evaluated_value() {
+        let input = "
+            (define (list . items) items)
+
+            (define (lookup key pairs)
+                (cond ((empty-list? pairs) false)
+                      ((eq? key (car (car pairs))) (cdr (car pairs)))
+                      (else (lookup key (cdr pairs)))))
+            (define settings '((color . red) (size . 10)))
+
+            (list (or (lookup 'color settings) 'blue)
+                  (or (lookup 'shape settings) 'circle)
+                  (and 1 2 3)
+                  (and 1 false 3)
+                  (or false false)
+                  (and)
+                  (or))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), "(red circle 3 false false true false)");
+    }
+
+    #[test]
+    fn test_tail_calls_in_and_and_or_run_in_constant_space() {
+        let input = "
+            (define (loop-and n) (and true (if (= n 0) 'done (loop-and (- n 1)))))
+            (define (loop-or n) (or false (if (= n 0) 'done (loop-or (- n 1)))))
+            (and (loop-and 100000) (loop-or 100000))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Symbol {
+                value: Symbol::new("done")
+            }
+        );
+    }
+
+    #[test]
+    fn test_and_stops_at_the_first_false_value() {
+        let input = "
+            (define x 0)
+            (and false (set! x 1))
+            (or 'found (set! x 2))
+            x
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(0)
+            }
+        );
+    }
+
+    #[test]
+    fn test_macros_do_not_capture_variables() {
+        let input = "
+            (define-syntax swap!
+              (syntax-rules ()
+                ((_ a b) (let ((tmp a)) (set! a b) (set! b tmp)))))
+            (define (list . items) items)
+            (define tmp 1)
+            (define other 2)
+            (swap! tmp other)
+            (list tmp other)
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), "(2 1)");
+    }
+
+    #[test]
+    fn test_macro_identifiers_refer_to_the_definitions_of_the_macro() {
+        let input = "
+            (define (list . items) items)
+            (define x 10)
+            (define-syntax get-x (syntax-rules () ((_) x)))
+            (define (g x) (get-x))
+            (define (helper) 'global)
+            (define-syntax call-helper (syntax-rules () ((_) (helper))))
+            (define (local-macro x)
+              (let-syntax ((get-local-x (syntax-rules () ((_) x))))
+                (let ((x 2))
+                  (list x (get-local-x) (get-x)))))
+
+            (list (g 1)
+                  (let ((helper (lambda () 'local))) (call-helper))
+                  (local-macro 7))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), "(10 global (2 7 10))");
+    }
+
+    #[test]
+    fn test_recursive_macros() {
+        let input = "
+            (define-syntax my-or
+              (syntax-rules ()
+                ((_) false)
+                ((_ e) e)
+                ((_ e rest ...) (let ((t e)) (if t t (my-or rest ...))))))
+            (define (list . items) items)
+            (define t 5)
+            (list (my-or) (my-or false t) (my-or 1 (car '())))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), "(false 5 1)");
+    }
+
+    #[test]
+    fn test_macros_with_bodies() {
+        let input = "
+            (define-syntax while
+              (syntax-rules ()
+                ((_ test body ...) (let loop () (when test body ... (loop))))))
+            (define i 0)
+            (define total 0)
+            (while (< i 5)
+              (set! total (+ total i))
+              (set! i (+ i 1)))
+            total
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(10)
+            }
+        );
+    }
+
+    #[test]
+    fn test_let_syntax() {
+        let input = "
+            (define (square x) (* x x))
+            (let-syntax ((twice (syntax-rules () ((_ f x) (f (f x))))))
+              (twice square 3))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(81)
+            }
+        );
+    }
+
+    #[test]
+    fn test_macros_are_kept_between_inputs() {
+        let mut interpreter = Interpreter::new();
+
+        interpreter
+            .interpret("(define-syntax double (syntax-rules () ((_ x) (* 2 x))))")
+            .unwrap();
+        let result = interpreter.interpret("(double 21)").unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(42)
+            }
+        );
+    }
+
+    #[test]
+    fn test_macro_without_a_matching_rule() {
+        let input = "
+            (define-syntax double (syntax-rules () ((_ x) (* 2 x))))
+            (double 1 2)
+        ";
+
+        let error = interpret(input).unwrap_err();
+
+        assert!(matches!(
+            error,
+            LisrError::Parse(Spanned {
+                value: LisrParseError::NoMatchingSyntaxRule { .. },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_procedure_macros() {
+        let input = "
+            (define (list . items) items)
+            (define-macro (my-unless test . body)
+              (list 'if test false (cons 'begin body)))
+            (define x 0)
+            (list (my-unless (= x 0) (set! x 1) 'nonzero)
+                  (my-unless (= x 1) (set! x 2) 'changed)
+                  x)
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), "(false changed 2)");
+    }
+
+    #[test]
+    fn test_procedure_macros_use_earlier_definitions() {
+        let input = "
+            (define (list . items) items)
+            (define (make-swap a b) (list 'let (list (list 'tmp a)) (list 'set! a b) (list 'set! b 'tmp)))
+            (define-macro (swap! a b) (make-swap a b))
+            (define first 1)
+            (define second 2)
+            (swap! first second)
+            (list first second)
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), "(2 1)");
+    }
+
+    #[test]
+    fn test_procedure_macros_are_not_hygienic() {
+        let input = "
+            (define (list . items) items)
+            (define-macro (with-it value body) (list 'let (list (list 'it value)) body))
+            (with-it (* 6 7) (+ it 1))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(43)
+            }
+        );
+    }
+
+    #[test]
+    fn test_quasiquote() {
+        let input = "
+            (define x 1)
+            (define rest '(2 3))
+            (define (square x) (* x x))
+            `(point ,x ,@rest (square ,(square 3)) ,@'() . ,(+ x 3))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), "(point 1 2 3 (square 9) . 4)");
+    }
+
+    #[test]
+    fn test_nested_quasiquote() {
+        let input = "
+            (define x 'outer)
+            `(a `(b ,(c ,x)))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result.to_string(),
+            "(a (quasiquote (b (unquote (c outer)))))"
+        );
+    }
+
+    #[test]
+    fn test_unquote_splicing_requires_a_list() {
+        let input = "
+            (define x 1)
+            `(a ,@x)
+        ";
+
+        let error = interpret(input).unwrap_err();
+
+        assert!(matches!(
+            error,
+            LisrError::Evaluation(Spanned {
+                value: LisrEvaluationError::RuntimeError {
+                    reason: "'unquote-splicing' requires a list"
+                },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_procedure_macros_with_quasiquote() {
+        let input = "
+            (define-macro (my-while test . body)
+              `(let loop () (when ,test ,@body (loop))))
+            (define i 0)
+            (define total 0)
+            (my-while (< i 4)
+              (set! total (+ total i))
+              (set! i (+ i 1)))
+            total
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result,
+            Expression::Number {
+                value: Number::Integer(6)
+            }
+        );
+    }
+
+    #[test]
+    fn test_exact_integers() {
+        let input = "
+            (define (list . items) items)
+            (list (* 4611686018427387904 2)
+                  (+ 9007199254740993 0)
+                  (exact? 1)
+                  (exact? 1.5)
+                  (inexact? (+ 1 0.5))
+                  (exact->inexact 3)
+                  (/ 10 4)
+                  (/ 10 5))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result.to_string(),
+            "(9223372036854775808 9007199254740993 true false true 3.0 5/2 2)"
+        );
+    }
+
+    #[test]
+    fn test_big_integers() {
+        let input = "
+            (define (list . items) items)
+            (define (square n) (* n n))
+            (define (fast-expt b n)
+              (cond ((= n 0) 1)
+                    ((even? n) (square (fast-expt b (/ n 2))))
+                    (else (* b (fast-expt b (- n 1))))))
+            (define (even? n) (= (remainder n 2) 0))
+            (define (factorial n) (if (= n 0) 1 (* n (factorial (- n 1)))))
+            (define big (fast-expt 2 200))
+            (list big
+                  (factorial 30)
+                  (- 0 big)
+                  (+ 123456789012345678901234567890 1)
+                  (< big (+ big 1))
+                  (= (/ big (fast-expt 2 100)) (fast-expt 2 100))
+                  (remainder big 1000000007)
+                  (remainder (factorial 30) (fast-expt 10 7))
+                  (gcd (factorial 30) big)
+                  (gcd)
+                  (- big big))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result.to_string(),
+            "(1606938044258990275541962092341162602522202993782792835301376 \
+             265252859812191058636308480000000 \
+             -1606938044258990275541962092341162602522202993782792835301376 \
+             123456789012345678901234567891 true true 499445072 0 67108864 0 0)"
+        );
+    }
+
+    #[test]
+    fn test_exact_rationals() {
+        let input = "
+            (define (list . items) items)
+            (define (sum-prices prices)
+              (if (empty-list? prices) 0 (+ (car prices) (sum-prices (cdr prices)))))
+            (list (/ 1 3)
+                  (+ 1/3 1/6)
+                  (* 2/3 3/2)
+                  (- 1/4 1)
+                  (/ 6 -4)
+                  (= (sum-prices (list 1/10 1/10 1/10)) 3/10)
+                  (< 1/3 0.34)
+                  (+ 1/2 0.25)
+                  (numerator 6/4)
+                  (denominator 6/4)
+                  (denominator 7)
+                  (denominator 0.5)
+                  (floor -7/2)
+                  (exact? 1/3)
+                  (exact->inexact 1/4))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result.to_string(),
+            "(1/3 1/2 1 -3/4 -3/2 true true 0.75 3 2 1 2.0 -4 true 0.25)"
+        );
+    }
+
+    #[test]
+    fn test_numeric_literals() {
+        let input = "
+            (define (list . items) items)
+            (list (+ #x1F #b1010 #o17 +1) 1e-3 #e1.25 #i1/4 -inf.0 (< -inf.0 -1e308))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), "(57 0.001 5/4 0.25 -inf.0 true)");
+    }
+
+    #[test]
+    fn test_number_comparisons() {
+        let input = "
+            (define (list . items) items)
+            (list (> 3 2) (> 2 3) (> 3 2 1) (> 3 3)
+                  (< 1 2 3) (< 1 3 2) (< \"a\" \"b\")
+                  (<= 1 1 2) (<= 2 1) (>= 2 2 1) (>= 1 2)
+                  (> 1/2 0.4) (<= +nan.0 1))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result.to_string(),
+            "(true false true false true false true true false true false true false)"
+        );
+    }
+
+    #[test]
+    fn test_math_primitives() {
+        let input = "
+            (define (list . items) items)
+            (list (abs -7) (abs -1/2) (abs -2.5)
+                  (min 3 1 2) (max 1 2.0) (max 1/2 1/3)
+                  (ceiling 1.2) (ceiling 7/2) (round 2.5) (round 3.5) (round 7/2) (truncate -2.7)
+                  (sqrt 16) (sqrt 1/4) (sqrt 2.25)
+                  (expt 2 10) (expt 2 -2) (expt 2.0 3) (expt 4 1/2)
+                  (exp 0) (log 1) (log 8 2) (sin 0) (cos 0) (tan 0) (atan 1 1)
+                  (gcd 12 18) (lcm 4 6) (lcm -4 6 10) (lcm))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result.to_string(),
+            "(7 1/2 2.5 1 2.0 1/2 2.0 4 2.0 4.0 4 -2.0 4 1/2 1.5 \
+             1024 1/4 8.0 2.0 1.0 0.0 3.0 0.0 1.0 0.0 0.7853981633974483 6 12 60 1)"
+        );
+    }
+
+    #[test]
+    fn test_number_predicates() {
+        let input = "
+            (define (list . items) items)
+            (list (number? 1) (number? 1/2) (number? 'one)
+                  (integer? 2) (integer? 2.0) (integer? 5/2) (integer? \"2\")
+                  (zero? 0) (zero? 0.0) (zero? 1/2)
+                  (positive? 1/2) (positive? 0) (negative? -0.5) (negative? 0)
+                  (even? 10) (even? 7) (odd? 7) (odd? -3) (even? 4.0))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result.to_string(),
+            "(true true false true true false false true true false \
+             true false true false true false true true true)"
+        );
+    }
+
+    #[test]
+    fn test_math_primitives_check_arity_and_types() {
+        for input in [
+            "(abs)",
+            "(abs 1 2)",
+            "(> 1)",
+            "(<)",
+            "(< 1)",
+            "(floor)",
+            "(min)",
+            "(expt 2)",
+            "(log 1 2 3)",
+            "(atan)",
+            "(number?)",
+        ] {
+            assert!(
+                matches!(
+                    interpret(input),
+                    Err(LisrError::Evaluation(Spanned {
+                        value: LisrEvaluationError::RuntimeError { .. },
+                        ..
+                    }))
+                ),
+                "{input}"
+            );
+        }
+        for input in [
+            "(abs 'a)",
+            "(> 1 \"2\")",
+            "(< 1 \"2\")",
+            "(floor 'a)",
+            "(even? 1.5)",
+            "(sqrt \"4\")",
+            "(lcm 1/2 3)",
+        ] {
+            assert!(
+                matches!(
+                    interpret(input),
+                    Err(LisrError::Evaluation(Spanned {
+                        value: LisrEvaluationError::TypeError,
+                        ..
+                    }))
+                ),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_string_library() {
+        let input = r#"
+            (define (list . items) items)
+            (define greeting "Zażółć gęślą jaźń")
+            (list (string-length greeting)
+                  (substring greeting 2 5)
+                  (substring greeting 13)
+                  (string-ref greeting 3)
+                  (string-append "a" "β" "" "ç")
+                  (string-append)
+                  (string-upcase "straße")
+                  (string-downcase "ÀB")
+                  (string-index greeting "gęś")
+                  (string-index greeting "xyz")
+                  (string? "") (string? 'a)
+                  (string=? "a" "a" "a") (string<? "a" "b" "c") (string>? "b" "a" "a"))
+        "#;
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result.to_string(),
+            r#"(17 "żół" "jaźń" #\ó "aβç" "" "STRASSE" "àb" 7 false true false true true false)"#
+        );
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        let input = r#"
+            (define (list . items) items)
+            (define line "name:\t\"lisr\"\n")
+            (list (string-length line) (string-split line "\t") (string-ref "\u{1F600}!" 0))
+        "#;
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), r#"(13 ("name:" "\"lisr\"\n") #\😀)"#);
+    }
+
+    #[test]
+    fn test_characters() {
+        let input = r#"
+            (define (list . items) items)
+            (list #\a #\space #\newline #\x41 #\( #\λ
+                  (char? #\a) (char? "a")
+                  (char->integer #\A) (integer->char 955)
+                  (char-upcase #\ä) (char-downcase #\Q) (char-upcase #\ß)
+                  (char-alphabetic? #\ж) (char-alphabetic? #\1)
+                  (char-numeric? #\7) (char-numeric? #\x)
+                  (char-whitespace? #\tab) (char-whitespace? #\a)
+                  (eq? (string-ref "abc" 1) #\b)
+                  (string-index "key=value" #\=)
+                  (list->string (list #\o #\k)))
+        "#;
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result.to_string(),
+            r#"(#\a #\space #\newline #\A #\( #\λ true false 65 #\λ #\Ä #\q #\ß true false true false true false true 3 "ok")"#
+        );
+    }
+
+    #[test]
+    fn test_tokenizing_with_characters() {
+        let input = r#"
+            (define (list . items) items)
+            (define (reverse items)
+              (let loop ((items items) (reversed ()))
+                (if (empty-list? items)
+                    reversed
+                    (loop (cdr items) (cons (car items) reversed)))))
+            (define (tokenize text)
+              (let loop ((chars (string->list text)) (word ()) (words ()))
+                (cond ((empty-list? chars)
+                       (reverse (if (empty-list? word)
+                                    words
+                                    (cons (list->string (reverse word)) words))))
+                      ((char-alphabetic? (car chars))
+                       (loop (cdr chars) (cons (char-downcase (car chars)) word) words))
+                      ((empty-list? word) (loop (cdr chars) word words))
+                      (else
+                       (loop (cdr chars) () (cons (list->string (reverse word)) words))))))
+            (tokenize "Hello, wide World!")
+        "#;
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), r#"("hello" "wide" "world")"#);
+    }
+
+    #[test]
+    fn test_character_primitives_check_arguments() {
+        assert!(matches!(
+            interpret("(integer->char 55296)"),
+            Err(LisrError::Evaluation(Spanned {
+                value: LisrEvaluationError::RuntimeError { .. },
+                ..
+            }))
+        ));
+        for input in [
+            r#"(char->integer "a")"#,
+            "(char-upcase 1)",
+            r#"(list->string "ab")"#,
+        ] {
+            assert!(
+                matches!(
+                    interpret(input),
+                    Err(LisrError::Evaluation(Spanned {
+                        value: LisrEvaluationError::TypeError,
+                        ..
+                    }))
+                ),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_splitting_and_joining_strings() {
+        let input = r#"
+            (define (list . items) items)
+            (list (string-split "a,b,,c" ",")
+                  (string-split "  one  two   three ")
+                  (string-join (string-split "2024-01-15" "-") "/")
+                  (string-join (list "x" "y"))
+                  (string-join (list))
+                  (string->list "añb"))
+        "#;
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result.to_string(),
+            r#"(("a" "b" "" "c") ("one" "two" "three") "2024/01/15" "x y" "" (#\a #\ñ #\b))"#
+        );
+    }
+
+    #[test]
+    fn test_converting_between_strings_and_numbers() {
+        let input = r##"
+            (define (list . items) items)
+            (list (string->number "42") (string->number "-1/3") (string->number "1e3")
+                  (string->number "ff" 16) (string->number "#b101")
+                  (string->number "abc") (string->number "")
+                  (number->string 42) (number->string 2.5) (number->string 255 16)
+                  (number->string -10 2) (number->string 3/4 2))
+        "##;
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(
+            result.to_string(),
+            r#"(42 -1/3 1000.0 255 5 false false "42" "2.5" "ff" "-1010" "11/100")"#
+        );
+    }
+
+    #[test]
+    fn test_string_library_checks_arguments() {
+        for input in [
+            r#"(substring "abc" 2 1)"#,
+            r#"(substring "abc" 0 4)"#,
+            r#"(string-ref "ąb" 2)"#,
+            r#"(string-ref "abc" -1)"#,
+            r#"(string-ref "abc" 100000000000000000000)"#,
+            r#"(substring "abc" 0 -100000000000000000000)"#,
+            r#"(string-split "abc" "")"#,
+            r#"(number->string 1.5 2)"#,
+            r#"(string->number "1" 3)"#,
+            r#"(string-length)"#,
+        ] {
+            assert!(
+                matches!(
+                    interpret(input),
+                    Err(LisrError::Evaluation(Spanned {
+                        value: LisrEvaluationError::RuntimeError { .. },
+                        ..
+                    }))
+                ),
+                "{input}"
+            );
+        }
+        for input in [
+            r#"(string-length 'abc)"#,
+            r#"(string-append "a" 1)"#,
+            r#"(substring "abc" 0.5)"#,
+            r#"(string-join "abc")"#,
+        ] {
+            assert!(
+                matches!(
+                    interpret(input),
+                    Err(LisrError::Evaluation(Spanned {
+                        value: LisrEvaluationError::TypeError,
+                        ..
+                    }))
+                ),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_integer_division() {
+        let input = "
+            (define (list . items) items)
+            (list (quotient 17 5) (quotient -17 5)
+                  (remainder 17 5) (remainder -17 5)
+                  (modulo 17 5) (modulo -17 5) (modulo 17 -5)
+                  (floor 2.5) (floor -2.5) (floor 7)
+                  (quotient 17.0 5))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), "(3 -3 2 -2 2 3 -3 2.0 -3.0 7 3.0)");
+    }
+
+    #[test]
+    fn test_exact_and_inexact_numbers_are_equal_by_value() {
+        let input = "
+            (define (list . items) items)
+            (list (= 1 1.0) (< 1 1.5) (eq? 2 2) (eq? 2 2.0))
+        ";
+
+        let result = interpret(input).unwrap();
+
+        assert_eq!(result.to_string(), "(true true true false)");
+    }
+
+    #[test]
+    fn test_division_by_exact_zero() {
+        let error = interpret("(quotient 1 0)").unwrap_err();
+
+        assert!(matches!(
+            error,
+            LisrError::Evaluation(Spanned {
+                value: LisrEvaluationError::RuntimeError {
+                    reason: "Division by zero"
+                },
+                ..
+            })
+        ));
     }
 }
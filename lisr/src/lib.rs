@@ -0,0 +1,18 @@
+#![feature(iterator_try_reduce)]
+
+pub mod big_integer;
+pub mod diagnostic;
+pub mod environment;
+pub mod evaluate;
+pub mod expand;
+pub mod expression;
+pub mod interpret;
+pub mod lisr_error;
+pub mod node;
+pub mod number;
+pub mod parse;
+pub mod scan;
+pub mod span;
+pub mod symbol;
+pub mod token;
+pub mod translate;
@@ -1,26 +1,85 @@
-use crate::{evaluate::LisrEvaluationError, parse::LisrParseError, scan::LisrScanError};
+use std::{error::Error, fmt};
+
+use crate::{
+    evaluate::LisrEvaluationError,
+    parse::LisrParseError,
+    scan::LisrScanError,
+    span::{Span, Spanned},
+};
 
 #[derive(Debug)]
 pub enum LisrError<'a> {
-    Scan(LisrScanError<'a>),
-    Parse(LisrParseError),
-    Evaluation(LisrEvaluationError),
+    Scan(Spanned<LisrScanError<'a>>),
+    Parse(Spanned<LisrParseError>),
+    Evaluation(Spanned<LisrEvaluationError>),
+}
+
+impl LisrError<'_> {
+    // The place in the source code that caused the error.
+    pub fn span(&self) -> Span {
+        match self {
+            LisrError::Scan(error) => error.span,
+            LisrError::Parse(error) => error.span,
+            LisrError::Evaluation(error) => error.span,
+        }
+    }
+
+    // Which stage of the interpreter has failed.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LisrError::Scan(_) => "scan",
+            LisrError::Parse(_) => "parse",
+            LisrError::Evaluation(_) => "evaluation",
+        }
+    }
+
+    // What went wrong, without the position.
+    pub fn message(&self) -> String {
+        match self {
+            LisrError::Scan(error) => error.value.to_string(),
+            LisrError::Parse(error) => error.value.to_string(),
+            LisrError::Evaluation(error) => error.value.to_string(),
+        }
+    }
+
+    // A hint on how to fix the error.
+    pub fn help(&self) -> Option<String> {
+        match self {
+            LisrError::Scan(error) => error.value.help(),
+            LisrError::Parse(error) => error.value.help(),
+            LisrError::Evaluation(error) => error.value.help(),
+        }
+    }
 }
 
-impl<'a> From<LisrScanError<'a>> for LisrError<'a> {
-    fn from(error: LisrScanError<'a>) -> Self {
+impl fmt::Display for LisrError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} error at {}: {}",
+            self.kind(),
+            self.span().start,
+            self.message()
+        )
+    }
+}
+
+impl Error for LisrError<'_> {}
+
+impl<'a> From<Spanned<LisrScanError<'a>>> for LisrError<'a> {
+    fn from(error: Spanned<LisrScanError<'a>>) -> Self {
         LisrError::Scan(error)
     }
 }
 
-impl From<LisrParseError> for LisrError<'_> {
-    fn from(error: LisrParseError) -> Self {
+impl From<Spanned<LisrParseError>> for LisrError<'_> {
+    fn from(error: Spanned<LisrParseError>) -> Self {
         LisrError::Parse(error)
     }
 }
 
-impl From<LisrEvaluationError> for LisrError<'_> {
-    fn from(error: LisrEvaluationError) -> Self {
+impl From<Spanned<LisrEvaluationError>> for LisrError<'_> {
+    fn from(error: Spanned<LisrEvaluationError>) -> Self {
         LisrError::Evaluation(error)
     }
 }
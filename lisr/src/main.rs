@@ -1,37 +1,34 @@
-#![feature(iterator_try_reduce)]
+use std::{env, io, process::ExitCode};
 
-use crate::interpret::interpret;
+mod repl;
+mod script;
 
-mod environment;
-mod evaluate;
-mod expression;
-mod interpret;
-mod lisr_error;
-mod node;
-mod parse;
-mod scan;
-mod token;
-mod translate;
+const USAGE: &str = "Usage:
+    lisr                             start an interactive session
+    lisr run <file.lisr> [args...]   run a script";
 
-fn main() {
-    let input = "
-        (define (reverse xs)
-            (begin
-                (define (iter ys accumulator)
-                    (if (empty-list? ys)
-                         accumulator
-                         (iter (cdr ys) (cons (car ys) accumulator))
-                    )
-                )
-                (iter xs ())
-            )
-        )
+fn main() -> ExitCode {
+    let arguments: Vec<String> = env::args().skip(1).collect();
 
-        (define one-two-three (cons 1 (cons 2 (cons 3 ()))))
-
-        (reverse one-two-three)
-    ";
+    match arguments.split_first() {
+        None => match repl::run(io::stdin().lock(), io::stdout()) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("lisr: {}", error);
+                ExitCode::FAILURE
+            }
+        },
+        Some((command, rest)) if command == "run" => match rest.split_first() {
+            Some((path, script_arguments)) => {
+                script::run(path, script_arguments, io::stdout(), io::stderr())
+            }
+            None => usage(),
+        },
+        Some(_) => usage(),
+    }
+}
 
-    let result = interpret(input);
-    println!("{:#?}", result);
+fn usage() -> ExitCode {
+    eprintln!("{}", USAGE);
+    ExitCode::from(2)
 }
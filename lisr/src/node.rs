@@ -1,9 +1,23 @@
 use std::collections::VecDeque;
 
-use crate::token::Token;
+use crate::{span::Span, token::Token};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Node {
-    Leaf { token: Token },
-    List { elements: VecDeque<Node> },
+    Leaf {
+        token: Token,
+        span: Span,
+    },
+    List {
+        elements: VecDeque<Node>,
+        span: Span,
+    },
+}
+
+impl Node {
+    pub fn span(&self) -> Span {
+        match self {
+            Node::Leaf { span, .. } | Node::List { span, .. } => *span,
+        }
+    }
 }
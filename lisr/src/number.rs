@@ -0,0 +1,613 @@
+use std::{cmp::Ordering, fmt};
+
+use crate::{big_integer::BigInteger, evaluate::LisrEvaluationError};
+
+// Integers and rationals are exact, reals are inexact. An operation with an
+// inexact operand gives an inexact result, so exactness is never made up.
+// Integers that fit in 64 bits are always stored as Integer, bigger ones as Big.
+// Rationals are always in lowest terms with a denominator greater than 1, so
+// `6/3` is stored as the integer 2.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Number {
+    Integer(i64),
+    Big(BigInteger),
+    Rational {
+        numerator: BigInteger,
+        denominator: BigInteger,
+    },
+    Real(f64),
+}
+
+impl Number {
+    pub fn from_big(value: BigInteger) -> Number {
+        match value.to_i64() {
+            Some(value) => Number::Integer(value),
+            None => Number::Big(value),
+        }
+    }
+
+    // Normalises the fraction. The denominator must not be zero.
+    pub fn from_fraction(numerator: BigInteger, denominator: BigInteger) -> Number {
+        let (numerator, denominator) = if denominator.is_negative() {
+            (numerator.negate(), denominator.negate())
+        } else {
+            (numerator, denominator)
+        };
+        let divisor = numerator.gcd(&denominator);
+        let (numerator, _) = numerator.divide_with_remainder(&divisor).unwrap();
+        let (denominator, _) = denominator.divide_with_remainder(&divisor).unwrap();
+        if denominator == BigInteger::from(1) {
+            Number::from_big(numerator)
+        } else {
+            Number::Rational {
+                numerator,
+                denominator,
+            }
+        }
+    }
+
+    pub fn is_exact(&self) -> bool {
+        !matches!(self, Number::Real(_))
+    }
+
+    pub fn to_inexact(&self) -> Number {
+        Number::Real(self.to_f64())
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Number::Integer(value) => *value as f64,
+            Number::Big(value) => value.to_f64(),
+            Number::Rational {
+                numerator,
+                denominator,
+            } => numerator.to_f64() / denominator.to_f64(),
+            Number::Real(value) => *value,
+        }
+    }
+
+    // Every finite real is a fraction with a power of two as its denominator.
+    pub fn to_exact(&self) -> Option<Number> {
+        let Number::Real(value) = self else {
+            return Some(self.clone());
+        };
+        if !value.is_finite() {
+            return None;
+        }
+        let bits = value.to_bits();
+        let biased_exponent = ((bits >> 52) & 0x7ff) as i32;
+        let fraction = (bits & ((1 << 52) - 1)) as i64;
+        // Subnormal numbers have no implicit leading 1.
+        let (mantissa, exponent) = if biased_exponent == 0 {
+            (fraction, -1074)
+        } else {
+            (fraction | (1 << 52), biased_exponent - 1075)
+        };
+        let mantissa = BigInteger::from(if *value < 0.0 { -mantissa } else { mantissa });
+        let power = BigInteger::from(2).pow(exponent.unsigned_abs());
+        if exponent >= 0 {
+            Some(Number::from_big(mantissa.multiply(&power)))
+        } else {
+            Some(Number::from_fraction(mantissa, power))
+        }
+    }
+
+    // Exact numbers as fractions, for exact arithmetic.
+    fn to_fraction(&self) -> Option<(BigInteger, BigInteger)> {
+        match self {
+            Number::Rational {
+                numerator,
+                denominator,
+            } => Some((numerator.clone(), denominator.clone())),
+            _ => self.to_big().map(|value| (value, BigInteger::from(1))),
+        }
+    }
+
+    // The numerator and denominator of inexact numbers are inexact too, so
+    // `(denominator 0.5)` is `2.0`.
+    pub fn numerator(&self) -> Option<Number> {
+        let (numerator, _) = self.to_exact()?.to_fraction()?;
+        Some(self.with_exactness_of(Number::from_big(numerator)))
+    }
+
+    pub fn denominator(&self) -> Option<Number> {
+        let (_, denominator) = self.to_exact()?.to_fraction()?;
+        Some(self.with_exactness_of(Number::from_big(denominator)))
+    }
+
+    fn with_exactness_of(&self, number: Number) -> Number {
+        if self.is_exact() {
+            number
+        } else {
+            number.to_inexact()
+        }
+    }
+
+    // Exact numbers as big integers, for operations that may not fit in 64 bits.
+    fn to_big(&self) -> Option<BigInteger> {
+        match self {
+            Number::Integer(value) => Some(BigInteger::from(*value)),
+            Number::Big(value) => Some(value.clone()),
+            Number::Rational { .. } | Number::Real(_) => None,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Number::Integer(value) => *value == 0,
+            Number::Big(_) | Number::Rational { .. } => false,
+            Number::Real(value) => *value == 0.0,
+        }
+    }
+
+    pub fn add(&self, other: &Number) -> Number {
+        if let (Number::Integer(a), Number::Integer(b)) = (self, other) {
+            if let Some(sum) = a.checked_add(*b) {
+                return Number::Integer(sum);
+            }
+        }
+        match (self.to_fraction(), other.to_fraction()) {
+            (Some((a, b)), Some((c, d))) => {
+                Number::from_fraction(a.multiply(&d).add(&c.multiply(&b)), b.multiply(&d))
+            }
+            _ => Number::Real(self.to_f64() + other.to_f64()),
+        }
+    }
+
+    pub fn subtract(&self, other: &Number) -> Number {
+        if let (Number::Integer(a), Number::Integer(b)) = (self, other) {
+            if let Some(difference) = a.checked_sub(*b) {
+                return Number::Integer(difference);
+            }
+        }
+        match (self.to_fraction(), other.to_fraction()) {
+            (Some((a, b)), Some((c, d))) => {
+                Number::from_fraction(a.multiply(&d).subtract(&c.multiply(&b)), b.multiply(&d))
+            }
+            _ => Number::Real(self.to_f64() - other.to_f64()),
+        }
+    }
+
+    pub fn multiply(&self, other: &Number) -> Number {
+        if let (Number::Integer(a), Number::Integer(b)) = (self, other) {
+            if let Some(product) = a.checked_mul(*b) {
+                return Number::Integer(product);
+            }
+        }
+        match (self.to_fraction(), other.to_fraction()) {
+            (Some((a, b)), Some((c, d))) => Number::from_fraction(a.multiply(&c), b.multiply(&d)),
+            _ => Number::Real(self.to_f64() * other.to_f64()),
+        }
+    }
+
+    // Dividing exact numbers gives an exact result, so `(/ 1 3)` is `1/3`.
+    pub fn divide(&self, other: &Number) -> Result<Number, LisrEvaluationError> {
+        match (self.to_fraction(), other.to_fraction()) {
+            (Some(_), Some((c, _))) if c.is_zero() => Err(division_by_zero()),
+            (Some((a, b)), Some((c, d))) => {
+                Ok(Number::from_fraction(a.multiply(&d), b.multiply(&c)))
+            }
+            _ => Ok(Number::Real(self.to_f64() / other.to_f64())),
+        }
+    }
+
+    // The quotient rounded towards zero.
+    pub fn quotient(&self, other: &Number) -> Result<Number, LisrEvaluationError> {
+        match (self.to_big(), other.to_big()) {
+            (Some(a), Some(b)) => a
+                .divide_with_remainder(&b)
+                .map(|(quotient, _)| Number::from_big(quotient))
+                .ok_or_else(division_by_zero),
+            _ => Ok(Number::Real((self.to_f64() / other.to_f64()).trunc())),
+        }
+    }
+
+    // Has the sign of the dividend, like `%`.
+    pub fn remainder(&self, other: &Number) -> Result<Number, LisrEvaluationError> {
+        match (self, other) {
+            (Number::Integer(_), Number::Integer(0)) => Err(division_by_zero()),
+            // Only i64::MIN % -1 overflows, and its remainder is 0.
+            (Number::Integer(a), Number::Integer(b)) => {
+                Ok(Number::Integer(a.checked_rem(*b).unwrap_or(0)))
+            }
+            _ => match (self.to_big(), other.to_big()) {
+                (Some(a), Some(b)) => a
+                    .divide_with_remainder(&b)
+                    .map(|(_, remainder)| Number::from_big(remainder))
+                    .ok_or_else(division_by_zero),
+                _ => Ok(Number::Real(self.to_f64() % other.to_f64())),
+            },
+        }
+    }
+
+    // Has the sign of the divisor.
+    pub fn modulo(&self, other: &Number) -> Result<Number, LisrEvaluationError> {
+        let remainder = self.remainder(other)?;
+        let has_sign_of_divisor =
+            remainder.is_zero() || remainder.is_negative() == other.is_negative();
+        if has_sign_of_divisor {
+            Ok(remainder)
+        } else {
+            Ok(remainder.add(other))
+        }
+    }
+
+    // The greatest common divisor of integers, which is never negative.
+    pub fn gcd(&self, other: &Number) -> Result<Number, LisrEvaluationError> {
+        match (self.to_big(), other.to_big()) {
+            (Some(a), Some(b)) => Ok(Number::from_big(a.gcd(&b))),
+            _ if self.is_integer() && other.is_integer() => {
+                let (mut a, mut b) = (self.to_f64().abs(), other.to_f64().abs());
+                while b != 0.0 {
+                    (a, b) = (b, a % b);
+                }
+                Ok(Number::Real(a))
+            }
+            _ => Err(LisrEvaluationError::TypeError),
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        match self {
+            Number::Integer(_) | Number::Big(_) => true,
+            Number::Rational { .. } => false,
+            Number::Real(value) => value.fract() == 0.0,
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        match self {
+            Number::Integer(value) => *value < 0,
+            Number::Big(value) => value.is_negative(),
+            Number::Rational { numerator, .. } => numerator.is_negative(),
+            Number::Real(value) => *value < 0.0,
+        }
+    }
+
+    pub fn floor(&self) -> Number {
+        match self {
+            Number::Rational {
+                numerator,
+                denominator,
+            } => {
+                let (quotient, remainder) = numerator.divide_with_remainder(denominator).unwrap();
+                if remainder.is_negative() {
+                    Number::from_big(quotient.subtract(&BigInteger::from(1)))
+                } else {
+                    Number::from_big(quotient)
+                }
+            }
+            Number::Real(value) => Number::Real(value.floor()),
+            _ => self.clone(),
+        }
+    }
+
+    pub fn ceiling(&self) -> Number {
+        match self {
+            Number::Rational { .. } => self.floor().add(&Number::Integer(1)),
+            Number::Real(value) => Number::Real(value.ceil()),
+            _ => self.clone(),
+        }
+    }
+
+    pub fn truncate(&self) -> Number {
+        match self {
+            Number::Rational {
+                numerator,
+                denominator,
+            } => Number::from_big(numerator.divide_with_remainder(denominator).unwrap().0),
+            Number::Real(value) => Number::Real(value.trunc()),
+            _ => self.clone(),
+        }
+    }
+
+    // Rounds halves to the even integer, so `(round 5/2)` is `2`.
+    pub fn round(&self) -> Number {
+        match self {
+            // Rationals are in lowest terms, so only halves have a denominator of 2.
+            Number::Rational { denominator, .. } if *denominator == BigInteger::from(2) => {
+                let floor = self.floor();
+                if floor.remainder(&Number::Integer(2)) == Ok(Number::Integer(0)) {
+                    floor
+                } else {
+                    floor.add(&Number::Integer(1))
+                }
+            }
+            Number::Rational { .. } => self
+                .add(&Number::from_fraction(
+                    BigInteger::from(1),
+                    BigInteger::from(2),
+                ))
+                .floor(),
+            Number::Real(value) => Number::Real(value.round_ties_even()),
+            _ => self.clone(),
+        }
+    }
+
+    pub fn abs(&self) -> Number {
+        match self {
+            Number::Real(value) => Number::Real(value.abs()),
+            _ if self.is_negative() => Number::Integer(0).subtract(self),
+            _ => self.clone(),
+        }
+    }
+
+    // The square root of an exact number is exact if it is a fraction of
+    // perfect squares, so `(sqrt 16)` is `4` and `(sqrt 2)` is inexact.
+    pub fn sqrt(&self) -> Number {
+        if let Some((numerator, denominator)) = self.to_fraction() {
+            if !numerator.is_negative() {
+                let (numerator_root, denominator_root) = (numerator.sqrt(), denominator.sqrt());
+                if numerator_root.multiply(&numerator_root) == numerator
+                    && denominator_root.multiply(&denominator_root) == denominator
+                {
+                    return Number::from_fraction(numerator_root, denominator_root);
+                }
+            }
+        }
+        Number::Real(self.to_f64().sqrt())
+    }
+
+    // An exact number to an integer power is exact.
+    pub fn expt(&self, exponent: &Number) -> Result<Number, LisrEvaluationError> {
+        let (Some((numerator, denominator)), Some(power)) = (self.to_fraction(), exponent.to_big())
+        else {
+            return Ok(Number::Real(self.to_f64().powf(exponent.to_f64())));
+        };
+        let Some(magnitude) = power
+            .abs()
+            .to_i64()
+            .and_then(|power| u32::try_from(power).ok())
+        else {
+            return Err(LisrEvaluationError::RuntimeError {
+                reason: "The exponent is too big for an exact power",
+            });
+        };
+        if !power.is_negative() {
+            Ok(Number::from_fraction(
+                numerator.pow(magnitude),
+                denominator.pow(magnitude),
+            ))
+        } else if numerator.is_zero() {
+            Err(division_by_zero())
+        } else {
+            Ok(Number::from_fraction(
+                denominator.pow(magnitude),
+                numerator.pow(magnitude),
+            ))
+        }
+    }
+
+    // The least common multiple of integers, which is never negative.
+    pub fn lcm(&self, other: &Number) -> Result<Number, LisrEvaluationError> {
+        let divisor = self.gcd(other)?;
+        if divisor.is_zero() {
+            return Ok(divisor);
+        }
+        self.multiply(other).abs().quotient(&divisor)
+    }
+
+    // Only exact numbers can be written in a radix other than 10.
+    pub fn to_string_radix(&self, radix: u32) -> Option<String> {
+        match self {
+            _ if radix == 10 => Some(self.to_string()),
+            Number::Integer(value) => Some(BigInteger::from(*value).to_string_radix(radix)),
+            Number::Big(value) => Some(value.to_string_radix(radix)),
+            Number::Rational {
+                numerator,
+                denominator,
+            } => Some(format!(
+                "{}/{}",
+                numerator.to_string_radix(radix),
+                denominator.to_string_radix(radix)
+            )),
+            Number::Real(_) => None,
+        }
+    }
+
+    // Compares numbers by their values, so `1` equals `1.0`.
+    pub fn compare(&self, other: &Number) -> Option<Ordering> {
+        match (self, other) {
+            (Number::Integer(a), Number::Integer(b)) => Some(a.cmp(b)),
+            _ => match (self.to_fraction(), other.to_fraction()) {
+                (Some((a, b)), Some((c, d))) => Some(a.multiply(&d).cmp(&c.multiply(&b))),
+                _ => self.to_f64().partial_cmp(&other.to_f64()),
+            },
+        }
+    }
+}
+
+fn division_by_zero() -> LisrEvaluationError {
+    LisrEvaluationError::RuntimeError {
+        reason: "Division by zero",
+    }
+}
+
+// Inexact numbers always have a decimal point, so `(exact->inexact 1)` is
+// printed as `1.0`.
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Number::Integer(value) => write!(f, "{}", value),
+            Number::Big(value) => write!(f, "{}", value),
+            Number::Rational {
+                numerator,
+                denominator,
+            } => write!(f, "{}/{}", numerator, denominator),
+            Number::Real(value) if value.is_nan() => write!(f, "+nan.0"),
+            Number::Real(value) if value.is_infinite() && *value > 0.0 => write!(f, "+inf.0"),
+            Number::Real(value) if value.is_infinite() => write!(f, "-inf.0"),
+            Number::Real(value) => write!(f, "{:?}", value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_keep_integers_exact() {
+        let result = Number::Integer(7).multiply(&Number::Integer(6));
+
+        assert_eq!(result, Number::Integer(42));
+        assert!(result.is_exact());
+    }
+
+    #[test]
+    fn should_make_results_with_an_inexact_operand_inexact() {
+        assert_eq!(
+            Number::Integer(1).add(&Number::Real(0.5)),
+            Number::Real(1.5)
+        );
+        assert_eq!(
+            Number::Real(2.0).multiply(&Number::Integer(3)),
+            Number::Real(6.0)
+        );
+    }
+
+    #[test]
+    fn should_promote_overflowing_integers_to_big_integers() {
+        let result = Number::Integer(i64::MAX).add(&Number::Integer(1));
+
+        assert_eq!(result.to_string(), "9223372036854775808");
+        assert!(matches!(result, Number::Big(_)));
+        assert_eq!(
+            result.subtract(&Number::Integer(1)),
+            Number::Integer(i64::MAX)
+        );
+    }
+
+    #[test]
+    fn should_divide_integers_exactly_if_possible() {
+        assert_eq!(
+            Number::Integer(10).divide(&Number::Integer(5)),
+            Ok(Number::Integer(2))
+        );
+        assert_eq!(
+            Number::Integer(1).divide(&Number::Real(4.0)),
+            Ok(Number::Real(0.25))
+        );
+        assert!(Number::Integer(1).divide(&Number::Integer(0)).is_err());
+    }
+
+    #[test]
+    fn should_keep_fractions_exact_and_in_lowest_terms() {
+        let third = Number::Integer(1).divide(&Number::Integer(3)).unwrap();
+        let sixth = Number::Integer(-2).divide(&Number::Integer(-12)).unwrap();
+
+        assert_eq!(third.to_string(), "1/3");
+        assert_eq!(sixth.to_string(), "1/6");
+        assert_eq!(third.add(&sixth).to_string(), "1/2");
+        assert_eq!(third.multiply(&Number::Integer(3)), Number::Integer(1));
+        assert_eq!(third.subtract(&Number::Integer(1)).to_string(), "-2/3");
+        assert_eq!(third.add(&Number::Real(0.5)), Number::Real(1.0 / 3.0 + 0.5));
+        assert_eq!(third.compare(&Number::Real(0.3)), Some(Ordering::Greater));
+    }
+
+    #[test]
+    fn should_floor_fractions_towards_negative_infinity() {
+        let fraction = |a, b| Number::Integer(a).divide(&Number::Integer(b)).unwrap();
+
+        assert_eq!(fraction(7, 2).floor(), Number::Integer(3));
+        assert_eq!(fraction(-7, 2).floor(), Number::Integer(-4));
+    }
+
+    #[test]
+    fn should_find_numerators_and_denominators() {
+        let fraction = Number::Integer(6).divide(&Number::Integer(-4)).unwrap();
+
+        assert_eq!(fraction.numerator(), Some(Number::Integer(-3)));
+        assert_eq!(fraction.denominator(), Some(Number::Integer(2)));
+        assert_eq!(Number::Integer(5).denominator(), Some(Number::Integer(1)));
+        assert_eq!(Number::Real(0.75).numerator(), Some(Number::Real(3.0)));
+        assert_eq!(Number::Real(0.75).denominator(), Some(Number::Real(4.0)));
+        assert_eq!(Number::Real(f64::NAN).denominator(), None);
+    }
+
+    #[test]
+    fn should_round_fractions_and_reals() {
+        let fraction = |a, b| Number::Integer(a).divide(&Number::Integer(b)).unwrap();
+
+        assert_eq!(fraction(5, 2).round(), Number::Integer(2));
+        assert_eq!(fraction(7, 2).round(), Number::Integer(4));
+        assert_eq!(fraction(-7, 3).round(), Number::Integer(-2));
+        assert_eq!(fraction(-7, 2).ceiling(), Number::Integer(-3));
+        assert_eq!(fraction(-7, 2).truncate(), Number::Integer(-3));
+        assert_eq!(Number::Real(2.5).round(), Number::Real(2.0));
+        assert_eq!(Number::Real(-2.5).truncate(), Number::Real(-2.0));
+    }
+
+    #[test]
+    fn should_find_exact_square_roots_if_possible() {
+        let fraction = |a, b| Number::Integer(a).divide(&Number::Integer(b)).unwrap();
+
+        assert_eq!(Number::Integer(16).sqrt(), Number::Integer(4));
+        assert_eq!(fraction(9, 4).sqrt(), fraction(3, 2));
+        assert_eq!(Number::Integer(2).sqrt(), Number::Real(2f64.sqrt()));
+        assert!(Number::Integer(-4).sqrt().to_f64().is_nan());
+    }
+
+    #[test]
+    fn should_raise_exact_numbers_to_exact_powers() {
+        let fraction = |a, b| Number::Integer(a).divide(&Number::Integer(b)).unwrap();
+
+        assert_eq!(
+            Number::Integer(2).expt(&Number::Integer(-2)),
+            Ok(fraction(1, 4))
+        );
+        assert_eq!(
+            fraction(-2, 3).expt(&Number::Integer(3)),
+            Ok(fraction(-8, 27))
+        );
+        assert_eq!(
+            Number::Integer(4).expt(&Number::Real(0.5)),
+            Ok(Number::Real(2.0))
+        );
+        assert!(Number::Integer(0).expt(&Number::Integer(-1)).is_err());
+    }
+
+    #[test]
+    fn should_round_quotients_towards_zero() {
+        assert_eq!(
+            Number::Integer(-7).quotient(&Number::Integer(2)),
+            Ok(Number::Integer(-3))
+        );
+        assert_eq!(
+            Number::Real(7.0).quotient(&Number::Integer(2)),
+            Ok(Number::Real(3.0))
+        );
+    }
+
+    #[test]
+    fn should_give_remainder_the_sign_of_dividend_and_modulo_the_sign_of_divisor() {
+        let remainder = |a, b| Number::Integer(a).remainder(&Number::Integer(b)).unwrap();
+        let modulo = |a, b| Number::Integer(a).modulo(&Number::Integer(b)).unwrap();
+
+        assert_eq!(remainder(-7, 2), Number::Integer(-1));
+        assert_eq!(modulo(-7, 2), Number::Integer(1));
+        assert_eq!(modulo(7, -2), Number::Integer(-1));
+        assert_eq!(modulo(6, -2), Number::Integer(0));
+        assert_eq!(remainder(i64::MIN, -1), Number::Integer(0));
+    }
+
+    #[test]
+    fn should_compare_numbers_by_value() {
+        assert_eq!(
+            Number::Integer(1).compare(&Number::Real(1.0)),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            Number::Integer(2).compare(&Number::Real(1.5)),
+            Some(Ordering::Greater)
+        );
+    }
+
+    #[test]
+    fn should_print_inexact_numbers_with_a_decimal_point() {
+        assert_eq!(Number::Integer(20).to_string(), "20");
+        assert_eq!(Number::Real(20.0).to_string(), "20.0");
+        assert_eq!(Number::Real(0.25).to_string(), "0.25");
+        assert_eq!(Number::Real(f64::NEG_INFINITY).to_string(), "-inf.0");
+    }
+}
@@ -1,11 +1,18 @@
 // Lisr's grammar:
-// list <- "(" { list } ")" | leaf
-// leaf <- identifier | string | quotation | number;
+// list <- "(" { list } ")" | quote list | leaf
+// quote <- "'" | "`" | "," | ",@"
+// leaf <- identifier | string | number;
+//
+// `'datum` is parsed as `(quote datum)`, `` `datum `` as `(quasiquote datum)`,
+// `,datum` as `(unquote datum)` and `,@datum` as `(unquote-splicing datum)`.
 
 use std::collections::VecDeque;
+use std::fmt;
 use std::iter::Peekable;
 
+use crate::evaluate::LisrEvaluationError;
 use crate::node::Node;
+use crate::span::{Span, Spanned};
 use crate::token::Token;
 
 #[derive(Debug, PartialEq)]
@@ -15,14 +22,198 @@ pub enum LisrParseError {
     ObjectNotInvokable,
     VariableRequiredInThisContext,
     AssignmentRequiresOneVariableAndOneValue,
-    IfRequiresPredicateConsequentAndAlternative,
+    IfRequiresPredicateAndConsequent {
+        found: usize,
+    },
     LambdaRequiresParameterListAndBody,
     UnexpectedExpressionForLambdaParameter,
     DefinitionRequiresVariableAndBody,
     ConsRequiresTwoArguments,
+    QuoteRequiresOneDatum,
+    UnexpectedDot,
+    LetRequiresBindingsAndBody,
+    BindingRequiresVariableAndValue,
+    CondRequiresClauses,
+    MalformedCondClause,
+    CaseRequiresKeyAndClauses,
+    MalformedCaseClause,
+    WhenRequiresTestAndBody,
+    UnlessRequiresTestAndBody,
+    DefineSyntaxRequiresNameAndRules,
+    LetSyntaxRequiresBindingsAndBody,
+    MalformedSyntaxRules,
+    NoMatchingSyntaxRule {
+        name: String,
+    },
+    DefineMacroRequiresNameAndProcedure,
+    MacroFailed {
+        name: String,
+        error: LisrEvaluationError,
+    },
+    ValueIsNotCode {
+        value: String,
+    },
+    UnquoteOutsideOfQuasiquote,
+    UnquoteSplicingOutsideOfList,
 }
 
-pub fn parse(tokens: Vec<Token>) -> Result<Vec<Node>, LisrParseError> {
+impl LisrParseError {
+    // A hint on how to fix the error.
+    pub fn help(&self) -> Option<String> {
+        match self {
+            LisrParseError::UnexpectedRightParentheses => {
+                Some(String::from("remove it or add a matching `(` before it"))
+            }
+            LisrParseError::UnclosedList => {
+                Some(String::from("this `(` does not have a matching `)`"))
+            }
+            LisrParseError::ObjectNotInvokable => Some(String::from(
+                "only procedures can be invoked - the first element of a list must be a procedure",
+            )),
+            LisrParseError::VariableRequiredInThisContext => {
+                Some(String::from("expected an identifier"))
+            }
+            LisrParseError::AssignmentRequiresOneVariableAndOneValue => Some(String::from(
+                "set! requires a variable and a value, like (set! x 42)",
+            )),
+            LisrParseError::IfRequiresPredicateAndConsequent { found } => Some(format!(
+                "if requires a predicate, a consequent and an optional alternative; found {} {}",
+                found,
+                if *found == 1 { "form" } else { "forms" }
+            )),
+            LisrParseError::LambdaRequiresParameterListAndBody => Some(String::from(
+                "lambda requires a parameter list and a body, like (lambda (x) (* x x))",
+            )),
+            LisrParseError::UnexpectedExpressionForLambdaParameter => {
+                Some(String::from("parameters must be identifiers"))
+            }
+            LisrParseError::DefinitionRequiresVariableAndBody => Some(String::from(
+                "define requires a variable and a value, like (define x 42) or (define (f x) x)",
+            )),
+            LisrParseError::ConsRequiresTwoArguments => Some(String::from(
+                "cons requires two arguments, like (cons 1 ())",
+            )),
+            LisrParseError::QuoteRequiresOneDatum => Some(String::from(
+                "quote requires exactly one datum, like (quote (1 2)) or '(1 2)",
+            )),
+            LisrParseError::LetRequiresBindingsAndBody => Some(String::from(
+                "let requires a list of bindings and a body, like (let ((x 1) (y 2)) (+ x y))",
+            )),
+            LisrParseError::BindingRequiresVariableAndValue => Some(String::from(
+                "a binding is a list of a variable and its value, like (x 1)",
+            )),
+            LisrParseError::CondRequiresClauses => Some(String::from(
+                "cond requires at least one clause, like (cond ((< x 0) 'negative) (else 'positive))",
+            )),
+            LisrParseError::MalformedCondClause => Some(String::from(
+                "a clause is a list of a test and expressions, like ((< x 0) 'negative), (test => receiver) or (else 'positive) as the last clause",
+            )),
+            LisrParseError::CaseRequiresKeyAndClauses => Some(String::from(
+                "case requires a key and clauses, like (case x ((1 2) 'small) (else 'big))",
+            )),
+            LisrParseError::MalformedCaseClause => Some(String::from(
+                "a clause is a list of data and expressions, like ((1 2) 'small), ((1 2) => receiver) or (else 'big) as the last clause",
+            )),
+            LisrParseError::WhenRequiresTestAndBody => Some(String::from(
+                "when requires a test and a body, like (when (< x 0) (display x))",
+            )),
+            LisrParseError::UnlessRequiresTestAndBody => Some(String::from(
+                "unless requires a test and a body, like (unless (< x 0) (display x))",
+            )),
+            LisrParseError::DefineSyntaxRequiresNameAndRules => Some(String::from(
+                "define-syntax requires a name and syntax rules, like (define-syntax my-if (syntax-rules () ((_ c a b) (cond (c a) (else b)))))",
+            )),
+            LisrParseError::LetSyntaxRequiresBindingsAndBody => Some(String::from(
+                "let-syntax requires a list of macros and a body, like (let-syntax ((double (syntax-rules () ((_ x) (* 2 x))))) (double 21))",
+            )),
+            LisrParseError::MalformedSyntaxRules => Some(String::from(
+                "syntax-rules requires a list of literals and rules made of a pattern and a template, like (syntax-rules () ((_ x) (* x x))); an ellipsis has to follow a pattern variable matched by an ellipsis",
+            )),
+            LisrParseError::NoMatchingSyntaxRule { name } => Some(format!(
+                "the form has to match one of the patterns in the syntax rules of `{}`",
+                name
+            )),
+            LisrParseError::DefineMacroRequiresNameAndProcedure => Some(String::from(
+                "define-macro requires a name with parameters and a body, like (define-macro (my-unless test body) `(if ,test false ,body))",
+            )),
+            LisrParseError::MacroFailed { error, .. } => error.help(),
+            LisrParseError::ValueIsNotCode { .. } => Some(String::from(
+                "a macro has to return code made of lists, symbols, numbers, strings and booleans",
+            )),
+            LisrParseError::UnquoteOutsideOfQuasiquote => Some(String::from(
+                "unquote can only be used inside of a quasiquote, like `(a ,b)",
+            )),
+            LisrParseError::UnquoteSplicingOutsideOfList => Some(String::from(
+                "unquote-splicing can only be used for an element of a list, like `(a ,@rest)",
+            )),
+            LisrParseError::UnexpectedDot => Some(String::from(
+                "a dot can only come before the last parameter, like (lambda (a . rest) a), or before the last element of a quoted list, like '(1 . 2)",
+            )),
+        }
+    }
+}
+
+impl fmt::Display for LisrParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LisrParseError::UnexpectedRightParentheses => write!(f, "unexpected `)`"),
+            LisrParseError::UnclosedList => write!(f, "unclosed list"),
+            LisrParseError::ObjectNotInvokable => write!(f, "object is not invokable"),
+            LisrParseError::VariableRequiredInThisContext => {
+                write!(f, "variable required in this context")
+            }
+            LisrParseError::AssignmentRequiresOneVariableAndOneValue => {
+                write!(f, "malformed assignment")
+            }
+            LisrParseError::IfRequiresPredicateAndConsequent { .. } => {
+                write!(f, "malformed if expression")
+            }
+            LisrParseError::LambdaRequiresParameterListAndBody => write!(f, "malformed lambda"),
+            LisrParseError::UnexpectedExpressionForLambdaParameter => {
+                write!(f, "unexpected expression for a lambda parameter")
+            }
+            LisrParseError::DefinitionRequiresVariableAndBody => {
+                write!(f, "malformed definition")
+            }
+            LisrParseError::ConsRequiresTwoArguments => write!(f, "malformed cons"),
+            LisrParseError::QuoteRequiresOneDatum => write!(f, "malformed quotation"),
+            LisrParseError::UnexpectedDot => write!(f, "unexpected `.`"),
+            LisrParseError::LetRequiresBindingsAndBody => write!(f, "malformed let"),
+            LisrParseError::BindingRequiresVariableAndValue => write!(f, "malformed binding"),
+            LisrParseError::CondRequiresClauses => write!(f, "malformed cond"),
+            LisrParseError::MalformedCondClause => write!(f, "malformed cond clause"),
+            LisrParseError::CaseRequiresKeyAndClauses => write!(f, "malformed case"),
+            LisrParseError::MalformedCaseClause => write!(f, "malformed case clause"),
+            LisrParseError::WhenRequiresTestAndBody => write!(f, "malformed when"),
+            LisrParseError::UnlessRequiresTestAndBody => write!(f, "malformed unless"),
+            LisrParseError::DefineSyntaxRequiresNameAndRules => {
+                write!(f, "malformed define-syntax")
+            }
+            LisrParseError::LetSyntaxRequiresBindingsAndBody => write!(f, "malformed let-syntax"),
+            LisrParseError::MalformedSyntaxRules => write!(f, "malformed syntax-rules"),
+            LisrParseError::NoMatchingSyntaxRule { name } => {
+                write!(f, "invalid use of macro `{}`", name)
+            }
+            LisrParseError::DefineMacroRequiresNameAndProcedure => {
+                write!(f, "malformed define-macro")
+            }
+            LisrParseError::MacroFailed { name, error } => {
+                write!(f, "expansion of macro `{}` failed: {}", name, error)
+            }
+            LisrParseError::ValueIsNotCode { value } => {
+                write!(f, "{} cannot be turned into code", value)
+            }
+            LisrParseError::UnquoteOutsideOfQuasiquote => {
+                write!(f, "unquote outside of quasiquote")
+            }
+            LisrParseError::UnquoteSplicingOutsideOfList => {
+                write!(f, "unquote-splicing outside of a list")
+            }
+        }
+    }
+}
+
+pub fn parse(tokens: Vec<Spanned<Token>>) -> Result<Vec<Node>, Spanned<LisrParseError>> {
     let mut nodes: Vec<Node> = Vec::new();
     let mut tokens = tokens.iter().peekable();
 
@@ -35,23 +226,53 @@ pub fn parse(tokens: Vec<Token>) -> Result<Vec<Node>, LisrParseError> {
 }
 
 fn parse_list<'a>(
-    tokens: &mut Peekable<impl Iterator<Item = &'a Token>>,
-) -> Result<Node, LisrParseError> {
-    if let Some(&token) = tokens.peek() {
+    tokens: &mut Peekable<impl Iterator<Item = &'a Spanned<Token>>>,
+) -> Result<Node, Spanned<LisrParseError>> {
+    if let Some(&Spanned { value: token, span }) = tokens.peek() {
         match token {
             Token::LeftParen => {
                 tokens.next();
+                let (elements, end) = parse_list_elements(tokens, *span)?;
                 return Ok(Node::List {
-                    elements: parse_list_elements(tokens)?,
+                    elements,
+                    span: Span::new(span.start, end.end),
                 });
             }
             Token::RightParen => {
-                return Err(LisrParseError::UnexpectedRightParentheses);
+                return Err(Spanned::new(
+                    LisrParseError::UnexpectedRightParentheses,
+                    *span,
+                ));
+            }
+            Token::Apostrophe | Token::Backquote | Token::Comma | Token::CommaAt => {
+                let keyword = match token {
+                    Token::Apostrophe => Token::Quote,
+                    Token::Backquote => Token::Quasiquote,
+                    Token::Comma => Token::Unquote,
+                    _ => Token::UnquoteSplicing,
+                };
+                tokens.next();
+                if tokens.peek().is_none() {
+                    return Err(Spanned::new(LisrParseError::QuoteRequiresOneDatum, *span));
+                }
+                let datum = parse_list(tokens)?;
+                let datum_span = datum.span();
+                return Ok(Node::List {
+                    elements: VecDeque::from([
+                        Node::Leaf {
+                            token: keyword,
+                            span: *span,
+                        },
+                        datum,
+                    ]),
+                    span: Span::new(span.start, datum_span.end),
+                });
             }
             _ => {
                 tokens.next();
                 return Ok(Node::Leaf {
                     token: token.clone(),
+                    span: *span,
                 });
             }
         }
@@ -61,30 +282,38 @@ fn parse_list<'a>(
     panic!("Internal error: cannot parse a list - empty input.");
 }
 
+// Parses the elements of a list after its left parenthesis (spanning
+// `left_paren`). Returns the elements and the span of the right parenthesis.
 fn parse_list_elements<'a>(
-    tokens: &mut Peekable<impl Iterator<Item = &'a Token>>,
-) -> Result<VecDeque<Node>, LisrParseError> {
+    tokens: &mut Peekable<impl Iterator<Item = &'a Spanned<Token>>>,
+    left_paren: Span,
+) -> Result<(VecDeque<Node>, Span), Spanned<LisrParseError>> {
     let mut elements: VecDeque<Node> = VecDeque::new();
 
-    while let Some(&token) = tokens.peek() {
+    while let Some(&Spanned { value: token, span }) = tokens.peek() {
         match token {
-            Token::LeftParen => {
+            Token::LeftParen
+            | Token::Apostrophe
+            | Token::Backquote
+            | Token::Comma
+            | Token::CommaAt => {
                 elements.push_back(parse_list(tokens)?);
             }
             Token::RightParen => {
                 tokens.next();
-                return Ok(elements);
+                return Ok((elements, *span));
             }
             _ => {
                 elements.push_back(Node::Leaf {
                     token: token.clone(),
+                    span: *span,
                 });
                 tokens.next();
             }
         }
     }
 
-    Err(LisrParseError::UnclosedList)
+    Err(Spanned::new(LisrParseError::UnclosedList, left_paren))
 }
 
 #[cfg(test)]
@@ -92,17 +321,30 @@ mod tests {
     use std::vec;
 
     use super::*;
+    use crate::{number::Number, scan::scan, span::Position};
+
+    fn with_default_spans(tokens: Vec<Token>) -> Vec<Spanned<Token>> {
+        tokens
+            .into_iter()
+            .map(|token| Spanned::new(token, Span::default()))
+            .collect()
+    }
 
     #[test]
     fn should_parse_a_leaf() {
-        let tokens = vec![Token::Number { value: 42.0 }];
+        let tokens = vec![Token::Number {
+            value: Number::Integer(42),
+        }];
 
-        let nodes = parse(tokens).unwrap();
+        let nodes = parse(with_default_spans(tokens)).unwrap();
 
         assert_eq!(
-            nodes.get(0),
+            nodes.first(),
             Some(&Node::Leaf {
-                token: Token::Number { value: 42.0 }
+                token: Token::Number {
+                    value: Number::Integer(42)
+                },
+                span: Span::default()
             })
         );
     }
@@ -114,29 +356,41 @@ mod tests {
             Token::Identifier {
                 name: String::from("+"),
             },
-            Token::Number { value: 1.0 },
-            Token::Number { value: 2.0 },
+            Token::Number {
+                value: Number::Integer(1),
+            },
+            Token::Number {
+                value: Number::Integer(2),
+            },
             Token::RightParen,
         ];
 
-        let nodes = parse(tokens).unwrap();
+        let nodes = parse(with_default_spans(tokens)).unwrap();
 
         assert_eq!(
-            nodes.get(0),
+            nodes.first(),
             Some(&Node::List {
                 elements: VecDeque::from([
                     Node::Leaf {
                         token: Token::Identifier {
                             name: String::from("+")
-                        }
+                        },
+                        span: Span::default()
                     },
                     Node::Leaf {
-                        token: Token::Number { value: 1.0 }
+                        token: Token::Number {
+                            value: Number::Integer(1)
+                        },
+                        span: Span::default()
                     },
                     Node::Leaf {
-                        token: Token::Number { value: 2.0 }
+                        token: Token::Number {
+                            value: Number::Integer(2)
+                        },
+                        span: Span::default()
                     }
-                ])
+                ]),
+                span: Span::default()
             })
         );
     }
@@ -145,12 +399,13 @@ mod tests {
     fn should_parse_an_empty_list() {
         let tokens = vec![Token::LeftParen, Token::RightParen];
 
-        let nodes = parse(tokens).unwrap();
+        let nodes = parse(with_default_spans(tokens)).unwrap();
 
         assert_eq!(
-            nodes.get(0),
+            nodes.first(),
             Some(&Node::List {
-                elements: VecDeque::new()
+                elements: VecDeque::new(),
+                span: Span::default()
             })
         );
     }
@@ -159,7 +414,7 @@ mod tests {
     fn should_return_error_on_unexpected_right_parentheses() {
         let tokens = vec![Token::RightParen];
 
-        let error = parse(tokens).unwrap_err();
+        let error = parse(with_default_spans(tokens)).unwrap_err().value;
 
         assert_eq!(error, LisrParseError::UnexpectedRightParentheses);
     }
@@ -171,12 +426,165 @@ mod tests {
             Token::Identifier {
                 name: String::from("+"),
             },
-            Token::Number { value: 2.0 },
-            Token::Number { value: 2.0 },
+            Token::Number {
+                value: Number::Integer(2),
+            },
+            Token::Number {
+                value: Number::Integer(2),
+            },
         ];
 
-        let error = parse(tokens).unwrap_err();
+        let error = parse(with_default_spans(tokens)).unwrap_err().value;
 
         assert_eq!(error, LisrParseError::UnclosedList);
     }
+
+    #[test]
+    fn should_parse_an_apostrophe_as_a_quotation() {
+        let tokens = vec![
+            Token::Apostrophe,
+            Token::LeftParen,
+            Token::Number {
+                value: Number::Integer(1),
+            },
+            Token::RightParen,
+        ];
+
+        let nodes = parse(with_default_spans(tokens)).unwrap();
+
+        assert_eq!(
+            nodes,
+            vec![Node::List {
+                elements: VecDeque::from([
+                    Node::Leaf {
+                        token: Token::Quote,
+                        span: Span::default()
+                    },
+                    Node::List {
+                        elements: VecDeque::from([Node::Leaf {
+                            token: Token::Number {
+                                value: Number::Integer(1)
+                            },
+                            span: Span::default()
+                        }]),
+                        span: Span::default()
+                    }
+                ]),
+                span: Span::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn should_parse_nested_quotations_inside_lists() {
+        let tokens = scan("(f ''x)").unwrap();
+
+        let nodes = parse(tokens).unwrap();
+
+        let Some(Node::List { elements, .. }) = nodes.first() else {
+            panic!("Expected a list");
+        };
+        assert_eq!(elements.len(), 2);
+        assert_eq!(
+            elements[1].span(),
+            Span::new(Position::new(1, 4), Position::new(1, 7))
+        );
+        let Node::List { elements, .. } = &elements[1] else {
+            panic!("Expected a quotation");
+        };
+        assert!(matches!(
+            &elements[1],
+            Node::List { elements, .. } if elements.len() == 2
+        ));
+    }
+
+    #[test]
+    fn should_parse_quasiquotation_prefixes() {
+        let tokens = vec![
+            Token::Backquote,
+            Token::LeftParen,
+            Token::Comma,
+            Token::Identifier {
+                name: String::from("x"),
+            },
+            Token::CommaAt,
+            Token::Identifier {
+                name: String::from("rest"),
+            },
+            Token::RightParen,
+        ];
+
+        let nodes = parse(with_default_spans(tokens)).unwrap();
+
+        let prefixed = |keyword, name: &str| Node::List {
+            elements: VecDeque::from([
+                Node::Leaf {
+                    token: keyword,
+                    span: Span::default(),
+                },
+                Node::Leaf {
+                    token: Token::Identifier {
+                        name: String::from(name),
+                    },
+                    span: Span::default(),
+                },
+            ]),
+            span: Span::default(),
+        };
+        assert_eq!(
+            nodes,
+            vec![Node::List {
+                elements: VecDeque::from([
+                    Node::Leaf {
+                        token: Token::Quasiquote,
+                        span: Span::default()
+                    },
+                    Node::List {
+                        elements: VecDeque::from([
+                            prefixed(Token::Unquote, "x"),
+                            prefixed(Token::UnquoteSplicing, "rest"),
+                        ]),
+                        span: Span::default()
+                    }
+                ]),
+                span: Span::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn should_not_allow_an_apostrophe_without_a_datum() {
+        let tokens = vec![Token::Apostrophe];
+
+        let error = parse(with_default_spans(tokens)).unwrap_err().value;
+
+        assert_eq!(error, LisrParseError::QuoteRequiresOneDatum);
+    }
+
+    #[test]
+    fn should_span_lists_from_left_to_right_parenthesis() {
+        let tokens = scan("(+ 1\n  (* 2 3))").unwrap();
+
+        let nodes = parse(tokens).unwrap();
+
+        assert_eq!(
+            nodes.first().map(Node::span),
+            Some(Span::new(Position::new(1, 1), Position::new(2, 11)))
+        );
+    }
+
+    #[test]
+    fn should_report_where_an_unclosed_list_starts() {
+        let tokens = scan("(define x 1)\n(+ x\n  (* 2 3)").unwrap();
+
+        let error = parse(tokens).unwrap_err();
+
+        assert_eq!(
+            error,
+            Spanned::new(
+                LisrParseError::UnclosedList,
+                Span::new(Position::new(2, 1), Position::new(2, 2))
+            )
+        );
+    }
 }
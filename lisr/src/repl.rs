@@ -0,0 +1,113 @@
+use std::io::{self, BufRead, Write};
+
+use lisr::{
+    diagnostic::Diagnostic, interpret::Interpreter, lisr_error::LisrError, parse::LisrParseError,
+    scan::LisrScanError, span::Spanned,
+};
+
+const PROMPT: &str = "lisr> ";
+const CONTINUATION_PROMPT: &str = "....> ";
+// Used in place of a file name when reporting errors.
+const SOURCE_NAME: &str = "<repl>";
+
+// Reads expressions line by line and prints their values. Lines are collected
+// until all the parentheses (and strings) are closed, so an expression can span
+// multiple lines. Definitions are kept for the whole session.
+pub fn run(input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let mut interpreter = Interpreter::new();
+    let mut buffer = String::new();
+    let mut lines = input.lines();
+
+    loop {
+        let prompt = if buffer.is_empty() {
+            PROMPT
+        } else {
+            CONTINUATION_PROMPT
+        };
+        write!(output, "{}", prompt)?;
+        output.flush()?;
+
+        let Some(line) = lines.next() else {
+            // End of input.
+            writeln!(output)?;
+            return Ok(());
+        };
+        buffer.push_str(&line?);
+        buffer.push('\n');
+
+        if buffer.trim().is_empty() {
+            buffer.clear();
+            continue;
+        }
+
+        match interpreter.interpret(&buffer) {
+            Ok(result) => writeln!(output, "{}", result)?,
+            Err(error) if is_incomplete(&error) => continue,
+            Err(error) => write!(output, "{}", Diagnostic::new(&error, SOURCE_NAME, &buffer))?,
+        }
+        buffer.clear();
+    }
+}
+
+fn is_incomplete(error: &LisrError) -> bool {
+    matches!(
+        error,
+        LisrError::Parse(Spanned {
+            value: LisrParseError::UnclosedList,
+            ..
+        }) | LisrError::Scan(Spanned {
+            value: LisrScanError::UnclosedString
+                | LisrScanError::UnclosedBlockComment
+                | LisrScanError::UnclosedDatumComment,
+            ..
+        })
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_session(input: &str) -> String {
+        let mut output = Vec::new();
+        run(input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn should_keep_definitions_between_lines() {
+        let output = run_session("(define x 2)\n(* x 21)\n");
+
+        assert_eq!(output, "lisr> x\nlisr> 42\nlisr> \n");
+    }
+
+    #[test]
+    fn should_wait_for_unclosed_lists() {
+        let output = run_session("(+ 1\n   2\n)\n");
+
+        assert_eq!(output, "lisr> ....> ....> 3\nlisr> \n");
+    }
+
+    #[test]
+    fn should_report_errors_and_continue() {
+        let output = run_session(")\nundefined\n(cons 1 ())\n");
+
+        assert_eq!(
+            output,
+            "lisr> parse error: unexpected `)`\n \
+             --> <repl>:1:1\n  \
+             |\n\
+             1 | )\n  \
+             | ^\n  \
+             = help: remove it or add a matching `(` before it\n\
+             lisr> evaluation error: undefined identifier\n \
+             --> <repl>:1:1\n  \
+             |\n\
+             1 | undefined\n  \
+             | ^^^^^^^^^\n  \
+             = help: define it before it is used, like (define x 42)\n\
+             lisr> (1)\n\
+             lisr> \n"
+        );
+    }
+}
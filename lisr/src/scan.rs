@@ -1,10 +1,18 @@
-use std::{iter::Peekable, str::Chars};
+use std::{fmt, iter::Peekable, str::Chars};
 
-use crate::token::Token;
+use crate::{
+    big_integer::BigInteger,
+    number::Number,
+    span::{Position, Span, Spanned},
+    token::Token,
+};
 
 const LEFT_PAREN: char = '(';
 const RIGHT_PAREN: char = ')';
 const SINGLE_QUOTE: char = '\'';
+const BACKQUOTE: char = '`';
+const COMMA: char = ',';
+const AT: char = '@';
 const DOUBLE_QUOTE: char = '"';
 const PLUS: char = '+';
 const ASTERISK: char = '*';
@@ -14,99 +22,360 @@ const DOT: char = '.';
 const RADIX: u32 = 10;
 const LESS_THAN: char = '<';
 const GREATER_THAN: char = '>';
+const EQUALS: char = '=';
+const NEWLINE: char = '\n';
+const CARRIAGE_RETURN: char = '\r';
+const SEMICOLON: char = ';';
+const HASH: char = '#';
+const VERTICAL_BAR: char = '|';
+const BACKSLASH: char = '\\';
+const LEFT_BRACE: char = '{';
+const RIGHT_BRACE: char = '}';
+
+// Characters that are written by name, like `#\space`.
+pub const CHARACTER_NAMES: [(&str, char); 9] = [
+    ("alarm", '\u{7}'),
+    ("backspace", '\u{8}'),
+    ("delete", '\u{7f}'),
+    ("escape", '\u{1b}'),
+    ("newline", '\n'),
+    ("null", '\0'),
+    ("return", '\r'),
+    ("space", ' '),
+    ("tab", '\t'),
+];
 
 #[derive(Debug, PartialEq)]
 pub enum LisrScanError<'a> {
     EmptyQuotation, // Empty quotations are not allowed.
     UnclosedString,
+    UnknownEscape { escape: char }, // Like `\q` in a string.
+    InvalidEscape { reason: &'a str },
     InvalidNumber { reason: &'a str },
+    InvalidCharacter { reason: &'a str },
     InvalidIdentifier { reason: &'a str },
+    UnclosedBlockComment,
+    EmptyDatumComment, // `#;` has to be followed by a datum.
+    UnclosedDatumComment,
 }
 
-pub fn scan(input: &str) -> Result<Vec<Token>, LisrScanError> {
-    let mut tokens: Vec<Token> = Vec::new();
-    let mut input = input.chars().peekable();
+impl LisrScanError<'_> {
+    // A hint on how to fix the error.
+    pub fn help(&self) -> Option<String> {
+        match self {
+            LisrScanError::EmptyQuotation => Some(String::from(
+                "a quote must be followed by the quoted text, like 'abc or `(a ,b)",
+            )),
+            LisrScanError::UnclosedString => Some(String::from("add a closing `\"`")),
+            LisrScanError::UnknownEscape { .. } => Some(String::from(
+                r#"the escapes are \", \\, \n, \t, \r, \xHH; and \u{HHHH}; write \\ for a backslash"#,
+            )),
+            LisrScanError::InvalidEscape { reason }
+            | LisrScanError::InvalidNumber { reason }
+            | LisrScanError::InvalidCharacter { reason }
+            | LisrScanError::InvalidIdentifier { reason } => Some(reason.to_string()),
+            LisrScanError::UnclosedBlockComment => Some(String::from(
+                "add a closing `|#` (block comments can be nested)",
+            )),
+            LisrScanError::EmptyDatumComment => Some(String::from(
+                "`#;` comments out the datum that follows it, like #;(+ 1 2)",
+            )),
+            LisrScanError::UnclosedDatumComment => Some(String::from(
+                "the list commented out by `#;` is missing a closing `)`",
+            )),
+        }
+    }
+}
+
+impl fmt::Display for LisrScanError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LisrScanError::EmptyQuotation => write!(f, "empty quotation"),
+            LisrScanError::UnclosedString => write!(f, "unclosed string"),
+            LisrScanError::UnknownEscape { escape } => write!(f, "unknown escape `\\{}`", escape),
+            LisrScanError::InvalidEscape { .. } => write!(f, "invalid escape"),
+            LisrScanError::InvalidNumber { .. } => write!(f, "invalid number"),
+            LisrScanError::InvalidCharacter { .. } => write!(f, "invalid character"),
+            LisrScanError::InvalidIdentifier { .. } => write!(f, "invalid identifier"),
+            LisrScanError::UnclosedBlockComment => write!(f, "unclosed block comment"),
+            LisrScanError::EmptyDatumComment => write!(f, "nothing to comment out"),
+            LisrScanError::UnclosedDatumComment => write!(f, "unclosed datum comment"),
+        }
+    }
+}
+
+// Characters of the input that keep track of the position of the next character.
+struct Source<'a> {
+    chars: Peekable<Chars<'a>>,
+    position: Position,
+}
+
+impl<'a> Source<'a> {
+    fn new(input: &'a str) -> Source<'a> {
+        Source {
+            chars: input.chars().peekable(),
+            position: Position::new(1, 1),
+        }
+    }
+
+    fn peek(&mut self) -> Option<&char> {
+        self.chars.peek()
+    }
+
+    // Peeks at the character after the next one.
+    fn peek_second(&self) -> Option<char> {
+        let mut chars = self.chars.clone();
+        chars.next();
+        chars.next()
+    }
+
+    // Not named `position` so that it is not shadowed by `Iterator::position`.
+    fn current_position(&self) -> Position {
+        self.position
+    }
+}
+
+impl Iterator for Source<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let char = self.chars.next()?;
+        if char == NEWLINE {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else {
+            self.position.column += 1;
+        }
+        Some(char)
+    }
+}
+
+pub fn scan(input: &str) -> Result<Vec<Spanned<Token>>, Spanned<LisrScanError<'_>>> {
+    let mut tokens: Vec<Spanned<Token>> = Vec::new();
+    let mut input = Source::new(input);
+
+    skip_whitespaces_and_comments(&mut input)?;
+    while input.peek().is_some() {
+        tokens.push(scan_spanned_token(&mut input)?);
+        skip_whitespaces_and_comments(&mut input)?;
+    }
+
+    Ok(tokens)
+}
 
+fn scan_spanned_token(
+    input: &mut Source,
+) -> Result<Spanned<Token>, Spanned<LisrScanError<'static>>> {
+    let start = input.current_position();
+    // Strings report invalid escapes with the span of the escape rather than
+    // of the whole string.
+    if input.peek() == Some(&DOUBLE_QUOTE) {
+        let token = scan_string(input)?;
+        return Ok(Spanned::new(
+            token,
+            Span::new(start, input.current_position()),
+        ));
+    }
+    match scan_token(input) {
+        Ok(token) => Ok(Spanned::new(
+            token,
+            Span::new(start, input.current_position()),
+        )),
+        Err(error) => Err(Spanned::new(
+            error,
+            Span::new(start, input.current_position()),
+        )),
+    }
+}
+
+// Comments are:
+// - `;` line comments, which last until the end of the line,
+// - `#| ... |#` block comments, which can be nested,
+// - `#;` datum comments, which comment out the datum that follows them (an atom
+//   or a whole list).
+fn skip_whitespaces_and_comments(
+    input: &mut Source,
+) -> Result<(), Spanned<LisrScanError<'static>>> {
     while let Some(&char) = input.peek() {
-        match char {
-            LEFT_PAREN => {
-                tokens.push(Token::LeftParen);
+        match (char, input.peek_second()) {
+            (char, _) if char.is_whitespace() => {
                 input.next();
             }
-            RIGHT_PAREN => {
-                tokens.push(Token::RightParen);
-                input.next();
-            }
-            DASH => {
-                input.next();
-
-                match input.peek() {
-                    Some(&next_char) => {
-                        if should_finish_scanning(next_char) {
-                            tokens.push(Token::Identifier {
-                                name: DASH.to_string(),
-                            });
-                            input.next();
-                        } else {
-                            tokens.push(scan_number(&mut input, true)?);
-                        }
-                    }
-                    None => {
-                        tokens.push(Token::Identifier {
-                            name: DASH.to_string(),
-                        });
-                    }
+            (SEMICOLON, _) => {
+                while input.peek().is_some_and(|&char| char != NEWLINE) {
+                    input.next();
                 }
             }
-            // Numbers with a preceeding plus sign are not allowed (for example: `+42`).
-            PLUS | ASTERISK | SLASH | LESS_THAN | GREATER_THAN => {
+            (HASH, Some(VERTICAL_BAR)) => skip_block_comment(input)?,
+            (HASH, Some(SEMICOLON)) => skip_datum_comment(input)?,
+            _ => break,
+        }
+    }
+    Ok(())
+}
+
+fn skip_block_comment(input: &mut Source) -> Result<(), Spanned<LisrScanError<'static>>> {
+    let start = input.current_position();
+    // Skip the opening `#|`.
+    input.next();
+    input.next();
+
+    let mut depth = 1;
+    while depth > 0 {
+        match (input.next(), input.peek()) {
+            (Some(HASH), Some(&VERTICAL_BAR)) => {
                 input.next();
-                match input.peek() {
-                    Some(&next_char) => {
-                        if should_finish_scanning(next_char) {
-                            input.next();
-                            tokens.push(Token::Identifier {
-                                name: char.to_string(),
-                            });
-                        } else {
-                            return Err(LisrScanError::InvalidIdentifier {
-                                reason: "Identifier cannot start with an operator.",
-                            });
-                        }
-                    }
-                    None => {
-                        tokens.push(Token::Identifier {
-                            name: char.to_string(),
-                        });
-                    }
-                }
+                depth += 1;
             }
-            char if char.is_whitespace() => {
-                // Skip whitespaces.
+            (Some(VERTICAL_BAR), Some(&HASH)) => {
                 input.next();
+                depth -= 1;
             }
-            _ => {
-                let literal = scan_literal(&mut input)?;
-                tokens.push(literal);
+            (Some(_), _) => {}
+            (None, _) => {
+                return Err(Spanned::new(
+                    LisrScanError::UnclosedBlockComment,
+                    Span::new(start, input.current_position()),
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+fn skip_datum_comment(input: &mut Source) -> Result<(), Spanned<LisrScanError<'static>>> {
+    let start = input.current_position();
+    // Skip the `#;`.
+    input.next();
+    input.next();
+
+    skip_whitespaces_and_comments(input)?;
+    let mut depth = 0;
+    loop {
+        if matches!(input.peek(), None | Some(&RIGHT_PAREN)) && depth == 0 {
+            return Err(Spanned::new(
+                LisrScanError::EmptyDatumComment,
+                Span::new(start, input.current_position()),
+            ));
+        }
+        if input.peek().is_none() {
+            return Err(Spanned::new(
+                LisrScanError::UnclosedDatumComment,
+                Span::new(start, input.current_position()),
+            ));
+        }
+
+        let is_prefix = match scan_spanned_token(input)?.value {
+            Token::LeftParen => {
+                depth += 1;
+                false
             }
+            Token::RightParen => {
+                depth -= 1;
+                false
+            }
+            // A prefix like the quote of '(a b) is a part of the datum that
+            // follows it, so the datum is skipped too.
+            Token::Apostrophe | Token::Backquote | Token::Comma | Token::CommaAt => true,
+            _ => false,
+        };
+        if depth == 0 && !is_prefix {
+            return Ok(());
         }
+        skip_whitespaces_and_comments(input)?;
     }
+}
 
-    Ok(tokens)
+fn scan_token(input: &mut Source) -> Result<Token, LisrScanError<'static>> {
+    let Some(&char) = input.peek() else {
+        // The caller of that function should make sure there is something to scan.
+        panic!("Failed while scanning a token - nothing to scan.");
+    };
+
+    match char {
+        LEFT_PAREN => {
+            input.next();
+            Ok(Token::LeftParen)
+        }
+        RIGHT_PAREN => {
+            input.next();
+            Ok(Token::RightParen)
+        }
+        DASH if input
+            .peek_second()
+            .is_some_and(|next_char| !should_finish_scanning(next_char)) =>
+        {
+            scan_number(input)
+        }
+        // A plus sign starts a number like `+42`, `+.5` or `+inf.0`.
+        PLUS if input.peek_second().is_some_and(|next_char| {
+            next_char.is_digit(RADIX) || matches!(next_char, DOT | 'i' | 'n')
+        }) =>
+        {
+            scan_number(input)
+        }
+        // `<=` and `>=` are the only operators with two characters.
+        LESS_THAN | GREATER_THAN if input.peek_second() == Some(EQUALS) => {
+            input.next();
+            input.next();
+            scan_operator(input, format!("{char}{EQUALS}"))
+        }
+        DASH | PLUS | ASTERISK | SLASH | LESS_THAN | GREATER_THAN => {
+            input.next();
+            scan_operator(input, char.to_string())
+        }
+        _ => scan_literal(input),
+    }
 }
 
-fn scan_literal(input: &mut Peekable<Chars>) -> Result<Token, LisrScanError<'static>> {
+// The operator has already been consumed - it must not be followed by anything.
+fn scan_operator(input: &mut Source, name: String) -> Result<Token, LisrScanError<'static>> {
+    match input.peek() {
+        Some(&next_char) if !should_finish_scanning(next_char) => {
+            Err(LisrScanError::InvalidIdentifier {
+                reason: "Identifier cannot start with an operator.",
+            })
+        }
+        _ => Ok(Token::Identifier { name }),
+    }
+}
+
+fn scan_literal(input: &mut Source) -> Result<Token, LisrScanError<'static>> {
     if let Some(&char) = input.peek() {
         match char {
             SINGLE_QUOTE => {
                 input.next();
-                scan_quotation(input)
+                scan_quotation(input, Token::Apostrophe)
             }
-            DOUBLE_QUOTE => {
+            BACKQUOTE => {
+                input.next();
+                scan_quotation(input, Token::Backquote)
+            }
+            COMMA => {
                 input.next();
-                scan_string(input)
+                if input.peek() == Some(&AT) {
+                    input.next();
+                    scan_quotation(input, Token::CommaAt)
+                } else {
+                    scan_quotation(input, Token::Comma)
+                }
+            }
+            // A lone dot separates the last element of a dotted list, like in `(a . b)`.
+            DOT if input.peek_second().is_none_or(should_finish_scanning) => {
+                input.next();
+                Ok(Token::Dot)
+            }
+            // The ellipsis of syntax rules, `...`.
+            DOT if input.peek_second() == Some(DOT) => scan_identifier(input),
+            char if char.is_digit(RADIX) || char == DOT => scan_number(input),
+            HASH if input.peek_second() == Some(BACKSLASH) => scan_character(input),
+            // Radix and exactness prefixes, like `#x1F` or `#e1.5`.
+            HASH if input
+                .peek_second()
+                .is_some_and(|next_char| "xXbBoOdDeEiI".contains(next_char)) =>
+            {
+                scan_number(input)
             }
-            char if char.is_digit(RADIX) || char == DOT => scan_number(input, false),
             _ => scan_identifier(input),
         }
     } else {
@@ -117,27 +386,24 @@ fn scan_literal(input: &mut Peekable<Chars>) -> Result<Token, LisrScanError<'sta
     }
 }
 
-fn scan_quotation(input: &mut Peekable<Chars>) -> Result<Token, LisrScanError<'static>> {
-    let mut lexeme = String::new();
-
-    while let Some(&char) = input.peek() {
-        if should_finish_scanning(char) {
-            break;
-        }
-        lexeme.push(char);
-        input.next();
-    }
-
-    if lexeme.is_empty() {
-        Err(LisrScanError::EmptyQuotation)
-    } else {
-        Ok(Token::Quotation {
-            text_of_quotation: lexeme,
-        })
+// The quoted datum is scanned separately - the parser puts it together with
+// the quote (`quote` is one of `'`, `` ` ``, `,` and `,@`).
+fn scan_quotation(input: &mut Source, quote: Token) -> Result<Token, LisrScanError<'static>> {
+    match input.peek() {
+        Some(&LEFT_PAREN) => Ok(quote),
+        Some(&char) if !should_finish_scanning(char) => Ok(quote),
+        _ => Err(LisrScanError::EmptyQuotation),
     }
 }
 
-fn scan_string(input: &mut Peekable<Chars>) -> Result<Token, LisrScanError<'static>> {
+// The escapes are `\"`, `\\`, `\n`, `\t`, `\r`, `\xHH;` and `\u{HHHH}` (with
+// any number of hexadecimal digits). A backslash at the end of a line continues
+// the string on the next line, skipping the indentation.
+fn scan_string(input: &mut Source) -> Result<Token, Spanned<LisrScanError<'static>>> {
+    let start = input.current_position();
+    // Skip the opening `"`.
+    input.next();
+
     let mut lexeme = String::new();
     while let Some(&char) = input.peek() {
         match char {
@@ -145,61 +411,338 @@ fn scan_string(input: &mut Peekable<Chars>) -> Result<Token, LisrScanError<'stat
                 input.next();
                 return Ok(Token::String { value: lexeme });
             }
+            BACKSLASH => {
+                let escape_start = input.current_position();
+                input.next();
+                match scan_escape(input) {
+                    Ok(Some(char)) => lexeme.push(char),
+                    Ok(None) => {}
+                    Err(LisrScanError::UnclosedString) => break,
+                    Err(error) => {
+                        return Err(Spanned::new(
+                            error,
+                            Span::new(escape_start, input.current_position()),
+                        ))
+                    }
+                }
+            }
             _ => {
                 lexeme.push(char);
                 input.next();
             }
         }
     }
-    Err(LisrScanError::UnclosedString)
+    Err(Spanned::new(
+        LisrScanError::UnclosedString,
+        Span::new(start, input.current_position()),
+    ))
 }
 
-fn scan_number(
-    input: &mut Peekable<Chars>,
-    negative: bool,
-) -> Result<Token, LisrScanError<'static>> {
-    let mut lexeme = String::new();
-    let mut is_decimal = false;
-
-    while let Some(&char) = input.peek() {
-        match char {
-            DOT => {
-                if is_decimal {
-                    return Err(LisrScanError::InvalidNumber {
-                        reason: "A number cannot have more than one decimal point.",
-                    });
+// Scans what follows a backslash. Gives None for a line continuation, which
+// stands for no character at all.
+fn scan_escape(input: &mut Source) -> Result<Option<char>, LisrScanError<'static>> {
+    let Some(char) = input.next() else {
+        return Err(LisrScanError::UnclosedString);
+    };
+    match char {
+        DOUBLE_QUOTE | BACKSLASH => Ok(Some(char)),
+        'n' => Ok(Some(NEWLINE)),
+        't' => Ok(Some('\t')),
+        'r' => Ok(Some('\r')),
+        'x' => scan_code_point(input, SEMICOLON).map(Some),
+        'u' => {
+            if input.next() != Some(LEFT_BRACE) {
+                return Err(LisrScanError::InvalidEscape {
+                    reason: "A Unicode escape must be written like \\u{1F600}.",
+                });
+            }
+            scan_code_point(input, RIGHT_BRACE).map(Some)
+        }
+        char if is_intraline_whitespace(char) || char == NEWLINE || char == CARRIAGE_RETURN => {
+            let mut char = char;
+            while is_intraline_whitespace(char) {
+                match input.next() {
+                    Some(next_char) => char = next_char,
+                    None => return Err(LisrScanError::UnclosedString),
                 }
-                is_decimal = true;
-                lexeme.push(char);
+            }
+            // Lines can also end with a carriage return and a newline.
+            if char == CARRIAGE_RETURN && input.peek() == Some(&NEWLINE) {
                 input.next();
+                char = NEWLINE;
             }
-            char if should_finish_scanning(char) => {
+            if char != NEWLINE {
+                return Err(LisrScanError::InvalidEscape {
+                    reason: "Only whitespace can follow a backslash that continues the line.",
+                });
+            }
+            while input
+                .peek()
+                .is_some_and(|&char| is_intraline_whitespace(char))
+            {
+                input.next();
+            }
+            Ok(None)
+        }
+        escape => Err(LisrScanError::UnknownEscape { escape }),
+    }
+}
+
+// Scans hexadecimal digits up to the terminator, like the `41;` of `\x41;`.
+fn scan_code_point(input: &mut Source, terminator: char) -> Result<char, LisrScanError<'static>> {
+    let mut digits = String::new();
+    loop {
+        match input.peek() {
+            Some(&char) if char == terminator => {
+                input.next();
                 break;
             }
-            char if !char.is_digit(RADIX) => {
+            Some(&char) if char.is_ascii_hexdigit() => {
+                digits.push(char);
+                input.next();
+            }
+            _ => {
+                return Err(LisrScanError::InvalidEscape {
+                    reason: "A code point must be hexadecimal digits followed by `;` in \\x41; or `}` in \\u{41}.",
+                })
+            }
+        }
+    }
+    u32::from_str_radix(&digits, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or(LisrScanError::InvalidEscape {
+            reason: "The escape is not a valid Unicode code point.",
+        })
+}
+
+fn is_intraline_whitespace(char: char) -> bool {
+    char == ' ' || char == '\t'
+}
+
+// Characters are written like `#\a`, by name like `#\space`, or by their code
+// point like `#\x41`. Any character can follow the `#\`, even `(` or a space.
+fn scan_character(input: &mut Source) -> Result<Token, LisrScanError<'static>> {
+    // Skip the `#\`.
+    input.next();
+    input.next();
+
+    let Some(first) = input.next() else {
+        return Err(LisrScanError::InvalidCharacter {
+            reason: "`#\\` must be followed by a character.",
+        });
+    };
+    let mut lexeme = String::from(first);
+    while let Some(&char) = input.peek() {
+        if should_finish_scanning(char) {
+            break;
+        }
+        lexeme.push(char);
+        input.next();
+    }
+
+    if lexeme.chars().count() == 1 {
+        return Ok(Token::Character { value: first });
+    }
+    if let Some((_, value)) = CHARACTER_NAMES.iter().find(|(name, _)| *name == lexeme) {
+        return Ok(Token::Character { value: *value });
+    }
+    match lexeme.strip_prefix('x') {
+        Some(digits) if digits.chars().all(|char| char.is_ascii_hexdigit()) => {
+            match u32::from_str_radix(digits, 16).ok().and_then(char::from_u32) {
+                Some(value) => Ok(Token::Character { value }),
+                None => Err(LisrScanError::InvalidCharacter {
+                    reason: "The character is not a valid Unicode code point.",
+                }),
+            }
+        }
+        _ => Err(LisrScanError::InvalidCharacter {
+            reason: "Unknown character name. Use one character, a name like space or newline, or a code point like x41.",
+        }),
+    }
+}
+
+// A number is scanned as a whole lexeme and then parsed, because its prefixes,
+// sign and exponent can only be read together. The syntax is:
+// prefix* sign? (integer | integer "/" integer | decimal | "inf.0" | "nan.0")
+// where a prefix is a radix (`#x`, `#b`, `#o` or `#d`) or an exactness (`#e` or
+// `#i`), and `inf.0` and `nan.0` need the sign.
+fn scan_number(input: &mut Source) -> Result<Token, LisrScanError<'static>> {
+    let mut lexeme = String::new();
+    while let Some(&char) = input.peek() {
+        if should_finish_scanning(char) {
+            break;
+        }
+        lexeme.push(char);
+        input.next();
+    }
+    Ok(Token::Number {
+        value: parse_number(&lexeme)?,
+    })
+}
+
+pub fn parse_number(lexeme: &str) -> Result<Number, LisrScanError<'static>> {
+    let mut radix = None;
+    let mut exactness = None;
+    let mut rest = lexeme;
+    while let Some(prefixed) = rest.strip_prefix(HASH) {
+        let mut chars = prefixed.chars();
+        let prefix = chars.next().map(|char| char.to_ascii_lowercase());
+        match prefix {
+            Some('x') if radix.is_none() => radix = Some(16),
+            Some('b') if radix.is_none() => radix = Some(2),
+            Some('o') if radix.is_none() => radix = Some(8),
+            Some('d') if radix.is_none() => radix = Some(RADIX),
+            Some('e') if exactness.is_none() => exactness = Some(true),
+            Some('i') if exactness.is_none() => exactness = Some(false),
+            Some('x' | 'b' | 'o' | 'd' | 'e' | 'i') => {
                 return Err(LisrScanError::InvalidNumber {
-                    reason: "Numbers can contain digits only.",
+                    reason: "A number can have at most one radix prefix and one exactness prefix.",
                 });
             }
             _ => {
-                lexeme.push(char);
-                input.next();
+                return Err(LisrScanError::InvalidNumber {
+                    reason: "Unknown number prefix. Use one of #x, #b, #o, #d, #e and #i.",
+                });
             }
         }
+        rest = chars.as_str();
     }
+    let radix = radix.unwrap_or(RADIX);
+    let is_exact = exactness == Some(true);
 
-    match lexeme.parse::<f64>() {
-        Ok(number) => {
-            let number = if negative { -1.0 * number } else { number };
-            Ok(Token::Number { value: number })
+    let (negative, unsigned) = if let Some(unsigned) = rest.strip_prefix(DASH) {
+        (true, unsigned)
+    } else if let Some(unsigned) = rest.strip_prefix(PLUS) {
+        (false, unsigned)
+    } else {
+        (false, rest)
+    };
+    let has_sign = unsigned.len() < rest.len();
+
+    let number = match unsigned {
+        "inf.0" | "nan.0" if has_sign && is_exact => {
+            return Err(LisrScanError::InvalidNumber {
+                reason: "Infinities and NaN cannot be exact.",
+            });
+        }
+        "inf.0" if has_sign && negative => Number::Real(f64::NEG_INFINITY),
+        "inf.0" if has_sign => Number::Real(f64::INFINITY),
+        "nan.0" if has_sign => Number::Real(f64::NAN),
+        _ if unsigned.contains(SLASH) => parse_fraction(unsigned, radix, negative)?,
+        _ if radix == RADIX && unsigned.contains([DOT, 'e', 'E']) => {
+            parse_decimal(unsigned, negative, is_exact)?
+        }
+        _ => match BigInteger::parse(unsigned, radix, negative) {
+            Some(integer) => Number::from_big(integer),
+            None => {
+                return Err(LisrScanError::InvalidNumber {
+                    reason: "Numbers can contain digits only.",
+                });
+            }
         },
-        Err(_) => {
-            Err(LisrScanError::InvalidNumber{ reason: "Could not parse the number. Only 64-bit floats represented in decimal system are allowed." })
+    };
+
+    if exactness == Some(false) {
+        Ok(number.to_inexact())
+    } else {
+        Ok(number)
+    }
+}
+
+// Fractions like `6/4` are exact and normalised, so they are read as `3/2`.
+fn parse_fraction(
+    unsigned: &str,
+    radix: u32,
+    negative: bool,
+) -> Result<Number, LisrScanError<'static>> {
+    let (numerator, denominator) = unsigned.split_once(SLASH).unwrap();
+    if denominator.contains(DOT) && !numerator.contains(DOT) {
+        return Err(LisrScanError::InvalidNumber {
+            reason: "A fraction can contain integers only.",
+        });
+    }
+    let (Some(numerator), Some(denominator)) = (
+        BigInteger::parse(numerator, radix, negative),
+        BigInteger::parse(denominator, radix, false),
+    ) else {
+        return Err(LisrScanError::InvalidNumber {
+            reason: "A fraction must be written as two integers separated by a single slash.",
+        });
+    };
+    if denominator.is_zero() {
+        return Err(LisrScanError::InvalidNumber {
+            reason: "A fraction cannot have a zero denominator.",
+        });
+    }
+    Ok(Number::from_fraction(numerator, denominator))
+}
+
+// Decimals like `1.5e-3` are inexact, unless they have the `#e` prefix - then
+// they are computed exactly, so `#e0.1` is `1/10` rather than the closest float.
+fn parse_decimal(
+    unsigned: &str,
+    negative: bool,
+    is_exact: bool,
+) -> Result<Number, LisrScanError<'static>> {
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (unsigned, None),
+    };
+    let (integer_digits, fraction_digits) = mantissa.split_once(DOT).unwrap_or((mantissa, ""));
+    if fraction_digits.contains(DOT) {
+        return Err(LisrScanError::InvalidNumber {
+            reason: "A number cannot have more than one decimal point.",
+        });
+    }
+    let is_digit = |char: char| char.is_digit(RADIX);
+    let has_digits = !(integer_digits.is_empty() && fraction_digits.is_empty());
+    if !has_digits
+        || !integer_digits
+            .chars()
+            .chain(fraction_digits.chars())
+            .all(is_digit)
+    {
+        return Err(LisrScanError::InvalidNumber {
+            reason: "Numbers can contain digits only.",
+        });
+    }
+    let exponent = match exponent.map(str::parse::<i32>) {
+        None => 0,
+        Some(Ok(exponent)) => exponent,
+        Some(Err(_)) => {
+            return Err(LisrScanError::InvalidNumber {
+                reason: "An exponent must be an integer.",
+            });
         }
+    };
+
+    if !is_exact {
+        let sign = if negative { "-" } else { "" };
+        let value = format!("{sign}0{integer_digits}.{fraction_digits}0e{exponent}");
+        return Ok(Number::Real(value.parse().unwrap()));
+    }
+    let digits = BigInteger::parse(
+        &format!("0{integer_digits}{fraction_digits}"),
+        RADIX,
+        negative,
+    )
+    .unwrap();
+    let scale = exponent as i64 - fraction_digits.len() as i64;
+    let Ok(power) = u32::try_from(scale.unsigned_abs()) else {
+        return Err(LisrScanError::InvalidNumber {
+            reason: "The exponent of an exact number is too big.",
+        });
+    };
+    let power = BigInteger::from(RADIX as i64).pow(power);
+    if scale >= 0 {
+        Ok(Number::from_big(digits.multiply(&power)))
+    } else {
+        Ok(Number::from_fraction(digits, power))
     }
 }
 
-fn scan_identifier(input: &mut Peekable<Chars>) -> Result<Token, LisrScanError<'static>> {
+fn scan_identifier(input: &mut Source) -> Result<Token, LisrScanError<'static>> {
     let mut lexeme = String::new();
     if let Some(&char) = input.peek() {
         if char.is_digit(RADIX) {
@@ -226,7 +769,7 @@ fn scan_identifier(input: &mut Peekable<Chars>) -> Result<Token, LisrScanError<'
     Ok(match_keyword_or_identifier(lexeme))
 }
 
-fn match_keyword_or_identifier(lexeme: String) -> Token {
+pub fn match_keyword_or_identifier(lexeme: String) -> Token {
     match lexeme.as_str() {
         "set!" => Token::Set,
         "define" => Token::Define,
@@ -238,42 +781,122 @@ fn match_keyword_or_identifier(lexeme: String) -> Token {
         "lambda" => Token::Lambda,
         "begin" => Token::Begin,
         "cons" => Token::Cons,
+        "quote" => Token::Quote,
+        "quasiquote" => Token::Quasiquote,
+        "unquote" => Token::Unquote,
+        "unquote-splicing" => Token::UnquoteSplicing,
+        "let" => Token::Let,
+        "let*" => Token::LetStar,
+        "letrec" => Token::Letrec,
+        "letrec*" => Token::LetrecStar,
+        "cond" => Token::Cond,
+        "case" => Token::Case,
+        "else" => Token::Else,
+        "=>" => Token::Arrow,
+        "when" => Token::When,
+        "unless" => Token::Unless,
+        "define-syntax" => Token::DefineSyntax,
+        "let-syntax" => Token::LetSyntax,
+        "letrec-syntax" => Token::LetrecSyntax,
+        "syntax-rules" => Token::SyntaxRules,
+        "define-macro" => Token::DefineMacro,
         _ => Token::Identifier { name: lexeme },
     }
 }
 
 fn should_finish_scanning(char: char) -> bool {
-    char.is_whitespace() || char == LEFT_PAREN || char == RIGHT_PAREN
+    char.is_whitespace() || char == LEFT_PAREN || char == RIGHT_PAREN || char == SEMICOLON
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn scan_tokens(input: &str) -> Vec<Token> {
+        scan(input)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.value)
+            .collect()
+    }
+
     #[test]
     fn should_scan_left_and_right_parentheses() {
         let input = "()";
 
-        let tokens = scan(input).unwrap();
+        let tokens = scan_tokens(input);
 
         assert_eq!(tokens, vec![Token::LeftParen, Token::RightParen]);
     }
 
+    #[test]
+    fn should_track_positions_of_tokens() {
+        let input = "(define x\n  \"abc\")";
+
+        let spans: Vec<Span> = scan(input)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.span)
+            .collect();
+
+        assert_eq!(
+            spans,
+            vec![
+                Span::new(Position::new(1, 1), Position::new(1, 2)),
+                Span::new(Position::new(1, 2), Position::new(1, 8)),
+                Span::new(Position::new(1, 9), Position::new(1, 10)),
+                Span::new(Position::new(2, 3), Position::new(2, 8)),
+                Span::new(Position::new(2, 8), Position::new(2, 9)),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_not_consume_parentheses_after_operators() {
+        let tokens = scan_tokens("(-)(+)");
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::LeftParen,
+                Token::Identifier {
+                    name: String::from("-")
+                },
+                Token::RightParen,
+                Token::LeftParen,
+                Token::Identifier {
+                    name: String::from("+")
+                },
+                Token::RightParen,
+            ]
+        );
+    }
+
     #[test]
     fn should_scan_numbers() {
         let input = "(+ 2.5 3.5)";
 
-        let tokens = scan(input).unwrap();
+        let tokens = scan_tokens(input);
 
-        assert_eq!(tokens.get(2), Some(&Token::Number { value: 2.5 }));
-        assert_eq!(tokens.get(3), Some(&Token::Number { value: 3.5 }));
+        assert_eq!(
+            tokens.get(2),
+            Some(&Token::Number {
+                value: Number::Real(2.5)
+            })
+        );
+        assert_eq!(
+            tokens.get(3),
+            Some(&Token::Number {
+                value: Number::Real(3.5)
+            })
+        );
     }
 
     #[test]
     fn should_scan_unicode_identifiers() {
         let input = "(😲 🍟)";
 
-        let tokens = scan(input).unwrap();
+        let tokens = scan_tokens(input);
 
         assert_eq!(
             tokens.get(1),
@@ -293,7 +916,7 @@ mod tests {
     fn should_scan_dash_as_identifier() {
         let input = "(- 2 1)";
 
-        let tokens = scan(input).unwrap();
+        let tokens = scan_tokens(input);
 
         assert_eq!(
             tokens.get(1),
@@ -307,19 +930,24 @@ mod tests {
     fn should_scan_dash_as_a_part_of_a_negative_number() {
         let input = "(- 5 -2.5)";
 
-        let tokens = scan(input).unwrap();
+        let tokens = scan_tokens(input);
 
-        assert_eq!(tokens.get(3), Some(&Token::Number { value: -2.5 }));
+        assert_eq!(
+            tokens.get(3),
+            Some(&Token::Number {
+                value: Number::Real(-2.5)
+            })
+        );
     }
 
     #[test]
     fn should_scan_dash_when_it_is_the_last_character() {
         let input = "-";
 
-        let tokens = scan(input).unwrap();
+        let tokens = scan_tokens(input);
 
         assert_eq!(
-            tokens.get(0),
+            tokens.first(),
             Some(&Token::Identifier {
                 name: String::from("-")
             })
@@ -328,12 +956,12 @@ mod tests {
 
     #[test]
     fn should_scan_primitive_operators_as_identifiers() {
-        let operators = vec!["+", "*", "/", "<", ">"];
+        let operators = ["+", "*", "/", "<", ">"];
 
         for operator in operators.iter() {
             let input = format!("({} 2 1", operator);
 
-            let tokens = scan(&input).unwrap();
+            let tokens = scan_tokens(&input);
 
             let actual_token_for_operator = tokens.get(1);
             let expected_token = Token::Identifier {
@@ -345,22 +973,122 @@ mod tests {
     }
 
     #[test]
-    fn should_scan_quotation() {
-        let input = "(concat 'one 'two)";
+    fn should_scan_dots_separated_from_other_tokens() {
+        let input = "(a . rest)(.5 .)";
 
-        let tokens = scan(input).unwrap();
+        let tokens = scan_tokens(input);
 
         assert_eq!(
-            tokens.get(2),
-            Some(&Token::Quotation {
-                text_of_quotation: String::from("one")
-            })
+            tokens,
+            vec![
+                Token::LeftParen,
+                Token::Identifier {
+                    name: String::from("a")
+                },
+                Token::Dot,
+                Token::Identifier {
+                    name: String::from("rest")
+                },
+                Token::RightParen,
+                Token::LeftParen,
+                Token::Number {
+                    value: Number::Real(0.5)
+                },
+                Token::Dot,
+                Token::RightParen,
+            ]
         );
+    }
+
+    #[test]
+    fn should_scan_integers_as_exact_numbers() {
+        let tokens = scan_tokens("42 -7 4.0 .5 99999999999999999999");
+
         assert_eq!(
-            tokens.get(3),
-            Some(&Token::Quotation {
-                text_of_quotation: String::from("two")
-            })
+            tokens,
+            vec![
+                Token::Number {
+                    value: Number::Integer(42)
+                },
+                Token::Number {
+                    value: Number::Integer(-7)
+                },
+                Token::Number {
+                    value: Number::Real(4.0)
+                },
+                Token::Number {
+                    value: Number::Real(0.5)
+                },
+                Token::Number {
+                    value: Number::from_big(
+                        BigInteger::parse("99999999999999999999", 10, false).unwrap()
+                    )
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_scan_quasiquotation() {
+        let input = "`(point ,x ,@rest)";
+
+        let tokens = scan_tokens(input);
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Backquote,
+                Token::LeftParen,
+                Token::Identifier {
+                    name: String::from("point")
+                },
+                Token::Comma,
+                Token::Identifier {
+                    name: String::from("x")
+                },
+                Token::CommaAt,
+                Token::Identifier {
+                    name: String::from("rest")
+                },
+                Token::RightParen,
+            ]
+        );
+    }
+
+    #[test]
+    fn should_not_scan_empty_unquotation() {
+        for input in ["(a ,)", "`", ",@ x"] {
+            let error = scan(input).unwrap_err();
+
+            assert_eq!(error.value, LisrScanError::EmptyQuotation);
+        }
+    }
+
+    #[test]
+    fn should_scan_quotation() {
+        let input = "(concat 'one '(two))";
+
+        let tokens = scan_tokens(input);
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::LeftParen,
+                Token::Identifier {
+                    name: String::from("concat")
+                },
+                Token::Apostrophe,
+                Token::Identifier {
+                    name: String::from("one")
+                },
+                Token::Apostrophe,
+                Token::LeftParen,
+                Token::Identifier {
+                    name: String::from("two")
+                },
+                Token::RightParen,
+                Token::RightParen,
+            ]
         );
     }
 
@@ -368,10 +1096,10 @@ mod tests {
     fn should_scan_strings() {
         let input = "\"a very\nlong string\"";
 
-        let tokens = scan(input).unwrap();
+        let tokens = scan_tokens(input);
 
         assert_eq!(
-            tokens.get(0),
+            tokens.first(),
             Some(&Token::String {
                 value: String::from("a very\nlong string")
             })
@@ -391,20 +1119,57 @@ mod tests {
             ("lambda", Token::Lambda),
             ("begin", Token::Begin),
             ("cons", Token::Cons),
+            ("quote", Token::Quote),
+            ("quasiquote", Token::Quasiquote),
+            ("unquote", Token::Unquote),
+            ("unquote-splicing", Token::UnquoteSplicing),
+            ("let", Token::Let),
+            ("let*", Token::LetStar),
+            ("letrec", Token::Letrec),
+            ("letrec*", Token::LetrecStar),
+            ("cond", Token::Cond),
+            ("case", Token::Case),
+            ("else", Token::Else),
+            ("=>", Token::Arrow),
+            ("when", Token::When),
+            ("unless", Token::Unless),
+            ("define-syntax", Token::DefineSyntax),
+            ("let-syntax", Token::LetSyntax),
+            ("letrec-syntax", Token::LetrecSyntax),
+            ("syntax-rules", Token::SyntaxRules),
+            ("define-macro", Token::DefineMacro),
         ];
 
         for (keyword, expected_token) in keyword_to_expected_token.iter() {
-            let tokens = scan(keyword).unwrap();
+            let tokens = scan_tokens(keyword);
 
-            assert_eq!(tokens.get(0), Some(expected_token));
+            assert_eq!(tokens.first(), Some(expected_token));
         }
     }
 
+    #[test]
+    fn should_scan_comparison_operators() {
+        let tokens = scan_tokens("(<= a b) (>= a b) (> a b)");
+
+        let names: Vec<_> = tokens
+            .iter()
+            .filter_map(|token| match token {
+                Token::Identifier { name } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(names, vec!["<=", "a", "b", ">=", "a", "b", ">", "a", "b"]);
+        assert!(matches!(
+            scan("(<=> a b)").unwrap_err().value,
+            LisrScanError::InvalidIdentifier { .. }
+        ));
+    }
+
     #[test]
     fn should_reject_identifiers_that_start_with_primitive_operator() {
         let input = "(define <3 'heart)";
 
-        let error = scan(input).unwrap_err();
+        let error = scan(input).unwrap_err().value;
 
         assert_eq!(
             error,
@@ -418,25 +1183,190 @@ mod tests {
     fn should_not_allow_empty_quotation() {
         let input = "(concat ' 'abc)";
 
-        let error = scan(input).unwrap_err();
+        let error = scan(input).unwrap_err().value;
 
         assert_eq!(error, LisrScanError::EmptyQuotation);
     }
 
+    #[test]
+    fn should_scan_escapes_in_strings() {
+        let input = r#""say \"hi\"\n\tto C:\\dir\r" "\x41;\x3bb;\u{1F600}""#;
+
+        let tokens = scan_tokens(input);
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::String {
+                    value: String::from("say \"hi\"\n\tto C:\\dir\r")
+                },
+                Token::String {
+                    value: String::from("Aλ😀")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_continue_strings_after_a_backslash_at_the_end_of_a_line() {
+        let input = "\"one \\   \n     two\" \"three\\\nfour\"";
+
+        let tokens = scan_tokens(input);
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::String {
+                    value: String::from("one two")
+                },
+                Token::String {
+                    value: String::from("threefour")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_continue_strings_after_a_backslash_at_the_end_of_a_windows_line() {
+        let input = "\"one \\  \r\n  two\" \"three\\\r\nfour\"";
+
+        let tokens = scan_tokens(input);
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::String {
+                    value: String::from("one two")
+                },
+                Token::String {
+                    value: String::from("threefour")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_unknown_escapes_with_their_position() {
+        let input = "(display \"a\\qb\")";
+
+        let error = scan(input).unwrap_err();
+
+        assert_eq!(error.value, LisrScanError::UnknownEscape { escape: 'q' });
+        assert_eq!(
+            error.span,
+            Span::new(Position::new(1, 12), Position::new(1, 14))
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_escapes() {
+        for (input, reason) in [
+            (
+                r#""\x41""#,
+                "A code point must be hexadecimal digits followed by `;` in \\x41; or `}` in \\u{41}.",
+            ),
+            (
+                r#""\x;""#,
+                "The escape is not a valid Unicode code point.",
+            ),
+            (
+                r#""\xD800;""#,
+                "The escape is not a valid Unicode code point.",
+            ),
+            (
+                r#""\u41""#,
+                "A Unicode escape must be written like \\u{1F600}.",
+            ),
+            (
+                r#""\u{110000}""#,
+                "The escape is not a valid Unicode code point.",
+            ),
+            (
+                r#""a\ b""#,
+                "Only whitespace can follow a backslash that continues the line.",
+            ),
+        ] {
+            let error = scan(input).unwrap_err().value;
+
+            assert_eq!(error, LisrScanError::InvalidEscape { reason }, "{input}");
+        }
+    }
+
+    #[test]
+    fn should_not_close_a_string_with_an_escaped_quote() {
+        let error = scan(r#""abc\""#).unwrap_err().value;
+
+        assert_eq!(error, LisrScanError::UnclosedString);
+    }
+
+    #[test]
+    fn should_scan_characters() {
+        let tokens = scan_tokens(r"(#\a #\space #\newline #\x41 #\x3bb #\( #\) #\  #\x)");
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::LeftParen,
+                Token::Character { value: 'a' },
+                Token::Character { value: ' ' },
+                Token::Character { value: '\n' },
+                Token::Character { value: 'A' },
+                Token::Character { value: 'λ' },
+                Token::Character { value: '(' },
+                Token::Character { value: ')' },
+                Token::Character { value: ' ' },
+                Token::Character { value: 'x' },
+                Token::RightParen,
+            ]
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_characters() {
+        for (input, reason) in [
+            (
+                r"#\spaces",
+                "Unknown character name. Use one character, a name like space or newline, or a code point like x41.",
+            ),
+            (
+                r"#\xD800",
+                "The character is not a valid Unicode code point.",
+            ),
+            (r"#\", "`#\\` must be followed by a character."),
+        ] {
+            let error = scan(input).unwrap_err().value;
+
+            assert_eq!(error, LisrScanError::InvalidCharacter { reason }, "{input}");
+        }
+    }
+
     #[test]
     fn should_return_an_error_when_string_is_not_closed() {
         let input = "\"Oops, an unclosed string";
 
-        let error = scan(input).unwrap_err();
+        let error = scan(input).unwrap_err().value;
 
         assert_eq!(error, LisrScanError::UnclosedString);
     }
 
+    #[test]
+    fn should_report_the_position_of_an_error() {
+        let input = "(+ 1\n   12.3.4)";
+
+        let error = scan(input).unwrap_err();
+
+        // The span covers the whole invalid number.
+        assert_eq!(
+            error.span,
+            Span::new(Position::new(2, 4), Position::new(2, 10))
+        );
+    }
+
     #[test]
     fn should_reject_numbers_with_more_than_one_decimal_point() {
         let input = "123.456.78";
 
-        let error = scan(input).unwrap_err();
+        let error = scan(input).unwrap_err().value;
 
         assert_eq!(
             error,
@@ -446,11 +1376,174 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_scan_fractions_in_lowest_terms() {
+        let tokens = scan_tokens("1/3 -6/4 8/4");
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Number {
+                    value: Number::from_fraction(BigInteger::from(1), BigInteger::from(3))
+                },
+                Token::Number {
+                    value: Number::from_fraction(BigInteger::from(-3), BigInteger::from(2))
+                },
+                Token::Number {
+                    value: Number::Integer(2)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_scan_exponents_and_signs() {
+        let tokens = scan_tokens("1e-9 +42 -1.5E3 +.5 2e2 -0");
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Number {
+                    value: Number::Real(1e-9)
+                },
+                Token::Number {
+                    value: Number::Integer(42)
+                },
+                Token::Number {
+                    value: Number::Real(-1500.0)
+                },
+                Token::Number {
+                    value: Number::Real(0.5)
+                },
+                Token::Number {
+                    value: Number::Real(200.0)
+                },
+                Token::Number {
+                    value: Number::Integer(0)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_scan_radix_and_exactness_prefixes() {
+        let tokens = scan_tokens("#x1F #b-1010 #o17 #d99 #XfF/10 #e1.5 #e1e2 #i3 #x#i10 #e#b101");
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Number {
+                    value: Number::Integer(31)
+                },
+                Token::Number {
+                    value: Number::Integer(-10)
+                },
+                Token::Number {
+                    value: Number::Integer(15)
+                },
+                Token::Number {
+                    value: Number::Integer(99)
+                },
+                Token::Number {
+                    value: Number::from_fraction(BigInteger::from(255), BigInteger::from(16))
+                },
+                Token::Number {
+                    value: Number::from_fraction(BigInteger::from(3), BigInteger::from(2))
+                },
+                Token::Number {
+                    value: Number::Integer(100)
+                },
+                Token::Number {
+                    value: Number::Real(3.0)
+                },
+                Token::Number {
+                    value: Number::Real(16.0)
+                },
+                Token::Number {
+                    value: Number::Integer(5)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_scan_exact_decimals_without_rounding() {
+        let tokens = scan_tokens("#e0.1");
+
+        assert_eq!(
+            tokens,
+            vec![Token::Number {
+                value: Number::from_fraction(BigInteger::from(1), BigInteger::from(10))
+            }]
+        );
+    }
+
+    #[test]
+    fn should_scan_infinities_and_nan() {
+        let tokens = scan_tokens("+inf.0 -inf.0 +nan.0");
+
+        assert!(matches!(
+            tokens.as_slice(),
+            [
+                Token::Number {
+                    value: Number::Real(positive)
+                },
+                Token::Number {
+                    value: Number::Real(negative)
+                },
+                Token::Number {
+                    value: Number::Real(nan)
+                },
+            ] if *positive == f64::INFINITY && *negative == f64::NEG_INFINITY && nan.is_nan()
+        ));
+    }
+
+    #[test]
+    fn should_reject_invalid_number_prefixes_and_exponents() {
+        for (input, reason) in [
+            (
+                "#x#x1",
+                "A number can have at most one radix prefix and one exactness prefix.",
+            ),
+            (
+                "#e#z1",
+                "Unknown number prefix. Use one of #x, #b, #o, #d, #e and #i.",
+            ),
+            ("#b102", "Numbers can contain digits only."),
+            ("1e", "An exponent must be an integer."),
+            ("#e+inf.0", "Infinities and NaN cannot be exact."),
+        ] {
+            let error = scan(input).unwrap_err().value;
+
+            assert_eq!(error, LisrScanError::InvalidNumber { reason });
+        }
+    }
+
+    #[test]
+    fn should_reject_invalid_fractions() {
+        for (input, reason) in [
+            ("1/0", "A fraction cannot have a zero denominator."),
+            (
+                "1/",
+                "A fraction must be written as two integers separated by a single slash.",
+            ),
+            (
+                "1.5/2",
+                "A fraction must be written as two integers separated by a single slash.",
+            ),
+            ("1/2.5", "A fraction can contain integers only."),
+        ] {
+            let error = scan(input).unwrap_err().value;
+
+            assert_eq!(error, LisrScanError::InvalidNumber { reason });
+        }
+    }
+
     #[test]
     fn should_reject_numbers_that_do_not_contain_digits_only() {
         let input = "0x123";
 
-        let error = scan(input).unwrap_err();
+        let error = scan(input).unwrap_err().value;
 
         assert_eq!(
             error,
@@ -464,7 +1557,7 @@ mod tests {
     fn should_reject_identifiers_that_start_with_a_digit() {
         let input = "(define (5plus x) (+ 5 x))";
 
-        let error = scan(input).unwrap_err();
+        let error = scan(input).unwrap_err().value;
 
         assert_eq!(
             error,
@@ -473,4 +1566,203 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn should_skip_line_comments() {
+        let input = "; a comment\n(x) ; after a list\n(y ; inside a list\n z);(w)";
+
+        let tokens = scan_tokens(input);
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::LeftParen,
+                Token::Identifier {
+                    name: String::from("x")
+                },
+                Token::RightParen,
+                Token::LeftParen,
+                Token::Identifier {
+                    name: String::from("y")
+                },
+                Token::Identifier {
+                    name: String::from("z")
+                },
+                Token::RightParen,
+            ]
+        );
+    }
+
+    #[test]
+    fn should_end_literals_at_line_comments() {
+        let input = "x;comment\n42;comment\n-;comment";
+
+        let tokens = scan_tokens(input);
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Identifier {
+                    name: String::from("x")
+                },
+                Token::Number {
+                    value: Number::Integer(42)
+                },
+                Token::Identifier {
+                    name: String::from("-")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_skip_block_comments_adjacent_to_parentheses() {
+        let input = "(#|first|# x #|second\nline|#)#|last|#";
+
+        let tokens = scan_tokens(input);
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::LeftParen,
+                Token::Identifier {
+                    name: String::from("x")
+                },
+                Token::RightParen,
+            ]
+        );
+    }
+
+    #[test]
+    fn should_skip_nested_block_comments() {
+        let input = "(#| outer #| inner |# (still a comment) |# x)";
+
+        let tokens = scan_tokens(input);
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::LeftParen,
+                Token::Identifier {
+                    name: String::from("x")
+                },
+                Token::RightParen,
+            ]
+        );
+    }
+
+    #[test]
+    fn should_skip_datum_comments_inside_lists() {
+        let input = "(+ 1 #;(* 2 (- 3)) #; 4 5)";
+
+        let tokens = scan_tokens(input);
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::LeftParen,
+                Token::Identifier {
+                    name: String::from("+")
+                },
+                Token::Number {
+                    value: Number::Integer(1)
+                },
+                Token::Number {
+                    value: Number::Integer(5)
+                },
+                Token::RightParen,
+            ]
+        );
+    }
+
+    #[test]
+    fn should_skip_comments_between_a_datum_comment_and_its_datum() {
+        let input = "(#; ; line\n #| block |# x y)";
+
+        let tokens = scan_tokens(input);
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::LeftParen,
+                Token::Identifier {
+                    name: String::from("y")
+                },
+                Token::RightParen,
+            ]
+        );
+    }
+
+    #[test]
+    fn should_skip_quoted_data_in_datum_comments() {
+        let inputs = [
+            "(#;'x y)",
+            "(#;'(a b) y)",
+            "(#;`(a ,b) y)",
+            "(#;`(a ,@(b c)) y)",
+        ];
+
+        for input in inputs {
+            let tokens = scan_tokens(input);
+
+            assert_eq!(
+                tokens,
+                vec![
+                    Token::LeftParen,
+                    Token::Identifier {
+                        name: String::from("y")
+                    },
+                    Token::RightParen,
+                ],
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn should_keep_track_of_positions_after_comments() {
+        let input = "#| one\ntwo |# ; three\n  x";
+
+        let spans: Vec<Span> = scan(input)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.span)
+            .collect();
+
+        assert_eq!(
+            spans,
+            vec![Span::new(Position::new(3, 3), Position::new(3, 4))]
+        );
+    }
+
+    #[test]
+    fn should_return_an_error_when_block_comment_is_not_closed() {
+        let input = "(x) #| outer #| inner |#";
+
+        let error = scan(input).unwrap_err();
+
+        assert_eq!(error.value, LisrScanError::UnclosedBlockComment);
+        assert_eq!(error.span.start, Position::new(1, 5));
+    }
+
+    #[test]
+    fn should_not_allow_empty_datum_comment() {
+        assert_eq!(
+            scan("(x #;)").unwrap_err().value,
+            LisrScanError::EmptyDatumComment
+        );
+        assert_eq!(
+            scan("x #; ; nothing").unwrap_err().value,
+            LisrScanError::EmptyDatumComment
+        );
+    }
+
+    #[test]
+    fn should_return_an_error_when_datum_comment_is_not_closed() {
+        let input = "#;(x (y)";
+
+        let error = scan(input).unwrap_err().value;
+
+        assert_eq!(error, LisrScanError::UnclosedDatumComment);
+    }
 }
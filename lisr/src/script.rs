@@ -0,0 +1,146 @@
+use std::{fs, io::Write, iter, process::ExitCode};
+
+use lisr::{diagnostic::Diagnostic, interpret::Interpreter};
+
+// Runs a lisr source file and prints the value of its last expression. The
+// path of the script followed by the arguments is available to the program
+// through `(command-line)`.
+pub fn run(
+    path: &str,
+    arguments: &[String],
+    mut output: impl Write,
+    mut error_output: impl Write,
+) -> ExitCode {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(error) => {
+            // There is nothing more we can do if writing the diagnostic fails.
+            let _ = writeln!(error_output, "lisr: cannot read {}: {}", path, error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut interpreter = Interpreter::new();
+    interpreter.define_command_line(
+        iter::once(path.to_string())
+            .chain(arguments.iter().cloned())
+            .collect(),
+    );
+
+    match interpreter.interpret_script(&source) {
+        Ok(result) => match writeln!(output, "{}", result) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(_) => ExitCode::FAILURE,
+        },
+        Err(error) => {
+            let _ = write!(error_output, "{}", Diagnostic::new(&error, path, &source));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, path::PathBuf};
+
+    use super::*;
+
+    // A script in the temporary directory, which is removed when the test is
+    // done, even if it fails.
+    struct Script {
+        path: PathBuf,
+    }
+
+    impl Script {
+        fn write(name: &str, source: &str) -> Script {
+            let path = env::temp_dir().join(format!("lisr-{}-{}.lisr", name, std::process::id()));
+            fs::write(&path, source).unwrap();
+            Script { path }
+        }
+
+        fn path(&self) -> &str {
+            self.path.to_str().unwrap()
+        }
+    }
+
+    impl Drop for Script {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+
+    fn run_script(path: &str, arguments: &[String]) -> (ExitCode, String, String) {
+        let mut output = Vec::new();
+        let mut error_output = Vec::new();
+        let exit_code = run(path, arguments, &mut output, &mut error_output);
+        (
+            exit_code,
+            String::from_utf8(output).unwrap(),
+            String::from_utf8(error_output).unwrap(),
+        )
+    }
+
+    #[test]
+    fn should_print_the_value_of_the_last_expression() {
+        let script = Script::write("last-value", "(define x 20)\n(+ x 22)\n");
+
+        let (exit_code, output, error_output) = run_script(script.path(), &[]);
+
+        assert_eq!(exit_code, ExitCode::SUCCESS);
+        assert_eq!(output, "42\n");
+        assert_eq!(error_output, "");
+    }
+
+    #[test]
+    fn should_expose_script_arguments() {
+        let script = Script::write("arguments", "(cdr (command-line))");
+
+        let (exit_code, output, _) = run_script(
+            script.path(),
+            &[String::from("first"), String::from("second")],
+        );
+
+        assert_eq!(exit_code, ExitCode::SUCCESS);
+        assert_eq!(output, "(\"first\" \"second\")\n");
+    }
+
+    #[test]
+    fn should_fail_on_errors() {
+        let script = Script::write("error", "(define x 1)\n(+ x y)\n");
+
+        let (exit_code, output, error_output) = run_script(script.path(), &[]);
+
+        assert_eq!(exit_code, ExitCode::FAILURE);
+        assert_eq!(output, "");
+        assert_eq!(
+            error_output,
+            format!(
+                "evaluation error: undefined identifier\n \
+                 --> {}:2:6\n  \
+                 |\n\
+                 2 | (+ x y)\n  \
+                 |      ^\n  \
+                 = help: define it before it is used, like (define x 42)\n",
+                script.path()
+            )
+        );
+    }
+
+    #[test]
+    fn should_stop_at_the_first_error() {
+        let script = Script::write("first-error", "(define x 1)\n(set! y 2)\nx\n");
+
+        let (exit_code, output, _) = run_script(script.path(), &[]);
+
+        assert_eq!(exit_code, ExitCode::FAILURE);
+        assert_eq!(output, "");
+    }
+
+    #[test]
+    fn should_fail_when_the_file_cannot_be_read() {
+        let (exit_code, _, error_output) = run_script("/nonexistent/script.lisr", &[]);
+
+        assert_eq!(exit_code, ExitCode::FAILURE);
+        assert!(error_output.starts_with("lisr: cannot read /nonexistent/script.lisr"));
+    }
+}
@@ -0,0 +1,119 @@
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    fmt,
+    hash::{Hash, Hasher},
+    rc::Rc,
+};
+
+// Symbols with the same name share their text, so they are compared and hashed
+// by address instead of by the characters of their names.
+#[derive(Clone)]
+pub struct Symbol {
+    name: Rc<str>,
+}
+
+thread_local! {
+    static SYMBOLS: RefCell<HashSet<Rc<str>>> = RefCell::new(HashSet::new());
+}
+
+impl Symbol {
+    pub fn new(name: &str) -> Symbol {
+        SYMBOLS.with(|symbols| {
+            let mut symbols = symbols.borrow_mut();
+            let name = match symbols.get(name) {
+                Some(name) => Rc::clone(name),
+                None => {
+                    let name: Rc<str> = Rc::from(name);
+                    symbols.insert(Rc::clone(&name));
+                    name
+                }
+            };
+            Symbol { name }
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.name, &other.name)
+    }
+}
+
+impl Eq for Symbol {}
+
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Rc::as_ptr(&self.name).cast::<u8>().hash(state);
+    }
+}
+
+// An address is already unique, so hashing it only has to spread its bits,
+// which a multiplication does a lot faster than the default hasher.
+#[derive(Default)]
+pub struct AddressHasher {
+    hash: u64,
+}
+
+impl Hasher for AddressHasher {
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.write_u64(u64::from(*byte));
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ value).wrapping_mul(0x517cc1b727220a95);
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Symbol({})", self.name)
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_intern_symbols_with_the_same_name() {
+        let first = Symbol::new("abc");
+        let second = Symbol::new(&String::from("abc"));
+
+        assert_eq!(first, second);
+        assert!(Rc::ptr_eq(&first.name, &second.name));
+    }
+
+    #[test]
+    fn should_tell_apart_symbols_with_different_names() {
+        assert_ne!(Symbol::new("abc"), Symbol::new("abd"));
+    }
+
+    #[test]
+    fn should_keep_the_name_of_a_symbol() {
+        let symbol = Symbol::new("hello-world");
+
+        assert_eq!(symbol.name(), "hello-world");
+        assert_eq!(symbol.to_string(), "hello-world");
+    }
+}
@@ -1,12 +1,22 @@
+use crate::number::Number;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Token {
     LeftParen,
     RightParen,
 
     String { value: String },
-    Number { value: f64 },
+    Number { value: Number },
+    Character { value: char },
 
-    Quotation { text_of_quotation: String },
+    // The `'` in `'datum`.
+    Apostrophe,
+    // The `` ` ``, `,` and `,@` in `` `(a ,b ,@c) ``.
+    Backquote,
+    Comma,
+    CommaAt,
+    // The `.` in `(a . b)`.
+    Dot,
 
     Identifier { name: String },
 
@@ -23,4 +33,66 @@ pub enum Token {
     Begin,
 
     Cons,
+
+    Quote,
+    Quasiquote,
+    Unquote,
+    UnquoteSplicing,
+
+    Let,
+    LetStar,
+    Letrec,
+    LetrecStar,
+
+    Cond,
+    Case,
+    Else,
+    // The `=>` in `(cond (test => receiver))`.
+    Arrow,
+    When,
+    Unless,
+
+    DefineSyntax,
+    LetSyntax,
+    LetrecSyntax,
+    SyntaxRules,
+    DefineMacro,
+}
+
+impl Token {
+    // The text of a keyword token, like `define` for Token::Define.
+    pub fn keyword(&self) -> Option<&'static str> {
+        match self {
+            Token::Set => Some("set!"),
+            Token::Define => Some("define"),
+            Token::True => Some("true"),
+            Token::False => Some("false"),
+            Token::If => Some("if"),
+            Token::And => Some("and"),
+            Token::Or => Some("or"),
+            Token::Lambda => Some("lambda"),
+            Token::Begin => Some("begin"),
+            Token::Cons => Some("cons"),
+            Token::Quote => Some("quote"),
+            Token::Quasiquote => Some("quasiquote"),
+            Token::Unquote => Some("unquote"),
+            Token::UnquoteSplicing => Some("unquote-splicing"),
+            Token::Let => Some("let"),
+            Token::LetStar => Some("let*"),
+            Token::Letrec => Some("letrec"),
+            Token::LetrecStar => Some("letrec*"),
+            Token::Cond => Some("cond"),
+            Token::Case => Some("case"),
+            Token::Else => Some("else"),
+            Token::Arrow => Some("=>"),
+            Token::When => Some("when"),
+            Token::Unless => Some("unless"),
+            Token::DefineSyntax => Some("define-syntax"),
+            Token::LetSyntax => Some("let-syntax"),
+            Token::LetrecSyntax => Some("letrec-syntax"),
+            Token::SyntaxRules => Some("syntax-rules"),
+            Token::DefineMacro => Some("define-macro"),
+            _ => None,
+        }
+    }
 }
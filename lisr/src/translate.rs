@@ -1,32 +1,42 @@
-use std::collections::VecDeque;
+use std::{collections::VecDeque, rc::Rc};
 
 use crate::{
-    expression::{Expression, Identifier, Parameter},
+    expression::{
+        Binding, CaseClause, ClauseBody, CondClause, Expression, Identifier, LetKind, Parameter,
+    },
     node::Node,
     parse::LisrParseError,
+    scan::match_keyword_or_identifier,
+    span::{Span, Spanned},
+    symbol::Symbol,
     token::Token,
 };
 
-pub fn translate<I>(nodes: I) -> Result<Vec<Expression>, LisrParseError>
+pub fn translate<I>(nodes: I) -> Result<Vec<Expression>, Spanned<LisrParseError>>
 where
     I: IntoIterator<Item = Node>,
 {
     nodes.into_iter().map(translate_node).collect()
 }
 
-fn translate_node(node: Node) -> Result<Expression, LisrParseError> {
+fn translate_node(node: Node) -> Result<Expression, Spanned<LisrParseError>> {
     match node {
-        Node::Leaf { token } => translate_leaf(token),
-        Node::List { elements } => translate_list(elements),
+        Node::Leaf { token, span } => translate_leaf(token, span),
+        Node::List { elements, span } => translate_list(elements, span),
     }
 }
 
-fn translate_leaf(token: Token) -> Result<Expression, LisrParseError> {
+fn translate_leaf(token: Token, span: Span) -> Result<Expression, Spanned<LisrParseError>> {
     match token {
         Token::String { value } => Ok(Expression::String { value }),
         Token::Number { value } => Ok(Expression::Number { value }),
-        Token::Quotation { text_of_quotation } => translate_quotation(text_of_quotation),
-        Token::Identifier { name } => Ok(Expression::Identifier(Identifier { name })),
+        Token::Character { value } => Ok(Expression::Character { value }),
+        Token::Identifier { name } => Ok(Expression::Identifier {
+            identifier: Identifier {
+                name: Symbol::new(&name),
+            },
+            span,
+        }),
         Token::Set => Ok(Expression::LisrInternalObject {
             name: String::from("set!"),
         }),
@@ -53,78 +63,163 @@ fn translate_leaf(token: Token) -> Result<Expression, LisrParseError> {
         Token::Cons => Ok(Expression::LisrInternalObject {
             name: String::from("cons"),
         }),
-        Token::LeftParen | Token::RightParen => {
-            panic!("Cannot translate parentheses to an expression")
+        Token::Quote => Ok(Expression::LisrInternalObject {
+            name: String::from("quote"),
+        }),
+        Token::Let
+        | Token::LetStar
+        | Token::Letrec
+        | Token::LetrecStar
+        | Token::Cond
+        | Token::Case
+        | Token::Else
+        | Token::Arrow
+        | Token::When
+        | Token::Unless
+        | Token::DefineSyntax
+        | Token::LetSyntax
+        | Token::LetrecSyntax
+        | Token::SyntaxRules
+        | Token::DefineMacro
+        | Token::Quasiquote
+        | Token::Unquote
+        | Token::UnquoteSplicing => Ok(Expression::LisrInternalObject {
+            name: String::from(token.keyword().unwrap_or_default()),
+        }),
+        Token::Dot => Err(Spanned::new(LisrParseError::UnexpectedDot, span)),
+        Token::LeftParen
+        | Token::RightParen
+        | Token::Apostrophe
+        | Token::Backquote
+        | Token::Comma
+        | Token::CommaAt => {
+            panic!("Cannot translate parentheses or apostrophes to an expression")
         }
     }
 }
 
-fn translate_quotation(text_of_quotation: String) -> Result<Expression, LisrParseError> {
-    // TODO: add translation to lists.
-    Ok(Expression::Quotation { text_of_quotation })
-}
-
-fn translate_list(mut elements: VecDeque<Node>) -> Result<Expression, LisrParseError> {
+fn translate_list(
+    mut elements: VecDeque<Node>,
+    span: Span,
+) -> Result<Expression, Spanned<LisrParseError>> {
     if let Some(first) = elements.pop_front() {
         let mut rest = elements;
         match first {
-            Node::Leaf { ref token } => match token {
+            Node::Leaf {
+                ref token,
+                span: first_span,
+            } => match token {
                 Token::String { .. }
                 | Token::Number { .. }
-                | Token::Quotation { .. }
+                | Token::Character { .. }
                 | Token::True
-                | Token::False => {
-                    return Err(LisrParseError::ObjectNotInvokable);
+                | Token::False
+                | Token::Else
+                | Token::Arrow
+                // Macros are expanded before translation.
+                | Token::DefineSyntax
+                | Token::LetSyntax
+                | Token::LetrecSyntax
+                | Token::SyntaxRules
+                | Token::DefineMacro => {
+                    return Err(Spanned::new(LisrParseError::ObjectNotInvokable, span));
                 }
-                Token::LeftParen | Token::RightParen => {
-                    panic!("Cannot translate parentheses to expression")
+                Token::LeftParen
+        | Token::RightParen
+        | Token::Apostrophe
+        | Token::Backquote
+        | Token::Comma
+        | Token::CommaAt => {
+                    panic!("Cannot translate parentheses or apostrophes to expression")
                 }
                 Token::Identifier { .. } => {
-                    return create_application(first, rest);
+                    return create_application(first, rest, span);
+                }
+                Token::Dot => {
+                    return Err(Spanned::new(LisrParseError::UnexpectedDot, first_span));
                 }
                 Token::Set => {
-                    return create_assignment(rest);
+                    return create_assignment(rest, span);
                 }
                 Token::Define => {
-                    return create_definition(rest);
+                    return create_definition(rest, span);
                 }
                 Token::If => {
-                    return create_if(rest);
+                    return create_if(rest, span);
                 }
                 Token::And => {
-                    return create_and(rest);
+                    return create_and(rest, span);
                 }
                 Token::Or => {
-                    return create_or(rest);
+                    return create_or(rest, span);
                 }
                 // TODO: Why not validate stuff in create_lambda like I do for other tokens?
                 Token::Lambda => {
                     let parameters = rest.pop_front();
-                    let body = rest.pop_front();
-                    match (parameters, body) {
-                        (Some(Node::List { elements }), Some(body)) => {
-                            return create_lambda(elements, body);
+                    match parameters {
+                        Some(
+                            parameters @ (Node::List { .. }
+                            | Node::Leaf {
+                                token: Token::Identifier { .. },
+                                ..
+                            }),
+                        ) if !rest.is_empty() => {
+                            return create_lambda(parameters, rest, span);
                         }
                         _ => {
-                            return Err(LisrParseError::LambdaRequiresParameterListAndBody);
+                            return Err(Spanned::new(
+                                LisrParseError::LambdaRequiresParameterListAndBody,
+                                span,
+                            ));
                         }
                     }
                 }
                 Token::Begin => {
-                    return create_begin(rest);
+                    return create_begin(rest, span);
                 }
                 Token::Cons => {
-                    return create_cons(rest);
+                    return create_cons(rest, span);
+                }
+                Token::Quote => {
+                    return create_quotation(rest, span);
+                }
+                Token::Quasiquote => {
+                    return create_quasiquotation(rest, span);
+                }
+                Token::Unquote | Token::UnquoteSplicing => {
+                    return Err(Spanned::new(
+                        LisrParseError::UnquoteOutsideOfQuasiquote,
+                        span,
+                    ));
+                }
+                Token::Let => {
+                    return create_let(LetKind::Let, rest, span);
+                }
+                Token::LetStar => {
+                    return create_let(LetKind::LetStar, rest, span);
+                }
+                Token::Letrec => {
+                    return create_let(LetKind::Letrec, rest, span);
+                }
+                Token::LetrecStar => {
+                    return create_let(LetKind::LetrecStar, rest, span);
+                }
+                Token::Cond => {
+                    return create_cond(rest, span);
+                }
+                Token::Case => {
+                    return create_case(rest, span);
+                }
+                Token::When => {
+                    return create_when(rest, span);
+                }
+                Token::Unless => {
+                    return create_unless(rest, span);
                 }
             },
             Node::List { .. } => {
                 // This must be an application if the first element is a list.
-                let procedure = translate_node(first)?;
-                let arguments = translate(rest)?;
-                return Ok(Expression::Application {
-                    procedure: Box::new(procedure),
-                    arguments,
-                });
+                return create_application(first, rest, span);
             }
         }
     }
@@ -135,60 +230,90 @@ fn translate_list(mut elements: VecDeque<Node>) -> Result<Expression, LisrParseE
 fn create_application(
     procedure: Node,
     arguments: VecDeque<Node>,
-) -> Result<Expression, LisrParseError> {
+    span: Span,
+) -> Result<Expression, Spanned<LisrParseError>> {
     let procedure = translate_node(procedure)?;
     let arguments = translate(arguments)?;
     Ok(Expression::Application {
         procedure: Box::new(procedure),
         arguments,
+        span,
     })
 }
 
-fn create_assignment(mut arguments: VecDeque<Node>) -> Result<Expression, LisrParseError> {
+fn create_assignment(
+    mut arguments: VecDeque<Node>,
+    span: Span,
+) -> Result<Expression, Spanned<LisrParseError>> {
     let variable = arguments.pop_front();
     let value = arguments.pop_front();
 
     if !arguments.is_empty() {
         // We should have consumed everything an assignment requires.
-        return Err(LisrParseError::AssignmentRequiresOneVariableAndOneValue);
+        return Err(Spanned::new(
+            LisrParseError::AssignmentRequiresOneVariableAndOneValue,
+            span,
+        ));
     }
 
     match (variable, value) {
         (
             Some(Node::Leaf {
                 token: Token::Identifier { name },
+                ..
             }),
             Some(value),
         ) => {
             let value = translate_node(value)?;
             Ok(Expression::Assignment {
-                variable: Identifier { name },
+                variable: Identifier {
+                    name: Symbol::new(&name),
+                },
                 value: Box::new(value),
+                span,
             })
         }
-        _ => Err(LisrParseError::AssignmentRequiresOneVariableAndOneValue),
+        _ => Err(Spanned::new(
+            LisrParseError::AssignmentRequiresOneVariableAndOneValue,
+            span,
+        )),
     }
 }
 
-fn create_definition(mut arguments: VecDeque<Node>) -> Result<Expression, LisrParseError> {
+fn create_definition(
+    mut arguments: VecDeque<Node>,
+    span: Span,
+) -> Result<Expression, Spanned<LisrParseError>> {
     let defined_entity = arguments.pop_front();
-    let value = arguments.pop_front();
 
-    match (defined_entity, value) {
-        (Some(defined_entity), Some(value)) => {
+    match defined_entity {
+        Some(defined_entity) if !arguments.is_empty() => {
             match defined_entity {
-                // Simple variable definition.
+                // Simple variable definition, which takes exactly one value.
                 Node::Leaf {
                     token: Token::Identifier { name },
+                    ..
                 } => {
+                    let (Some(value), None) = (arguments.pop_front(), arguments.pop_front()) else {
+                        return Err(Spanned::new(
+                            LisrParseError::DefinitionRequiresVariableAndBody,
+                            span,
+                        ));
+                    };
                     let value = translate_node(value)?;
                     Ok(Expression::Definition {
-                        variable: Identifier { name },
+                        variable: Identifier {
+                            name: Symbol::new(&name),
+                        },
                         value: Box::new(value),
+                        span,
                     })
                 }
                 // Function definition.
-                Node::List { mut elements } => {
+                Node::List {
+                    mut elements,
+                    span: signature_span,
+                } => {
                     let function_name = elements.pop_front();
                     match function_name {
                         Some(Node::Leaf {
@@ -196,88 +321,171 @@ fn create_definition(mut arguments: VecDeque<Node>) -> Result<Expression, LisrPa
                                 Token::Identifier {
                                     name: function_name,
                                 },
+                            ..
                         }) => {
-                            let body = value;
-                            let lambda = create_lambda(elements, body)?;
+                            let parameters = Node::List {
+                                elements,
+                                span: signature_span,
+                            };
+                            let lambda = create_lambda(parameters, arguments, span)?;
                             Ok(Expression::Definition {
                                 variable: Identifier {
-                                    name: function_name,
+                                    name: Symbol::new(&function_name),
                                 },
                                 value: Box::new(lambda),
+                                span,
                             })
                         }
-                        _ => Err(LisrParseError::VariableRequiredInThisContext),
+                        Some(node) => Err(Spanned::new(
+                            LisrParseError::VariableRequiredInThisContext,
+                            node.span(),
+                        )),
+                        None => Err(Spanned::new(
+                            LisrParseError::VariableRequiredInThisContext,
+                            span,
+                        )),
                     }
                 }
-                _ => Err(LisrParseError::VariableRequiredInThisContext),
+                node => Err(Spanned::new(
+                    LisrParseError::VariableRequiredInThisContext,
+                    node.span(),
+                )),
             }
         }
-        _ => Err(LisrParseError::DefinitionRequiresVariableAndBody),
+        _ => Err(Spanned::new(
+            LisrParseError::DefinitionRequiresVariableAndBody,
+            span,
+        )),
     }
 }
 
-fn create_if(mut arguments: VecDeque<Node>) -> Result<Expression, LisrParseError> {
+fn create_if(
+    mut arguments: VecDeque<Node>,
+    span: Span,
+) -> Result<Expression, Spanned<LisrParseError>> {
+    let found = arguments.len();
     let predicate = arguments.pop_front();
     let consequent = arguments.pop_front();
     let alternative = arguments.pop_front();
 
     if !arguments.is_empty() {
         // We should have consumed everything that an if statement requires.
-        return Err(LisrParseError::IfRequiresPredicateConsequentAndAlternative);
+        return Err(Spanned::new(
+            LisrParseError::IfRequiresPredicateAndConsequent { found },
+            span,
+        ));
     }
 
     match (predicate, consequent, alternative) {
-        (Some(predicate), Some(consequent), Some(alternative)) => {
+        (Some(predicate), Some(consequent), alternative) => {
             let predicate = translate_node(predicate)?;
             let consequent = translate_node(consequent)?;
-            let alternative = translate_node(alternative)?;
+            // Without an alternative, the value of a false if is unspecified.
+            let alternative = match alternative {
+                Some(alternative) => translate_node(alternative)?,
+                None => Expression::Unspecified,
+            };
             Ok(Expression::If {
                 predicate: Box::new(predicate),
                 consequent: Box::new(consequent),
                 alternative: Box::new(alternative),
+                span,
             })
         }
-        _ => Err(LisrParseError::IfRequiresPredicateConsequentAndAlternative),
+        _ => Err(Spanned::new(
+            LisrParseError::IfRequiresPredicateAndConsequent { found },
+            span,
+        )),
     }
 }
 
-fn create_and(arguments: VecDeque<Node>) -> Result<Expression, LisrParseError> {
+fn create_and(
+    arguments: VecDeque<Node>,
+    span: Span,
+) -> Result<Expression, Spanned<LisrParseError>> {
     let operands = translate(arguments)?;
-    Ok(Expression::And { operands })
+    Ok(Expression::And { operands, span })
 }
 
-fn create_or(arguments: VecDeque<Node>) -> Result<Expression, LisrParseError> {
+fn create_or(arguments: VecDeque<Node>, span: Span) -> Result<Expression, Spanned<LisrParseError>> {
     let operands = translate(arguments)?;
-    Ok(Expression::Or { operands })
+    Ok(Expression::Or { operands, span })
 }
 
-fn create_lambda(parameters: VecDeque<Node>, body: Node) -> Result<Expression, LisrParseError> {
-    let parameters = translate_parameters(parameters)?;
-    let body = translate_node(body)?;
+// Parameters are either a list, like `(a b)` or `(a b . rest)`, or a single
+// identifier, like `args`, that gets all the arguments. The body is a sequence
+// of expressions, like in a `begin`.
+fn create_lambda(
+    parameters: Node,
+    body: VecDeque<Node>,
+    span: Span,
+) -> Result<Expression, Spanned<LisrParseError>> {
+    let (parameters, rest_parameter) = match parameters {
+        Node::List { elements, .. } => translate_parameters(elements)?,
+        parameter => (Vec::new(), Some(translate_parameter(parameter)?)),
+    };
+    let body = create_body(body, span)?;
     Ok(Expression::Lambda {
-        parameters,
-        body: Box::new(body),
+        parameters: Rc::from(parameters),
+        rest_parameter,
+        body: Rc::new(body),
     })
 }
 
-fn translate_parameters(parameters: VecDeque<Node>) -> Result<Vec<Parameter>, LisrParseError> {
-    parameters
-        .into_iter()
-        .map(|element| match element {
-            Node::Leaf {
-                token: Token::Identifier { name },
-            } => Ok(Parameter { name }),
-            _ => Err(LisrParseError::UnexpectedExpressionForLambdaParameter),
-        })
-        .collect()
+fn translate_parameters(
+    parameters: VecDeque<Node>,
+) -> Result<(Vec<Parameter>, Option<Parameter>), Spanned<LisrParseError>> {
+    let mut parameters = parameters.into_iter();
+    let mut required_parameters = Vec::new();
+
+    while let Some(parameter) = parameters.next() {
+        if let Node::Leaf {
+            token: Token::Dot,
+            span,
+        } = parameter
+        {
+            // Exactly one parameter has to follow the dot.
+            return match (parameters.next(), parameters.next()) {
+                (Some(rest_parameter), None) => Ok((
+                    required_parameters,
+                    Some(translate_parameter(rest_parameter)?),
+                )),
+                _ => Err(Spanned::new(LisrParseError::UnexpectedDot, span)),
+            };
+        }
+        required_parameters.push(translate_parameter(parameter)?);
+    }
+
+    Ok((required_parameters, None))
+}
+
+fn translate_parameter(parameter: Node) -> Result<Parameter, Spanned<LisrParseError>> {
+    match parameter {
+        Node::Leaf {
+            token: Token::Identifier { name },
+            ..
+        } => Ok(Parameter {
+            name: Symbol::new(&name),
+        }),
+        _ => Err(Spanned::new(
+            LisrParseError::UnexpectedExpressionForLambdaParameter,
+            parameter.span(),
+        )),
+    }
 }
 
-fn create_begin(arguments: VecDeque<Node>) -> Result<Expression, LisrParseError> {
+fn create_begin(
+    arguments: VecDeque<Node>,
+    span: Span,
+) -> Result<Expression, Spanned<LisrParseError>> {
     let sequence = translate(arguments)?;
-    Ok(Expression::Begin { sequence })
+    Ok(Expression::Begin { sequence, span })
 }
 
-fn create_cons(mut arguments: VecDeque<Node>) -> Result<Expression, LisrParseError> {
+fn create_cons(
+    mut arguments: VecDeque<Node>,
+    span: Span,
+) -> Result<Expression, Spanned<LisrParseError>> {
     let left = arguments.pop_front();
     let right = arguments.pop_front();
     match (left, right) {
@@ -285,13 +493,545 @@ fn create_cons(mut arguments: VecDeque<Node>) -> Result<Expression, LisrParseErr
             first: Box::new(translate_node(left)?),
             rest: Box::new(translate_node(right)?),
         }),
-        _ => Err(LisrParseError::ConsRequiresTwoArguments),
+        _ => Err(Spanned::new(LisrParseError::ConsRequiresTwoArguments, span)),
+    }
+}
+
+fn create_let(
+    kind: LetKind,
+    mut arguments: VecDeque<Node>,
+    span: Span,
+) -> Result<Expression, Spanned<LisrParseError>> {
+    if let (
+        LetKind::Let,
+        Some(Node::Leaf {
+            token: Token::Identifier { .. },
+            ..
+        }),
+    ) = (kind, arguments.front())
+    {
+        return create_named_let(arguments, span);
+    }
+
+    let bindings = match arguments.pop_front() {
+        Some(Node::List { elements, .. }) if !arguments.is_empty() => elements,
+        _ => {
+            return Err(Spanned::new(
+                LisrParseError::LetRequiresBindingsAndBody,
+                span,
+            ))
+        }
+    };
+
+    let bindings = bindings
+        .into_iter()
+        .map(translate_binding)
+        .collect::<Result<Vec<Binding>, Spanned<LisrParseError>>>()?;
+    let body = create_body(arguments, span)?;
+    Ok(Expression::Let {
+        kind,
+        bindings,
+        body: Box::new(body),
+        span,
+    })
+}
+
+// `(let loop ((i 0)) body)` becomes a call of a local procedure:
+// `((letrec ((loop (lambda (i) body))) loop) 0)`, so calls of `loop` in tail
+// positions run in constant space.
+fn create_named_let(
+    mut arguments: VecDeque<Node>,
+    span: Span,
+) -> Result<Expression, Spanned<LisrParseError>> {
+    let (
+        Some(Node::Leaf {
+            token: Token::Identifier { name },
+            span: name_span,
+        }),
+        Some(Node::List {
+            elements: bindings, ..
+        }),
+    ) = (arguments.pop_front(), arguments.pop_front())
+    else {
+        return Err(Spanned::new(
+            LisrParseError::LetRequiresBindingsAndBody,
+            span,
+        ));
+    };
+    if arguments.is_empty() {
+        return Err(Spanned::new(
+            LisrParseError::LetRequiresBindingsAndBody,
+            span,
+        ));
+    }
+
+    let mut parameters = Vec::new();
+    let mut initial_values = Vec::new();
+    for binding in bindings {
+        let Binding { variable, value } = translate_binding(binding)?;
+        parameters.push(Parameter {
+            name: variable.name,
+        });
+        initial_values.push(value);
+    }
+
+    let procedure = Expression::Lambda {
+        parameters: Rc::from(parameters),
+        rest_parameter: None,
+        body: Rc::new(create_body(arguments, span)?),
+    };
+    let procedure_name = Identifier {
+        name: Symbol::new(&name),
+    };
+    Ok(Expression::Application {
+        procedure: Box::new(Expression::Let {
+            kind: LetKind::Letrec,
+            bindings: vec![Binding {
+                variable: procedure_name.clone(),
+                value: procedure,
+            }],
+            body: Box::new(Expression::Identifier {
+                identifier: procedure_name,
+                span: name_span,
+            }),
+            span,
+        }),
+        arguments: initial_values,
+        span,
+    })
+}
+
+fn translate_binding(binding: Node) -> Result<Binding, Spanned<LisrParseError>> {
+    let binding_span = binding.span();
+    let Node::List { mut elements, .. } = binding else {
+        return Err(Spanned::new(
+            LisrParseError::BindingRequiresVariableAndValue,
+            binding_span,
+        ));
+    };
+
+    match (
+        elements.pop_front(),
+        elements.pop_front(),
+        elements.is_empty(),
+    ) {
+        (
+            Some(Node::Leaf {
+                token: Token::Identifier { name },
+                ..
+            }),
+            Some(value),
+            true,
+        ) => Ok(Binding {
+            variable: Identifier {
+                name: Symbol::new(&name),
+            },
+            value: translate_node(value)?,
+        }),
+        _ => Err(Spanned::new(
+            LisrParseError::BindingRequiresVariableAndValue,
+            binding_span,
+        )),
+    }
+}
+
+// A body made of many expressions is evaluated like a begin.
+fn create_body(
+    mut expressions: VecDeque<Node>,
+    span: Span,
+) -> Result<Expression, Spanned<LisrParseError>> {
+    if expressions.len() == 1 {
+        if let Some(expression) = expressions.pop_front() {
+            return translate_node(expression);
+        }
+    }
+    create_begin(expressions, span)
+}
+
+fn create_cond(clauses: VecDeque<Node>, span: Span) -> Result<Expression, Spanned<LisrParseError>> {
+    if clauses.is_empty() {
+        return Err(Spanned::new(LisrParseError::CondRequiresClauses, span));
+    }
+
+    let last_clause = clauses.len() - 1;
+    let clauses = clauses
+        .into_iter()
+        .enumerate()
+        .map(|(index, clause)| {
+            let clause_span = clause.span();
+            let malformed_clause =
+                || Spanned::new(LisrParseError::MalformedCondClause, clause_span);
+            let Node::List { mut elements, .. } = clause else {
+                return Err(malformed_clause());
+            };
+            let test = match elements.pop_front() {
+                // Only the last clause can be an else clause and it needs a body.
+                Some(Node::Leaf {
+                    token: Token::Else, ..
+                }) if index == last_clause && !elements.is_empty() => Expression::True,
+                Some(Node::Leaf {
+                    token: Token::Else, ..
+                })
+                | None => return Err(malformed_clause()),
+                Some(test) => translate_node(test)?,
+            };
+            let body = create_clause_body(elements, clause_span, malformed_clause)?;
+            Ok(CondClause { test, body })
+        })
+        .collect::<Result<Vec<CondClause>, Spanned<LisrParseError>>>()?;
+
+    Ok(Expression::Cond { clauses, span })
+}
+
+fn create_case(
+    mut arguments: VecDeque<Node>,
+    span: Span,
+) -> Result<Expression, Spanned<LisrParseError>> {
+    let (Some(key), false) = (arguments.pop_front(), arguments.is_empty()) else {
+        return Err(Spanned::new(
+            LisrParseError::CaseRequiresKeyAndClauses,
+            span,
+        ));
+    };
+    let key = translate_node(key)?;
+
+    let mut clauses = Vec::new();
+    let mut else_clause = None;
+    while let Some(clause) = arguments.pop_front() {
+        let clause_span = clause.span();
+        let malformed_clause = || Spanned::new(LisrParseError::MalformedCaseClause, clause_span);
+        let Node::List { mut elements, .. } = clause else {
+            return Err(malformed_clause());
+        };
+        let data = elements.pop_front();
+        // Case clauses always have a body, the value of a key is not interesting.
+        if elements.is_empty() {
+            return Err(malformed_clause());
+        }
+        let body = create_clause_body(elements, clause_span, malformed_clause)?;
+        match data {
+            // Only the last clause can be an else clause.
+            Some(Node::Leaf {
+                token: Token::Else, ..
+            }) if arguments.is_empty() => else_clause = Some(body),
+            Some(Node::List { elements: data, .. }) => clauses.push(CaseClause {
+                data: data
+                    .into_iter()
+                    .map(translate_datum)
+                    .collect::<Result<Vec<Expression>, Spanned<LisrParseError>>>()?,
+                body,
+            }),
+            _ => return Err(malformed_clause()),
+        }
+    }
+
+    Ok(Expression::Case {
+        key: Box::new(key),
+        clauses,
+        else_clause,
+        span,
+    })
+}
+
+// Translates what follows the test of a clause. A `=>` has to be followed by
+// exactly one receiver.
+fn create_clause_body(
+    mut elements: VecDeque<Node>,
+    span: Span,
+    malformed_clause: impl Fn() -> Spanned<LisrParseError>,
+) -> Result<ClauseBody, Spanned<LisrParseError>> {
+    match elements.front() {
+        None => Ok(ClauseBody::TestValue),
+        Some(Node::Leaf {
+            token: Token::Arrow,
+            ..
+        }) => match (
+            elements.pop_front(),
+            elements.pop_front(),
+            elements.is_empty(),
+        ) {
+            (_, Some(receiver), true) => {
+                Ok(ClauseBody::Receiver(Box::new(translate_node(receiver)?)))
+            }
+            _ => Err(malformed_clause()),
+        },
+        Some(_) => Ok(ClauseBody::Sequence(Box::new(create_body(elements, span)?))),
+    }
+}
+
+// `(when test body...)` becomes `(if test (begin body...) <unspecified>)`.
+fn create_when(
+    mut arguments: VecDeque<Node>,
+    span: Span,
+) -> Result<Expression, Spanned<LisrParseError>> {
+    let (Some(test), false) = (arguments.pop_front(), arguments.is_empty()) else {
+        return Err(Spanned::new(LisrParseError::WhenRequiresTestAndBody, span));
+    };
+    Ok(Expression::If {
+        predicate: Box::new(translate_node(test)?),
+        consequent: Box::new(create_body(arguments, span)?),
+        alternative: Box::new(Expression::Unspecified),
+        span,
+    })
+}
+
+// `(unless test body...)` becomes `(if test <unspecified> (begin body...))`.
+fn create_unless(
+    mut arguments: VecDeque<Node>,
+    span: Span,
+) -> Result<Expression, Spanned<LisrParseError>> {
+    let (Some(test), false) = (arguments.pop_front(), arguments.is_empty()) else {
+        return Err(Spanned::new(
+            LisrParseError::UnlessRequiresTestAndBody,
+            span,
+        ));
+    };
+    Ok(Expression::If {
+        predicate: Box::new(translate_node(test)?),
+        consequent: Box::new(Expression::Unspecified),
+        alternative: Box::new(create_body(arguments, span)?),
+        span,
+    })
+}
+
+fn create_quotation(
+    mut arguments: VecDeque<Node>,
+    span: Span,
+) -> Result<Expression, Spanned<LisrParseError>> {
+    match (arguments.pop_front(), arguments.is_empty()) {
+        (Some(datum), true) => Ok(Expression::Quote {
+            datum: Box::new(translate_datum(datum)?),
+        }),
+        _ => Err(Spanned::new(LisrParseError::QuoteRequiresOneDatum, span)),
+    }
+}
+
+// Turns quoted code into data: lists become conses and identifiers (keywords
+// too) become symbols. The element after a dot, like `b` in `(a . b)`, becomes
+// the rest of the last pair.
+pub fn translate_datum(node: Node) -> Result<Expression, Spanned<LisrParseError>> {
+    match node {
+        Node::Leaf { token, span } => match token {
+            Token::String { value } => Ok(Expression::String { value }),
+            Token::Number { value } => Ok(Expression::Number { value }),
+            Token::Character { value } => Ok(Expression::Character { value }),
+            Token::True => Ok(Expression::True),
+            Token::False => Ok(Expression::False),
+            Token::Identifier { name } => Ok(Expression::Symbol {
+                value: Symbol::new(&name),
+            }),
+            Token::Dot => Err(Spanned::new(LisrParseError::UnexpectedDot, span)),
+            Token::LeftParen
+            | Token::RightParen
+            | Token::Apostrophe
+            | Token::Backquote
+            | Token::Comma
+            | Token::CommaAt => {
+                panic!("Cannot translate parentheses or apostrophes to a datum")
+            }
+            keyword => Ok(Expression::Symbol {
+                value: Symbol::new(keyword.keyword().unwrap_or_default()),
+            }),
+        },
+        Node::List { elements, .. } => {
+            translate_list_datum(elements, translate_datum, translate_datum)
+        }
+    }
+}
+
+// Turns a list into conses, translating the elements with `translate_element`
+// and the element after a dot with `translate_rest`.
+fn translate_list_datum(
+    mut elements: VecDeque<Node>,
+    mut translate_element: impl FnMut(Node) -> Result<Expression, Spanned<LisrParseError>>,
+    translate_rest: impl FnOnce(Node) -> Result<Expression, Spanned<LisrParseError>>,
+) -> Result<Expression, Spanned<LisrParseError>> {
+    let mut last_rest = Expression::EmptyList;
+    let is_dotted = elements.len() >= 3
+        && matches!(
+            elements[elements.len() - 2],
+            Node::Leaf {
+                token: Token::Dot,
+                ..
+            }
+        );
+    if is_dotted {
+        if let Some(last) = elements.pop_back() {
+            last_rest = translate_rest(last)?;
+        }
+        elements.pop_back();
+    }
+
+    elements
+        .into_iter()
+        .rev()
+        .try_fold(last_rest, |rest, element| {
+            Ok(Expression::Cons {
+                first: Box::new(translate_element(element)?),
+                rest: Box::new(rest),
+            })
+        })
+}
+
+fn create_quasiquotation(
+    mut arguments: VecDeque<Node>,
+    span: Span,
+) -> Result<Expression, Spanned<LisrParseError>> {
+    match (arguments.pop_front(), arguments.is_empty()) {
+        (Some(template), true) => Ok(Expression::Quasiquote {
+            template: Box::new(translate_template(template, 0)?),
+        }),
+        _ => Err(Spanned::new(LisrParseError::QuoteRequiresOneDatum, span)),
+    }
+}
+
+// Translates a quasiquoted template like data, except for the unquoted parts,
+// which are translated as expressions. `depth` counts the quasiquotes nested in
+// the template - only unquotes outside of them are evaluated.
+fn translate_template(node: Node, depth: usize) -> Result<Expression, Spanned<LisrParseError>> {
+    let Node::List { elements, span } = node else {
+        return translate_datum(node);
+    };
+
+    match (elements.front(), elements.len()) {
+        (
+            Some(Node::Leaf {
+                token: Token::Unquote,
+                ..
+            }),
+            2,
+        ) if depth == 0 => Ok(Expression::Unquote {
+            expression: Box::new(translate_node(unquoted(elements))?),
+        }),
+        (
+            Some(Node::Leaf {
+                token: Token::UnquoteSplicing,
+                ..
+            }),
+            2,
+        ) if depth == 0 => Err(Spanned::new(
+            LisrParseError::UnquoteSplicingOutsideOfList,
+            span,
+        )),
+        (
+            Some(Node::Leaf {
+                token: Token::Unquote | Token::UnquoteSplicing,
+                ..
+            }),
+            2,
+        ) => translate_list_datum(
+            elements,
+            |element| translate_template(element, depth - 1),
+            |rest| translate_template(rest, depth - 1),
+        ),
+        (
+            Some(Node::Leaf {
+                token: Token::Quasiquote,
+                ..
+            }),
+            2,
+        ) => translate_list_datum(
+            elements,
+            |element| translate_template(element, depth + 1),
+            |rest| translate_template(rest, depth + 1),
+        ),
+        _ => translate_list_datum(
+            elements,
+            |element| match element {
+                Node::List { elements, span }
+                    if depth == 0
+                        && elements.len() == 2
+                        && matches!(
+                            elements.front(),
+                            Some(Node::Leaf {
+                                token: Token::UnquoteSplicing,
+                                ..
+                            })
+                        ) =>
+                {
+                    Ok(Expression::UnquoteSplicing {
+                        expression: Box::new(translate_node(unquoted(elements))?),
+                        span,
+                    })
+                }
+                element => translate_template(element, depth),
+            },
+            |rest| translate_template(rest, depth),
+        ),
+    }
+}
+
+// The expression of `(unquote expression)` or `(unquote-splicing expression)`.
+fn unquoted(mut elements: VecDeque<Node>) -> Node {
+    elements
+        .pop_back()
+        .expect("unquote is followed by an expression")
+}
+
+// Turns data back into code, the opposite of `translate_datum`. Symbols with the
+// name of a keyword become keywords. The nodes are placed at `span`.
+pub fn untranslate_datum(datum: Expression, span: Span) -> Result<Node, Spanned<LisrParseError>> {
+    let leaf = |token| Ok(Node::Leaf { token, span });
+    match datum {
+        Expression::String { value } => leaf(Token::String { value }),
+        Expression::Number { value } => leaf(Token::Number { value }),
+        Expression::Character { value } => leaf(Token::Character { value }),
+        Expression::True => leaf(Token::True),
+        Expression::False => leaf(Token::False),
+        // Identifiers written by hand cannot contain whitespace, and the
+        // expander relies on it to rename the ones introduced by macros.
+        Expression::Symbol { value } if value.name().contains(char::is_whitespace) => {
+            Err(Spanned::new(
+                LisrParseError::ValueIsNotCode {
+                    value: value.to_string(),
+                },
+                span,
+            ))
+        }
+        Expression::Symbol { value } => {
+            leaf(match_keyword_or_identifier(String::from(value.name())))
+        }
+        Expression::EmptyList => Ok(Node::List {
+            elements: VecDeque::new(),
+            span,
+        }),
+        Expression::Cons { first, rest } => {
+            let mut elements = VecDeque::from([untranslate_datum(*first, span)?]);
+            let mut rest = *rest;
+            loop {
+                match rest {
+                    Expression::Cons { first, rest: next } => {
+                        elements.push_back(untranslate_datum(*first, span)?);
+                        rest = *next;
+                    }
+                    Expression::EmptyList => break,
+                    last => {
+                        elements.push_back(Node::Leaf {
+                            token: Token::Dot,
+                            span,
+                        });
+                        elements.push_back(untranslate_datum(last, span)?);
+                        break;
+                    }
+                }
+            }
+            Ok(Node::List { elements, span })
+        }
+        value => Err(Spanned::new(
+            LisrParseError::ValueIsNotCode {
+                value: value.to_string(),
+            },
+            span,
+        )),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        environment::Environment, number::Number, parse::parse, scan::scan, span::Position,
+    };
 
     #[test]
     fn should_create_application() {
@@ -301,22 +1041,33 @@ mod tests {
                     token: Token::Identifier {
                         name: String::from("square"),
                     },
+                    span: Span::default(),
                 },
                 Node::Leaf {
-                    token: Token::Number { value: 10.0 },
+                    token: Token::Number {
+                        value: Number::Integer(10),
+                    },
+                    span: Span::default(),
                 },
             ]),
+            span: Span::default(),
         };
 
         let result = translate(vec![application]).unwrap();
 
         assert_eq!(
-            result.get(0),
+            result.first(),
             Some(&Expression::Application {
-                procedure: Box::new(Expression::Identifier(Identifier {
-                    name: String::from("square")
-                })),
-                arguments: vec![Expression::Number { value: 10.0 }]
+                procedure: Box::new(Expression::Identifier {
+                    identifier: Identifier {
+                        name: Symbol::new("square")
+                    },
+                    span: Span::default()
+                }),
+                arguments: vec![Expression::Number {
+                    value: Number::Integer(10)
+                }],
+                span: Span::default()
             })
         );
     }
@@ -331,30 +1082,48 @@ mod tests {
                             token: Token::Identifier {
                                 name: String::from("make-adder"),
                             },
+                            span: Span::default(),
                         },
                         Node::Leaf {
-                            token: Token::Number { value: 5.0 },
+                            token: Token::Number {
+                                value: Number::Integer(5),
+                            },
+                            span: Span::default(),
                         },
                     ]),
+                    span: Span::default(),
                 },
                 Node::Leaf {
-                    token: Token::Number { value: 42.0 },
+                    token: Token::Number {
+                        value: Number::Integer(42),
+                    },
+                    span: Span::default(),
                 },
             ]),
+            span: Span::default(),
         };
 
         let result = translate(vec![application]).unwrap();
 
         assert_eq!(
-            result.get(0),
+            result.first(),
             Some(&Expression::Application {
                 procedure: Box::new(Expression::Application {
-                    procedure: Box::new(Expression::Identifier(Identifier {
-                        name: String::from("make-adder")
-                    })),
-                    arguments: vec![Expression::Number { value: 5.0 }]
+                    procedure: Box::new(Expression::Identifier {
+                        identifier: Identifier {
+                            name: Symbol::new("make-adder")
+                        },
+                        span: Span::default()
+                    }),
+                    arguments: vec![Expression::Number {
+                        value: Number::Integer(5)
+                    }],
+                    span: Span::default()
                 }),
-                arguments: vec![Expression::Number { value: 42.0 }]
+                arguments: vec![Expression::Number {
+                    value: Number::Integer(42)
+                }],
+                span: Span::default()
             })
         );
     }
@@ -363,27 +1132,38 @@ mod tests {
     fn should_create_assignment() {
         let assignment = Node::List {
             elements: VecDeque::from([
-                Node::Leaf { token: Token::Set },
+                Node::Leaf {
+                    token: Token::Set,
+                    span: Span::default(),
+                },
                 Node::Leaf {
                     token: Token::Identifier {
                         name: String::from("x"),
                     },
+                    span: Span::default(),
                 },
                 Node::Leaf {
-                    token: Token::Number { value: 42.0 },
+                    token: Token::Number {
+                        value: Number::Integer(42),
+                    },
+                    span: Span::default(),
                 },
             ]),
+            span: Span::default(),
         };
 
         let result = translate(vec![assignment]).unwrap();
 
         assert_eq!(
-            result.get(0),
+            result.first(),
             Some(&Expression::Assignment {
                 variable: Identifier {
-                    name: String::from("x")
+                    name: Symbol::new("x")
                 },
-                value: Box::new(Expression::Number { value: 42.0 })
+                value: Box::new(Expression::Number {
+                    value: Number::Integer(42)
+                }),
+                span: Span::default()
             })
         );
     }
@@ -394,27 +1174,36 @@ mod tests {
             elements: VecDeque::from([
                 Node::Leaf {
                     token: Token::Define,
+                    span: Span::default(),
                 },
                 Node::Leaf {
                     token: Token::Identifier {
                         name: String::from("x"),
                     },
+                    span: Span::default(),
                 },
                 Node::Leaf {
-                    token: Token::Number { value: 42.0 },
+                    token: Token::Number {
+                        value: Number::Integer(42),
+                    },
+                    span: Span::default(),
                 },
             ]),
+            span: Span::default(),
         };
 
         let result = translate(vec![definition]).unwrap();
 
         assert_eq!(
-            result.get(0),
+            result.first(),
             Some(&Expression::Definition {
                 variable: Identifier {
-                    name: String::from("x")
+                    name: Symbol::new("x")
                 },
-                value: Box::new(Expression::Number { value: 42.0 })
+                value: Box::new(Expression::Number {
+                    value: Number::Integer(42)
+                }),
+                span: Span::default()
             })
         );
     }
@@ -423,25 +1212,43 @@ mod tests {
     fn should_create_if() {
         let if_statement = Node::List {
             elements: VecDeque::from([
-                Node::Leaf { token: Token::If },
-                Node::Leaf { token: Token::True },
                 Node::Leaf {
-                    token: Token::Number { value: 1.0 },
+                    token: Token::If,
+                    span: Span::default(),
+                },
+                Node::Leaf {
+                    token: Token::True,
+                    span: Span::default(),
+                },
+                Node::Leaf {
+                    token: Token::Number {
+                        value: Number::Integer(1),
+                    },
+                    span: Span::default(),
                 },
                 Node::Leaf {
-                    token: Token::Number { value: 0.0 },
+                    token: Token::Number {
+                        value: Number::Integer(0),
+                    },
+                    span: Span::default(),
                 },
             ]),
+            span: Span::default(),
         };
 
         let result = translate(vec![if_statement]).unwrap();
 
         assert_eq!(
-            result.get(0),
+            result.first(),
             Some(&Expression::If {
                 predicate: Box::new(Expression::True),
-                consequent: Box::new(Expression::Number { value: 1.0 }),
-                alternative: Box::new(Expression::Number { value: 0.0 })
+                consequent: Box::new(Expression::Number {
+                    value: Number::Integer(1)
+                }),
+                alternative: Box::new(Expression::Number {
+                    value: Number::Integer(0)
+                }),
+                span: Span::default()
             })
         );
     }
@@ -450,20 +1257,29 @@ mod tests {
     fn should_create_and() {
         let and = Node::List {
             elements: VecDeque::from([
-                Node::Leaf { token: Token::And },
-                Node::Leaf { token: Token::True },
+                Node::Leaf {
+                    token: Token::And,
+                    span: Span::default(),
+                },
+                Node::Leaf {
+                    token: Token::True,
+                    span: Span::default(),
+                },
                 Node::Leaf {
                     token: Token::False,
+                    span: Span::default(),
                 },
             ]),
+            span: Span::default(),
         };
 
         let result = translate(vec![and]).unwrap();
 
         assert_eq!(
-            result.get(0),
+            result.first(),
             Some(&Expression::And {
-                operands: vec![Expression::True, Expression::False]
+                operands: vec![Expression::True, Expression::False],
+                span: Span::default()
             })
         );
     }
@@ -472,20 +1288,29 @@ mod tests {
     fn should_create_or() {
         let or = Node::List {
             elements: VecDeque::from([
-                Node::Leaf { token: Token::Or },
+                Node::Leaf {
+                    token: Token::Or,
+                    span: Span::default(),
+                },
                 Node::Leaf {
                     token: Token::False,
+                    span: Span::default(),
+                },
+                Node::Leaf {
+                    token: Token::True,
+                    span: Span::default(),
                 },
-                Node::Leaf { token: Token::True },
             ]),
+            span: Span::default(),
         };
 
         let result = translate(vec![or]).unwrap();
 
         assert_eq!(
-            result.get(0),
+            result.first(),
             Some(&Expression::Or {
-                operands: vec![Expression::False, Expression::True]
+                operands: vec![Expression::False, Expression::True],
+                span: Span::default()
             })
         );
     }
@@ -496,6 +1321,7 @@ mod tests {
             elements: VecDeque::from([
                 Node::Leaf {
                     token: Token::Lambda,
+                    span: Span::default(),
                 },
                 Node::List {
                     elements: VecDeque::from([
@@ -503,13 +1329,16 @@ mod tests {
                             token: Token::Identifier {
                                 name: String::from("x"),
                             },
+                            span: Span::default(),
                         },
                         Node::Leaf {
                             token: Token::Identifier {
                                 name: String::from("y"),
                             },
+                            span: Span::default(),
                         },
                     ]),
+                    span: Span::default(),
                 },
                 Node::List {
                     elements: VecDeque::from([
@@ -517,47 +1346,63 @@ mod tests {
                             token: Token::Identifier {
                                 name: String::from("+"),
                             },
+                            span: Span::default(),
                         },
                         Node::Leaf {
                             token: Token::Identifier {
                                 name: String::from("x"),
                             },
+                            span: Span::default(),
                         },
                         Node::Leaf {
                             token: Token::Identifier {
                                 name: String::from("y"),
                             },
+                            span: Span::default(),
                         },
                     ]),
+                    span: Span::default(),
                 },
             ]),
+            span: Span::default(),
         };
 
         let result = translate(vec![lambda]).unwrap();
 
         assert_eq!(
-            result.get(0),
+            result.first(),
             Some(&Expression::Lambda {
-                parameters: vec![
+                parameters: Rc::from([
                     Parameter {
-                        name: String::from("x")
+                        name: Symbol::new("x")
                     },
                     Parameter {
-                        name: String::from("y")
+                        name: Symbol::new("y")
                     }
-                ],
-                body: Box::new(Expression::Application {
-                    procedure: Box::new(Expression::Identifier(Identifier {
-                        name: String::from("+")
-                    })),
+                ]),
+                rest_parameter: None,
+                body: Rc::new(Expression::Application {
+                    procedure: Box::new(Expression::Identifier {
+                        identifier: Identifier {
+                            name: Symbol::new("+")
+                        },
+                        span: Span::default()
+                    }),
                     arguments: vec![
-                        Expression::Identifier(Identifier {
-                            name: String::from("x")
-                        }),
-                        Expression::Identifier(Identifier {
-                            name: String::from("y")
-                        })
-                    ]
+                        Expression::Identifier {
+                            identifier: Identifier {
+                                name: Symbol::new("x")
+                            },
+                            span: Span::default()
+                        },
+                        Expression::Identifier {
+                            identifier: Identifier {
+                                name: Symbol::new("y")
+                            },
+                            span: Span::default()
+                        }
+                    ],
+                    span: Span::default()
                 })
             })
         );
@@ -569,81 +1414,669 @@ mod tests {
             elements: VecDeque::from([
                 Node::Leaf {
                     token: Token::Begin,
+                    span: Span::default(),
                 },
                 Node::Leaf {
-                    token: Token::Number { value: 1.0 },
+                    token: Token::Number {
+                        value: Number::Integer(1),
+                    },
+                    span: Span::default(),
                 },
             ]),
+            span: Span::default(),
         };
 
         let result = translate(vec![begin]).unwrap();
 
         assert_eq!(
-            result.get(0),
+            result.first(),
             Some(&Expression::Begin {
-                sequence: vec![Expression::Number { value: 1.0 }]
+                sequence: vec![Expression::Number {
+                    value: Number::Integer(1)
+                }],
+                span: Span::default()
+            })
+        );
+    }
+
+    #[test]
+    fn should_create_if_without_alternative() {
+        let nodes = parse(scan("(if true 1)").unwrap()).unwrap();
+
+        let result = translate(nodes).unwrap();
+
+        assert_eq!(
+            result.first(),
+            Some(&Expression::If {
+                predicate: Box::new(Expression::True),
+                consequent: Box::new(Expression::Number {
+                    value: Number::Integer(1)
+                }),
+                alternative: Box::new(Expression::Unspecified),
+                span: Span::new(Position::new(1, 1), Position::new(1, 12))
             })
         );
     }
 
+    #[test]
+    fn should_count_the_forms_of_a_malformed_if() {
+        for (input, found) in [("(if)", 0), ("(if true)", 1), ("(if true 1 2 3)", 4)] {
+            let nodes = parse(scan(input).unwrap()).unwrap();
+
+            let error = translate(nodes).unwrap_err().value;
+
+            assert_eq!(
+                error,
+                LisrParseError::IfRequiresPredicateAndConsequent { found }
+            );
+        }
+    }
+
     #[test]
     fn should_create_cons() {
         let cons = Node::List {
             elements: VecDeque::from([
-                Node::Leaf { token: Token::Cons },
                 Node::Leaf {
-                    token: Token::Number { value: 1.0 },
+                    token: Token::Cons,
+                    span: Span::default(),
+                },
+                Node::Leaf {
+                    token: Token::Number {
+                        value: Number::Integer(1),
+                    },
+                    span: Span::default(),
                 },
                 Node::List {
                     elements: VecDeque::new(),
+                    span: Span::default(),
                 },
             ]),
+            span: Span::default(),
         };
 
         let result = translate(vec![cons]).unwrap();
 
         assert_eq!(
-            result.get(0),
+            result.first(),
             Some(&Expression::Cons {
-                first: Box::new(Expression::Number { value: 1.0 }),
+                first: Box::new(Expression::Number {
+                    value: Number::Integer(1)
+                }),
                 rest: Box::new(Expression::EmptyList)
             })
         );
     }
 
     #[test]
-    fn should_return_an_error_for_non_invokable_objects() {
-        // TODO: Parametrize the test for other non-invokable objects.
-        let ast = Node::List {
+    fn should_create_let() {
+        let let_expression = Node::List {
             elements: VecDeque::from([
                 Node::Leaf {
-                    token: Token::String {
-                        value: String::from("add"),
-                    },
+                    token: Token::Let,
+                    span: Span::default(),
                 },
-                Node::Leaf {
-                    token: Token::Number { value: 1.0 },
+                Node::List {
+                    elements: VecDeque::from([Node::List {
+                        elements: VecDeque::from([
+                            Node::Leaf {
+                                token: Token::Identifier {
+                                    name: String::from("x"),
+                                },
+                                span: Span::default(),
+                            },
+                            Node::Leaf {
+                                token: Token::Number {
+                                    value: Number::Integer(1),
+                                },
+                                span: Span::default(),
+                            },
+                        ]),
+                        span: Span::default(),
+                    }]),
+                    span: Span::default(),
                 },
                 Node::Leaf {
-                    token: Token::Number { value: 2.0 },
+                    token: Token::Identifier {
+                        name: String::from("x"),
+                    },
+                    span: Span::default(),
                 },
             ]),
+            span: Span::default(),
         };
 
-        let error = translate(vec![ast]).unwrap_err();
+        let result = translate(vec![let_expression]).unwrap();
 
-        assert_eq!(error, LisrParseError::ObjectNotInvokable);
+        assert_eq!(
+            result.first(),
+            Some(&Expression::Let {
+                kind: LetKind::Let,
+                bindings: vec![Binding {
+                    variable: Identifier {
+                        name: Symbol::new("x")
+                    },
+                    value: Expression::Number {
+                        value: Number::Integer(1)
+                    }
+                }],
+                body: Box::new(Expression::Identifier {
+                    identifier: Identifier {
+                        name: Symbol::new("x")
+                    },
+                    span: Span::default()
+                }),
+                span: Span::default()
+            })
+        );
     }
 
     #[test]
-    fn lambda_should_require_parameters_and_body() {
-        let ast = Node::List {
-            elements: VecDeque::from([Node::Leaf {
-                token: Token::Lambda,
-            }]),
-        };
+    fn should_create_let_variants() {
+        for (input, kind) in [
+            ("(let* () 1)", LetKind::LetStar),
+            ("(letrec () 1)", LetKind::Letrec),
+            ("(letrec* () 1)", LetKind::LetrecStar),
+        ] {
+            let nodes = parse(scan(input).unwrap()).unwrap();
+
+            let result = translate(nodes).unwrap();
+
+            assert!(matches!(
+                result.first(),
+                Some(Expression::Let { kind: actual_kind, bindings, .. })
+                    if *actual_kind == kind && bindings.is_empty()
+            ));
+        }
+    }
+
+    #[test]
+    fn should_create_let_with_a_body_of_many_expressions() {
+        let nodes = parse(scan("(let () 1 2)").unwrap()).unwrap();
+
+        let result = translate(nodes).unwrap();
+
+        assert_eq!(
+            result.first(),
+            Some(&Expression::Let {
+                kind: LetKind::Let,
+                bindings: vec![],
+                body: Box::new(Expression::Begin {
+                    sequence: vec![
+                        Expression::Number {
+                            value: Number::Integer(1)
+                        },
+                        Expression::Number {
+                            value: Number::Integer(2)
+                        }
+                    ],
+                    span: Span::new(Position::new(1, 1), Position::new(1, 13))
+                }),
+                span: Span::new(Position::new(1, 1), Position::new(1, 13))
+            })
+        );
+    }
+
+    #[test]
+    fn should_create_lambda_with_a_body_of_many_expressions() {
+        for input in ["(lambda () 1 2)", "(define (f) 1 2)"] {
+            let nodes = parse(scan(input).unwrap()).unwrap();
+
+            let result = translate(nodes).unwrap();
+
+            let lambda = match result.first() {
+                Some(Expression::Definition { value, .. }) => value.as_ref(),
+                lambda => lambda.unwrap(),
+            };
+            let Expression::Lambda { body, .. } = lambda else {
+                panic!("expected a lambda, got {lambda:?}");
+            };
+            let Expression::Begin { sequence, .. } = body.as_ref() else {
+                panic!("expected a begin, got {body:?}");
+            };
+            assert_eq!(
+                sequence,
+                &vec![
+                    Expression::Number {
+                        value: Number::Integer(1)
+                    },
+                    Expression::Number {
+                        value: Number::Integer(2)
+                    }
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn should_reject_variable_definition_with_many_values() {
+        for input in ["(define x 1 2)", "(define x)", "(define (f))"] {
+            let nodes = parse(scan(input).unwrap()).unwrap();
+
+            let error = translate(nodes).unwrap_err().value;
+
+            assert_eq!(error, LisrParseError::DefinitionRequiresVariableAndBody);
+        }
+    }
+
+    #[test]
+    fn should_create_named_let() {
+        let nodes = parse(scan("(let loop ((i 0)) (loop i))").unwrap()).unwrap();
+
+        let result = translate(nodes).unwrap();
+
+        let span = Span::new(Position::new(1, 1), Position::new(1, 28));
+        let loop_identifier = Identifier {
+            name: Symbol::new("loop"),
+        };
+        assert_eq!(
+            result.first(),
+            Some(&Expression::Application {
+                procedure: Box::new(Expression::Let {
+                    kind: LetKind::Letrec,
+                    bindings: vec![Binding {
+                        variable: loop_identifier.clone(),
+                        value: Expression::Lambda {
+                            parameters: Rc::from([Parameter {
+                                name: Symbol::new("i")
+                            }]),
+                            rest_parameter: None,
+                            body: Rc::new(Expression::Application {
+                                procedure: Box::new(Expression::Identifier {
+                                    identifier: loop_identifier.clone(),
+                                    span: Span::new(Position::new(1, 20), Position::new(1, 24))
+                                }),
+                                arguments: vec![Expression::Identifier {
+                                    identifier: Identifier {
+                                        name: Symbol::new("i")
+                                    },
+                                    span: Span::new(Position::new(1, 25), Position::new(1, 26))
+                                }],
+                                span: Span::new(Position::new(1, 19), Position::new(1, 27))
+                            })
+                        }
+                    }],
+                    body: Box::new(Expression::Identifier {
+                        identifier: loop_identifier,
+                        span: Span::new(Position::new(1, 6), Position::new(1, 10))
+                    }),
+                    span
+                }),
+                arguments: vec![Expression::Number {
+                    value: Number::Integer(0)
+                }],
+                span
+            })
+        );
+    }
+
+    #[test]
+    fn should_require_bindings_and_body_in_named_let() {
+        for input in ["(let loop)", "(let loop ((i 0)))", "(let loop i i)"] {
+            let nodes = parse(scan(input).unwrap()).unwrap();
+
+            let error = translate(nodes).unwrap_err().value;
+
+            assert_eq!(error, LisrParseError::LetRequiresBindingsAndBody);
+        }
+    }
+
+    #[test]
+    fn should_require_bindings_and_body_in_let() {
+        for input in ["(let)", "(let ((x 1)))", "(let* x x)", "(letrec 1 2)"] {
+            let nodes = parse(scan(input).unwrap()).unwrap();
+
+            let error = translate(nodes).unwrap_err().value;
+
+            assert_eq!(error, LisrParseError::LetRequiresBindingsAndBody);
+        }
+    }
+
+    #[test]
+    fn should_report_the_position_of_a_malformed_binding() {
+        for (input, start, end) in [
+            ("(let ((x 1) y) x)", 13, 14),
+            ("(let* ((x 1) (y)) x)", 14, 17),
+            ("(letrec ((x 1 2)) x)", 10, 17),
+            ("(let ((1 x)) x)", 7, 12),
+        ] {
+            let nodes = parse(scan(input).unwrap()).unwrap();
+
+            let error = translate(nodes).unwrap_err();
+
+            assert_eq!(
+                error,
+                Spanned::new(
+                    LisrParseError::BindingRequiresVariableAndValue,
+                    Span::new(Position::new(1, start), Position::new(1, end))
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn should_create_cond() {
+        let nodes = parse(scan("(cond (x 1 2) (y => f) (z) (else 3))").unwrap()).unwrap();
+
+        let result = translate(nodes).unwrap();
+
+        let identifier = |name: &str, start: usize| Expression::Identifier {
+            identifier: Identifier {
+                name: Symbol::new(name),
+            },
+            span: Span::new(Position::new(1, start), Position::new(1, start + 1)),
+        };
+        assert_eq!(
+            result.first(),
+            Some(&Expression::Cond {
+                clauses: vec![
+                    CondClause {
+                        test: identifier("x", 8),
+                        body: ClauseBody::Sequence(Box::new(Expression::Begin {
+                            sequence: vec![
+                                Expression::Number {
+                                    value: Number::Integer(1)
+                                },
+                                Expression::Number {
+                                    value: Number::Integer(2)
+                                }
+                            ],
+                            span: Span::new(Position::new(1, 7), Position::new(1, 14))
+                        }))
+                    },
+                    CondClause {
+                        test: identifier("y", 16),
+                        body: ClauseBody::Receiver(Box::new(identifier("f", 21)))
+                    },
+                    CondClause {
+                        test: identifier("z", 25),
+                        body: ClauseBody::TestValue
+                    },
+                    CondClause {
+                        test: Expression::True,
+                        body: ClauseBody::Sequence(Box::new(Expression::Number {
+                            value: Number::Integer(3)
+                        }))
+                    },
+                ],
+                span: Span::new(Position::new(1, 1), Position::new(1, 37))
+            })
+        );
+    }
+
+    #[test]
+    fn should_report_malformed_cond_clauses() {
+        for input in [
+            "(cond x)",
+            "(cond ())",
+            "(cond (else 1) (x 2))",
+            "(cond (else))",
+            "(cond (x =>))",
+            "(cond (x => f g))",
+        ] {
+            let nodes = parse(scan(input).unwrap()).unwrap();
+
+            let error = translate(nodes).unwrap_err().value;
+
+            assert_eq!(error, LisrParseError::MalformedCondClause, "{}", input);
+        }
+        let nodes = parse(scan("(cond)").unwrap()).unwrap();
+        assert_eq!(
+            translate(nodes).unwrap_err().value,
+            LisrParseError::CondRequiresClauses
+        );
+    }
 
-        let error = translate(vec![ast]).unwrap_err();
+    #[test]
+    fn should_create_case() {
+        let nodes = parse(scan("(case x ((1 \"one\" one) 1) (else => f))").unwrap()).unwrap();
+
+        let result = translate(nodes).unwrap();
+
+        assert_eq!(
+            result.first(),
+            Some(&Expression::Case {
+                key: Box::new(Expression::Identifier {
+                    identifier: Identifier {
+                        name: Symbol::new("x")
+                    },
+                    span: Span::new(Position::new(1, 7), Position::new(1, 8))
+                }),
+                clauses: vec![CaseClause {
+                    data: vec![
+                        Expression::Number {
+                            value: Number::Integer(1)
+                        },
+                        Expression::String {
+                            value: String::from("one")
+                        },
+                        Expression::Symbol {
+                            value: Symbol::new("one")
+                        }
+                    ],
+                    body: ClauseBody::Sequence(Box::new(Expression::Number {
+                        value: Number::Integer(1)
+                    }))
+                }],
+                else_clause: Some(ClauseBody::Receiver(Box::new(Expression::Identifier {
+                    identifier: Identifier {
+                        name: Symbol::new("f")
+                    },
+                    span: Span::new(Position::new(1, 36), Position::new(1, 37))
+                }))),
+                span: Span::new(Position::new(1, 1), Position::new(1, 39))
+            })
+        );
+    }
+
+    #[test]
+    fn should_report_malformed_case_clauses() {
+        for input in [
+            "(case x 1)",
+            "(case x ((1)))",
+            "(case x (1 2))",
+            "(case x (else 1) ((2) 2))",
+            "(case x ((1) =>))",
+        ] {
+            let nodes = parse(scan(input).unwrap()).unwrap();
+
+            let error = translate(nodes).unwrap_err().value;
+
+            assert_eq!(error, LisrParseError::MalformedCaseClause, "{}", input);
+        }
+        for input in ["(case)", "(case x)"] {
+            let nodes = parse(scan(input).unwrap()).unwrap();
+
+            let error = translate(nodes).unwrap_err().value;
+
+            assert_eq!(error, LisrParseError::CaseRequiresKeyAndClauses);
+        }
+    }
+
+    #[test]
+    fn should_create_when_and_unless() {
+        let nodes = parse(scan("(when true 1) (unless true 2)").unwrap()).unwrap();
+
+        let result = translate(nodes).unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                Expression::If {
+                    predicate: Box::new(Expression::True),
+                    consequent: Box::new(Expression::Number {
+                        value: Number::Integer(1)
+                    }),
+                    alternative: Box::new(Expression::Unspecified),
+                    span: Span::new(Position::new(1, 1), Position::new(1, 14))
+                },
+                Expression::If {
+                    predicate: Box::new(Expression::True),
+                    consequent: Box::new(Expression::Unspecified),
+                    alternative: Box::new(Expression::Number {
+                        value: Number::Integer(2)
+                    }),
+                    span: Span::new(Position::new(1, 15), Position::new(1, 30))
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn should_require_test_and_body_in_when_and_unless() {
+        for (input, expected_error) in [
+            ("(when)", LisrParseError::WhenRequiresTestAndBody),
+            ("(when true)", LisrParseError::WhenRequiresTestAndBody),
+            ("(unless)", LisrParseError::UnlessRequiresTestAndBody),
+            ("(unless true)", LisrParseError::UnlessRequiresTestAndBody),
+        ] {
+            let nodes = parse(scan(input).unwrap()).unwrap();
+
+            let error = translate(nodes).unwrap_err().value;
+
+            assert_eq!(error, expected_error);
+        }
+    }
+
+    #[test]
+    fn should_create_quotation() {
+        let quotation = Node::List {
+            elements: VecDeque::from([
+                Node::Leaf {
+                    token: Token::Quote,
+                    span: Span::default(),
+                },
+                Node::List {
+                    elements: VecDeque::from([
+                        Node::Leaf {
+                            token: Token::Identifier {
+                                name: String::from("a"),
+                            },
+                            span: Span::default(),
+                        },
+                        Node::List {
+                            elements: VecDeque::from([Node::Leaf {
+                                token: Token::String {
+                                    value: String::from("b"),
+                                },
+                                span: Span::default(),
+                            }]),
+                            span: Span::default(),
+                        },
+                        Node::Leaf {
+                            token: Token::True,
+                            span: Span::default(),
+                        },
+                    ]),
+                    span: Span::default(),
+                },
+            ]),
+            span: Span::default(),
+        };
+
+        let result = translate(vec![quotation]).unwrap();
+
+        assert_eq!(
+            result.first(),
+            Some(&Expression::Quote {
+                datum: Box::new(Expression::Cons {
+                    first: Box::new(Expression::Symbol {
+                        value: Symbol::new("a")
+                    }),
+                    rest: Box::new(Expression::Cons {
+                        first: Box::new(Expression::Cons {
+                            first: Box::new(Expression::String {
+                                value: String::from("b")
+                            }),
+                            rest: Box::new(Expression::EmptyList)
+                        }),
+                        rest: Box::new(Expression::Cons {
+                            first: Box::new(Expression::True),
+                            rest: Box::new(Expression::EmptyList)
+                        })
+                    })
+                })
+            })
+        );
+    }
+
+    #[test]
+    fn should_quote_keywords_as_symbols() {
+        let nodes = parse(scan("'(define letrec*)").unwrap()).unwrap();
+
+        let result = translate(nodes).unwrap();
+
+        assert_eq!(
+            result.first(),
+            Some(&Expression::Quote {
+                datum: Box::new(Expression::Cons {
+                    first: Box::new(Expression::Symbol {
+                        value: Symbol::new("define")
+                    }),
+                    rest: Box::new(Expression::Cons {
+                        first: Box::new(Expression::Symbol {
+                            value: Symbol::new("letrec*")
+                        }),
+                        rest: Box::new(Expression::EmptyList)
+                    })
+                })
+            })
+        );
+    }
+
+    #[test]
+    fn should_require_exactly_one_datum_in_quotation() {
+        for input in ["(quote)", "(quote 1 2)"] {
+            let nodes = parse(scan(input).unwrap()).unwrap();
+
+            let error = translate(nodes).unwrap_err().value;
+
+            assert_eq!(error, LisrParseError::QuoteRequiresOneDatum);
+        }
+    }
+
+    #[test]
+    fn should_return_an_error_for_non_invokable_objects() {
+        // TODO: Parametrize the test for other non-invokable objects.
+        let ast = Node::List {
+            elements: VecDeque::from([
+                Node::Leaf {
+                    token: Token::String {
+                        value: String::from("add"),
+                    },
+                    span: Span::default(),
+                },
+                Node::Leaf {
+                    token: Token::Number {
+                        value: Number::Integer(1),
+                    },
+                    span: Span::default(),
+                },
+                Node::Leaf {
+                    token: Token::Number {
+                        value: Number::Integer(2),
+                    },
+                    span: Span::default(),
+                },
+            ]),
+            span: Span::default(),
+        };
+
+        let error = translate(vec![ast]).unwrap_err().value;
+
+        assert_eq!(error, LisrParseError::ObjectNotInvokable);
+    }
+
+    #[test]
+    fn lambda_should_require_parameters_and_body() {
+        let ast = Node::List {
+            elements: VecDeque::from([Node::Leaf {
+                token: Token::Lambda,
+                span: Span::default(),
+            }]),
+            span: Span::default(),
+        };
+
+        let error = translate(vec![ast]).unwrap_err().value;
 
         assert_eq!(error, LisrParseError::LambdaRequiresParameterListAndBody);
     }
@@ -652,16 +2085,21 @@ mod tests {
     fn assignment_should_require_a_variable_and_a_value() {
         let ast = Node::List {
             elements: VecDeque::from([
-                Node::Leaf { token: Token::Set },
+                Node::Leaf {
+                    token: Token::Set,
+                    span: Span::default(),
+                },
                 Node::Leaf {
                     token: Token::Identifier {
                         name: String::from("variable"),
                     },
+                    span: Span::default(),
                 },
             ]),
+            span: Span::default(),
         };
 
-        let error = translate(vec![ast]).unwrap_err();
+        let error = translate(vec![ast]).unwrap_err().value;
 
         assert_eq!(
             error,
@@ -675,15 +2113,21 @@ mod tests {
             elements: VecDeque::from([
                 Node::Leaf {
                     token: Token::Define,
+                    span: Span::default(),
+                },
+                Node::Leaf {
+                    token: Token::True,
+                    span: Span::default(),
                 },
-                Node::Leaf { token: Token::True },
                 Node::Leaf {
                     token: Token::False,
+                    span: Span::default(),
                 },
             ]),
+            span: Span::default(),
         };
 
-        let error = translate(vec![ast]).unwrap_err();
+        let error = translate(vec![ast]).unwrap_err().value;
 
         assert_eq!(error, LisrParseError::VariableRequiredInThisContext);
     }
@@ -694,24 +2138,36 @@ mod tests {
             elements: VecDeque::from([
                 Node::Leaf {
                     token: Token::Define,
+                    span: Span::default(),
                 },
                 Node::List {
                     elements: VecDeque::from([
                         Node::Leaf {
-                            token: Token::Number { value: 1.0 },
+                            token: Token::Number {
+                                value: Number::Integer(1),
+                            },
+                            span: Span::default(),
                         },
                         Node::Leaf {
-                            token: Token::Number { value: 2.0 },
+                            token: Token::Number {
+                                value: Number::Integer(2),
+                            },
+                            span: Span::default(),
                         },
                     ]),
+                    span: Span::default(),
                 },
                 Node::Leaf {
-                    token: Token::Number { value: 3.0 },
+                    token: Token::Number {
+                        value: Number::Integer(3),
+                    },
+                    span: Span::default(),
                 },
             ]),
+            span: Span::default(),
         };
 
-        let error = translate(vec![ast]).unwrap_err();
+        let error = translate(vec![ast]).unwrap_err().value;
 
         assert_eq!(error, LisrParseError::VariableRequiredInThisContext);
     }
@@ -720,14 +2176,21 @@ mod tests {
     fn should_require_two_arguments_for_cons() {
         let ast = Node::List {
             elements: VecDeque::from([
-                Node::Leaf { token: Token::Cons },
                 Node::Leaf {
-                    token: Token::Number { value: 1.0 },
+                    token: Token::Cons,
+                    span: Span::default(),
+                },
+                Node::Leaf {
+                    token: Token::Number {
+                        value: Number::Integer(1),
+                    },
+                    span: Span::default(),
                 },
             ]),
+            span: Span::default(),
         };
 
-        let error = translate(vec![ast]).unwrap_err();
+        let error = translate(vec![ast]).unwrap_err().value;
 
         assert_eq!(error, LisrParseError::ConsRequiresTwoArguments);
     }
@@ -738,6 +2201,7 @@ mod tests {
             elements: VecDeque::from([
                 Node::Leaf {
                     token: Token::Define,
+                    span: Span::default(),
                 },
                 Node::List {
                     elements: VecDeque::from([
@@ -745,13 +2209,16 @@ mod tests {
                             token: Token::Identifier {
                                 name: String::from("square"),
                             },
+                            span: Span::default(),
                         },
                         Node::Leaf {
                             token: Token::Identifier {
                                 name: String::from("x"),
                             },
+                            span: Span::default(),
                         },
                     ]),
+                    span: Span::default(),
                 },
                 Node::List {
                     elements: VecDeque::from([
@@ -759,49 +2226,287 @@ mod tests {
                             token: Token::Identifier {
                                 name: String::from("*"),
                             },
+                            span: Span::default(),
                         },
                         Node::Leaf {
                             token: Token::Identifier {
                                 name: String::from("x"),
                             },
+                            span: Span::default(),
                         },
                         Node::Leaf {
                             token: Token::Identifier {
                                 name: String::from("x"),
                             },
+                            span: Span::default(),
                         },
                     ]),
+                    span: Span::default(),
                 },
             ]),
+            span: Span::default(),
         };
 
         let result = translate(vec![ast]).unwrap();
 
         assert_eq!(
-            result.get(0),
+            result.first(),
             Some(&Expression::Definition {
                 variable: Identifier {
-                    name: String::from("square")
+                    name: Symbol::new("square")
                 },
                 value: Box::new(Expression::Lambda {
-                    parameters: vec![Parameter {
-                        name: String::from("x")
-                    }],
-                    body: Box::new(Expression::Application {
-                        procedure: Box::new(Expression::Identifier(Identifier {
-                            name: String::from("*")
-                        })),
+                    parameters: Rc::from([Parameter {
+                        name: Symbol::new("x")
+                    }]),
+                    rest_parameter: None,
+                    body: Rc::new(Expression::Application {
+                        procedure: Box::new(Expression::Identifier {
+                            identifier: Identifier {
+                                name: Symbol::new("*")
+                            },
+                            span: Span::default()
+                        }),
                         arguments: vec![
-                            Expression::Identifier(Identifier {
-                                name: String::from("x")
-                            }),
-                            Expression::Identifier(Identifier {
-                                name: String::from("x")
-                            })
-                        ]
+                            Expression::Identifier {
+                                identifier: Identifier {
+                                    name: Symbol::new("x")
+                                },
+                                span: Span::default()
+                            },
+                            Expression::Identifier {
+                                identifier: Identifier {
+                                    name: Symbol::new("x")
+                                },
+                                span: Span::default()
+                            }
+                        ],
+                        span: Span::default()
+                    })
+                }),
+                span: Span::default()
+            })
+        );
+    }
+
+    #[test]
+    fn should_report_the_position_of_an_invalid_parameter() {
+        let tokens = scan("(define (f x\n           1) x)").unwrap();
+        let nodes = parse(tokens).unwrap();
+
+        let error = translate(nodes).unwrap_err();
+
+        assert_eq!(
+            error,
+            Spanned::new(
+                LisrParseError::UnexpectedExpressionForLambdaParameter,
+                Span::new(Position::new(2, 12), Position::new(2, 13))
+            )
+        );
+    }
+
+    #[test]
+    fn should_create_lambda_with_rest_parameter() {
+        let nodes = parse(scan("(lambda (a b . rest) rest)").unwrap()).unwrap();
+
+        let result = translate(nodes).unwrap();
+
+        assert_eq!(
+            result.first(),
+            Some(&Expression::Lambda {
+                parameters: Rc::from([
+                    Parameter {
+                        name: Symbol::new("a")
+                    },
+                    Parameter {
+                        name: Symbol::new("b")
+                    }
+                ]),
+                rest_parameter: Some(Parameter {
+                    name: Symbol::new("rest")
+                }),
+                body: Rc::new(Expression::Identifier {
+                    identifier: Identifier {
+                        name: Symbol::new("rest")
+                    },
+                    span: Span::new(Position::new(1, 22), Position::new(1, 26))
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn should_create_lambda_with_only_rest_parameter() {
+        let nodes = parse(scan("(lambda args 1)").unwrap()).unwrap();
+
+        let result = translate(nodes).unwrap();
+
+        assert_eq!(
+            result.first(),
+            Some(&Expression::Lambda {
+                parameters: Rc::from([]),
+                rest_parameter: Some(Parameter {
+                    name: Symbol::new("args")
+                }),
+                body: Rc::new(Expression::Number {
+                    value: Number::Integer(1)
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn should_create_procedure_definition_with_rest_parameter() {
+        let nodes = parse(scan("(define (f . args) 1)").unwrap()).unwrap();
+
+        let result = translate(nodes).unwrap();
+
+        assert_eq!(
+            result.first(),
+            Some(&Expression::Definition {
+                variable: Identifier {
+                    name: Symbol::new("f")
+                },
+                value: Box::new(Expression::Lambda {
+                    parameters: Rc::from([]),
+                    rest_parameter: Some(Parameter {
+                        name: Symbol::new("args")
+                    }),
+                    body: Rc::new(Expression::Number {
+                        value: Number::Integer(1)
+                    }),
+                }),
+                span: Span::new(Position::new(1, 1), Position::new(1, 22))
+            })
+        );
+    }
+
+    #[test]
+    fn should_require_exactly_one_parameter_after_a_dot() {
+        for input in [
+            "(lambda (a .) a)",
+            "(lambda (a . b c) a)",
+            "(define (f . ) 1)",
+        ] {
+            let nodes = parse(scan(input).unwrap()).unwrap();
+
+            let error = translate(nodes).unwrap_err().value;
+
+            assert_eq!(error, LisrParseError::UnexpectedDot);
+        }
+    }
+
+    #[test]
+    fn should_quote_dotted_lists() {
+        let nodes = parse(scan("'(1 2 . 3)").unwrap()).unwrap();
+
+        let result = translate(nodes).unwrap();
+
+        assert_eq!(
+            result.first(),
+            Some(&Expression::Quote {
+                datum: Box::new(Expression::Cons {
+                    first: Box::new(Expression::Number {
+                        value: Number::Integer(1)
+                    }),
+                    rest: Box::new(Expression::Cons {
+                        first: Box::new(Expression::Number {
+                            value: Number::Integer(2)
+                        }),
+                        rest: Box::new(Expression::Number {
+                            value: Number::Integer(3)
+                        })
                     })
                 })
             })
         );
     }
+
+    #[test]
+    fn should_not_allow_dots_outside_of_dotted_lists() {
+        for input in ["'(1 . 2 3)", "'(. 2)", "(+ 1 . 2)", "(. 1)"] {
+            let nodes = parse(scan(input).unwrap()).unwrap();
+
+            let error = translate(nodes).unwrap_err().value;
+
+            assert_eq!(error, LisrParseError::UnexpectedDot);
+        }
+    }
+
+    #[test]
+    fn should_turn_data_back_into_code() {
+        let mut nodes =
+            parse(scan("(if (< x 0) \"negative\" (lambda (f . rest) f))").unwrap()).unwrap();
+        let node = nodes.remove(0);
+
+        let datum = translate_datum(node).unwrap();
+        let result = untranslate_datum(datum.clone(), Span::default()).unwrap();
+
+        assert_eq!(translate_datum(result.clone()).unwrap(), datum);
+        assert!(translate(vec![result]).is_ok());
+    }
+
+    #[test]
+    fn should_not_turn_procedures_into_code() {
+        let error = untranslate_datum(
+            Expression::CompoundProcedure {
+                parameters: Rc::from([]),
+                rest_parameter: None,
+                body: Rc::new(Expression::EmptyList),
+                environment: Environment::new(),
+            },
+            Span::default(),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            error.value,
+            LisrParseError::ValueIsNotCode {
+                value: String::from("#<procedure>")
+            }
+        );
+    }
+
+    #[test]
+    fn should_create_quasiquotation() {
+        let nodes = parse(scan("`(1 ,x)").unwrap()).unwrap();
+
+        let result = translate(nodes).unwrap();
+
+        assert!(matches!(
+            result.first(),
+            Some(Expression::Quasiquote { template }) if matches!(
+                template.as_ref(),
+                Expression::Cons { first, rest } if **first == Expression::Number { value: Number::Integer(1) }
+                    && matches!(
+                        rest.as_ref(),
+                        Expression::Cons { first, .. } if matches!(
+                            first.as_ref(),
+                            Expression::Unquote { expression } if matches!(
+                                expression.as_ref(),
+                                Expression::Identifier { identifier, .. } if identifier.name.name() == "x"
+                            )
+                        )
+                    )
+            )
+        ));
+    }
+
+    #[test]
+    fn should_not_allow_unquotes_outside_of_quasiquotations() {
+        let nodes = parse(scan("(list ,x)").unwrap()).unwrap();
+
+        let error = translate(nodes).unwrap_err().value;
+
+        assert_eq!(error, LisrParseError::UnquoteOutsideOfQuasiquote);
+    }
+
+    #[test]
+    fn should_not_allow_unquote_splicing_outside_of_lists() {
+        let nodes = parse(scan("`,@x").unwrap()).unwrap();
+
+        let error = translate(nodes).unwrap_err().value;
+
+        assert_eq!(error, LisrParseError::UnquoteSplicingOutsideOfList);
+    }
 }